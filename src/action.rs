@@ -0,0 +1,97 @@
+//! [Action] impl.
+
+use ::serde::{Deserialize, Serialize};
+
+/// Action of the bottom button row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Save config to a new file.
+    Save,
+    /// Load config from a file.
+    Load,
+    /// Merge config from a file into current edits.
+    Merge,
+    /// Copy config to clipboard.
+    Copy,
+    /// Compare config with one from a file.
+    Compare,
+    /// Edit config file in an external editor.
+    Edit,
+    /// Restore loaded config.
+    Reload,
+    /// Close application.
+    Cancel,
+    /// Run or kill executable.
+    Run,
+    /// Kill executable and run it again.
+    Restart,
+    /// Run executable in the background.
+    Background,
+}
+
+impl Action {
+    /// All actions in default order.
+    pub const ALL: [Action; 11] = [
+        Action::Save,
+        Action::Load,
+        Action::Merge,
+        Action::Copy,
+        Action::Compare,
+        Action::Edit,
+        Action::Reload,
+        Action::Cancel,
+        Action::Run,
+        Action::Restart,
+        Action::Background,
+    ];
+
+    /// Actions shown in the bottom button row by default.
+    pub const DEFAULT: [Action; 6] = [
+        Action::Save,
+        Action::Load,
+        Action::Edit,
+        Action::Reload,
+        Action::Cancel,
+        Action::Run,
+    ];
+
+    /// Label of action.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Action::Save => "Save",
+            Action::Load => "Load",
+            Action::Merge => "Merge",
+            Action::Copy => "Copy",
+            Action::Compare => "Compare",
+            Action::Edit => "Edit",
+            Action::Reload => "Reload",
+            Action::Cancel => "Cancel",
+            Action::Run => "Run",
+            Action::Restart => "Restart",
+            Action::Background => "Background",
+        }
+    }
+
+    /// Help text of action.
+    pub const fn help(self) -> &'static str {
+        match self {
+            Action::Save => "Save config to a new file",
+            Action::Load => "Load config from a file",
+            Action::Merge => "Merge config from a file into current edits, ignoring empty values",
+            Action::Copy => "Copy config to clipboard as TOML",
+            Action::Compare => "Compare config with one from a file",
+            Action::Edit => "Edit config file in an external editor, then load it",
+            Action::Reload => "Discard edits and restore loaded config",
+            Action::Cancel => "Close application",
+            Action::Run => {
+                "Run executable (Ctrl+R), or kill it while running. Ctrl+Shift+R repeats the last run"
+            }
+            Action::Restart => {
+                "Kill running executable and run it again with the same config once it has \
+                exited, or run it if it is not running"
+            }
+            Action::Background => "Run executable in the background without capturing output",
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! [Arg] impl.
+
+use ::serde::{Deserialize, Serialize};
+
+/// Operating system an argument applies to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetOs {
+    /// Argument applies on all platforms.
+    #[default]
+    Any,
+    /// Argument applies on unix platforms.
+    Unix,
+    /// Argument applies on windows.
+    Windows,
+    /// Argument applies on macos.
+    Macos,
+}
+
+impl TargetOs {
+    /// Check if target is [TargetOs::Any].
+    pub const fn is_any(&self) -> bool {
+        matches!(self, TargetOs::Any)
+    }
+
+    /// Check if target matches the platform the application is running on.
+    pub const fn is_current(self) -> bool {
+        match self {
+            TargetOs::Any => true,
+            TargetOs::Unix => cfg!(unix),
+            TargetOs::Windows => cfg!(windows),
+            TargetOs::Macos => cfg!(target_os = "macos"),
+        }
+    }
+}
+
+/// Application argument, which may be limited to a platform, described and
+/// disabled.
+///
+/// Serialized as a plain string when it applies to all platforms, has no
+/// description and is enabled, and as a table
+/// `{ value = "...", os = "...", desc = "...", enabled = false }` otherwise.
+/// Keys other than `value` may be left out of tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ArgRepr", into = "ArgRepr")]
+pub struct Arg {
+    /// Argument value.
+    pub value: String,
+    /// Platform argument applies to.
+    pub os: TargetOs,
+    /// Description of argument, shown in the ui.
+    pub desc: Option<String>,
+    /// Disabled arguments are kept in the config but not passed.
+    pub enabled: bool,
+}
+
+impl Arg {
+    /// Check if argument applies to the platform the application is running on.
+    pub const fn is_current(&self) -> bool {
+        self.os.is_current()
+    }
+
+    /// Check if argument is enabled and applies to the platform the
+    /// application is running on, such that it is passed.
+    pub const fn is_active(&self) -> bool {
+        self.enabled && self.is_current()
+    }
+
+    /// Check if argument applies to all platforms, has no description and is
+    /// enabled, such that it may be edited as text.
+    pub const fn is_plain(&self) -> bool {
+        matches!(self.os, TargetOs::Any) && self.desc.is_none() && self.enabled
+    }
+
+    /// Join values of plain args using shell quoting.
+    pub fn join_plain(args: &[Arg]) -> String {
+        ::shell_words::join(
+            args.iter()
+                .filter(|arg| arg.is_plain())
+                .map(|arg| &arg.value),
+        )
+    }
+}
+
+impl From<String> for Arg {
+    fn from(value: String) -> Self {
+        Self {
+            value,
+            os: TargetOs::Any,
+            desc: None,
+            enabled: true,
+        }
+    }
+}
+
+/// Serialized representation of [Arg].
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ArgRepr {
+    /// Plain argument.
+    Plain(String),
+    /// Argument with a platform, description or enabled state.
+    Tagged {
+        /// Argument value.
+        value: String,
+        /// Platform argument applies to.
+        #[serde(default, skip_serializing_if = "TargetOs::is_any")]
+        os: TargetOs,
+        /// Description of argument.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        desc: Option<String>,
+        /// Whether argument is passed.
+        #[serde(default = "enabled_default", skip_serializing_if = "is_enabled")]
+        enabled: bool,
+    },
+}
+
+/// Arguments are enabled unless stated otherwise.
+const fn enabled_default() -> bool {
+    true
+}
+
+/// Check if enabled, such that it may be left out when serialized.
+const fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+impl From<ArgRepr> for Arg {
+    fn from(value: ArgRepr) -> Self {
+        match value {
+            ArgRepr::Plain(value) => Self::from(value),
+            ArgRepr::Tagged {
+                value,
+                os,
+                desc,
+                enabled,
+            } => Self {
+                value,
+                os,
+                desc,
+                enabled,
+            },
+        }
+    }
+}
+
+impl From<Arg> for ArgRepr {
+    fn from(value: Arg) -> Self {
+        if value.is_plain() {
+            return Self::Plain(value.value);
+        }
+        let Arg {
+            value,
+            os,
+            desc,
+            enabled,
+        } = value;
+        Self::Tagged {
+            value,
+            os,
+            desc,
+            enabled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of argument serialization.
+
+    use super::*;
+
+    /// Arguments as found in configs.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Args {
+        /// Arguments.
+        arg: Vec<Arg>,
+    }
+
+    /// Mixed plain and tagged arguments survive a round trip, plain ones
+    /// as strings.
+    #[test]
+    fn round_trip() {
+        let content = r#"arg = [
+            "--a",
+            { value = "--b", desc = "Described" },
+            { value = "--c", enabled = false },
+            { value = "--d", os = "windows" },
+            { value = "--e" },
+        ]"#;
+        let args: Args = ::toml::from_str(content).unwrap();
+        assert_eq!(
+            args.arg,
+            [
+                Arg::from("--a".to_owned()),
+                Arg {
+                    desc: Some("Described".into()),
+                    ..Arg::from("--b".to_owned())
+                },
+                Arg {
+                    enabled: false,
+                    ..Arg::from("--c".to_owned())
+                },
+                Arg {
+                    os: TargetOs::Windows,
+                    ..Arg::from("--d".to_owned())
+                },
+                Arg::from("--e".to_owned()),
+            ]
+        );
+
+        let value = ::toml::Value::try_from(&args).unwrap();
+        let serialized = value["arg"].as_array().unwrap();
+        assert_eq!(serialized[0].as_str(), Some("--a"));
+        assert_eq!(serialized[1]["desc"].as_str(), Some("Described"));
+        assert_eq!(serialized[2]["enabled"].as_bool(), Some(false));
+        assert_eq!(serialized[3]["os"].as_str(), Some("windows"));
+        assert_eq!(serialized[4].as_str(), Some("--e"));
+        for tagged in &serialized[1..4] {
+            let keys = tagged.as_table().unwrap().len();
+            assert_eq!(keys, 2, "only changed keys are serialized: {tagged}");
+        }
+
+        let reparsed: Args = ::toml::from_str(&::toml::to_string(&args).unwrap()).unwrap();
+        assert_eq!(reparsed, args);
+    }
+}
@@ -0,0 +1,174 @@
+//! Audit log of runs, written as one json object per line.
+
+use ::std::{
+    io::Write,
+    path::PathBuf,
+    process::ExitStatus,
+    time::{Duration, SystemTime},
+};
+
+use ::serde::Serialize;
+use ::tokio::io::AsyncWriteExt;
+
+use crate::{
+    Message,
+    config::{self, Config},
+    env,
+    status::Level,
+    timestamp::Timestamp,
+};
+
+/// Value recorded for environment variables when values are redacted.
+const REDACTED: &str = "<redacted>";
+
+/// Error raised when an audit record cannot be written.
+#[derive(Debug, ::thiserror::Error)]
+pub enum AuditError {
+    /// Record could not be serialized.
+    #[error("could not serialize audit record\n{0}")]
+    Serialize(#[from] ::serde_json::Error),
+
+    /// Audit log could not be written.
+    #[error("could not write audit log {path:?}\n{source}")]
+    Write {
+        /// Path of audit log.
+        path: PathBuf,
+        /// IO error.
+        #[source]
+        source: ::std::io::Error,
+    },
+}
+
+impl From<AuditError> for Message {
+    fn from(value: AuditError) -> Self {
+        Message::SetStatusLevel(
+            match value {
+                AuditError::Serialize(_) => "could not serialize audit record".into(),
+                AuditError::Write { path, source: _ } => {
+                    format!("could not write audit log {path:?}")
+                }
+            },
+            Level::Warning,
+        )
+    }
+}
+
+/// Record of a single run.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    /// Time run started as an RFC 3339 timestamp.
+    pub timestamp: String,
+    /// Command line that was ran.
+    pub command: String,
+    /// Working directory, if set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Environment variables set for the run, values are replaced by a
+    /// placeholder if [Config::audit_redact_env] is set. Variables read
+    /// from files are always redacted.
+    #[serde(
+        serialize_with = "env::serialize",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub env: Vec<(String, String)>,
+    /// Exit code, if the process could be ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Error, if the process could not be ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Seconds from start until run finished.
+    pub duration_secs: f64,
+}
+
+impl AuditRecord {
+    /// Create record of a run of config started at started. Command,
+    /// working directory and environment are recorded as ran, expanded by
+    /// [Config::expand_paths], or as given if they cannot be expanded.
+    pub fn new(config: &Config, started: SystemTime, result: Result<ExitStatus, String>) -> Self {
+        let duration = started.elapsed().unwrap_or(Duration::ZERO);
+        let (exit_code, error) = match result {
+            Ok(status) => (Some(config::exit_code(status)), None),
+            Err(err) => (None, Some(err)),
+        };
+        let expanded = config.expand_paths();
+        let (config, mut env) = match &expanded {
+            // Conditional variables are already applied when expanded.
+            Ok(expanded) => (expanded, expanded.env.clone()),
+            Err(err) => {
+                ::log::warn!("recording unexpanded config in audit log\n{err}");
+                (
+                    config,
+                    env::with_conditions(
+                        &config.env,
+                        &config.env_when,
+                        config.active_profile.as_deref(),
+                    ),
+                )
+            }
+        };
+        if config.audit_redact_env {
+            for (_, value) in &mut env {
+                *value = REDACTED.to_owned();
+            }
+        }
+        env.extend(
+            config
+                .env_from_file
+                .iter()
+                .map(|entry| (entry.name.clone(), REDACTED.to_owned())),
+        );
+        Self {
+            timestamp: Timestamp::from(started).to_string(),
+            command: config.command_line(),
+            cwd: config.cwd.clone(),
+            env,
+            exit_code,
+            error,
+            duration_secs: duration.as_secs_f64(),
+        }
+    }
+
+    /// Serialize record as a line of json, including line terminator.
+    ///
+    /// # Errors
+    /// If the record cannot be serialized.
+    fn to_line(&self) -> Result<Vec<u8>, AuditError> {
+        let mut line = ::serde_json::to_vec(self)?;
+        line.push(b'\n');
+        Ok(line)
+    }
+
+    /// Append record to audit log at path.
+    ///
+    /// # Errors
+    /// If the record cannot be serialized or written.
+    pub async fn append(self, path: PathBuf) -> Result<(), AuditError> {
+        let line = self.to_line()?;
+        let result = async {
+            ::tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .await?
+                .write_all(&line)
+                .await
+        }
+        .await;
+        result.map_err(|source| AuditError::Write { path, source })
+    }
+
+    /// Append record to audit log at path, blocking until written.
+    ///
+    /// # Errors
+    /// If the record cannot be serialized or written.
+    pub fn append_blocking(&self, path: PathBuf) -> Result<(), AuditError> {
+        let line = self.to_line()?;
+        ::std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|source| AuditError::Write { path, source })
+    }
+}
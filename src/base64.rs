@@ -0,0 +1,148 @@
+//! Base64 encoding and decoding, for configs passed inline.
+
+/// Error raised when text is not valid base64.
+#[derive(Debug, Clone, PartialEq, Eq, ::thiserror::Error)]
+pub enum DecodeError {
+    /// Text contains a character outside of the alphabet.
+    #[error("invalid base64 character {character:?} at {index}")]
+    InvalidCharacter {
+        /// Invalid character.
+        character: char,
+        /// Byte index of character.
+        index: usize,
+    },
+
+    /// Text ends in the middle of a byte.
+    #[error("invalid base64 length, {0} trailing characters")]
+    InvalidLength(usize),
+}
+
+/// Standard base64 alphabet, used when encoding.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as padded base64 using the standard alphabet.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut buffer = [0u8; 3];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        let value = u32::from_be_bytes([0, buffer[0], buffer[1], buffer[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(char::from(
+                    ALPHABET[(value >> (18 - 6 * index)) as usize & 63],
+                ));
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Value of base64 digit, both the standard and the url safe alphabet are
+/// accepted.
+const fn digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decode base64 text, padding is optional and whitespace is ignored such
+/// that wrapped text may be decoded.
+///
+/// # Errors
+/// If text contains characters outside of the alphabet, or if it ends in the
+/// middle of a byte.
+pub fn decode(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut count = 0usize;
+    for (index, character) in text
+        .trim_end_matches(['=', ' ', '\t', '\r', '\n'])
+        .char_indices()
+    {
+        if character.is_ascii_whitespace() {
+            continue;
+        }
+        let value = u8::try_from(character)
+            .ok()
+            .and_then(digit)
+            .ok_or(DecodeError::InvalidCharacter { character, index })?;
+        buffer = (buffer << 6) | u32::from(value);
+        count += 1;
+        if count == 4 {
+            bytes.extend_from_slice(&buffer.to_be_bytes()[1..]);
+            buffer = 0;
+            count = 0;
+        }
+    }
+    match count {
+        0 => {}
+        2 => bytes.push((buffer >> 4) as u8),
+        3 => bytes.extend_from_slice(&((buffer >> 2) as u16).to_be_bytes()),
+        count => return Err(DecodeError::InvalidLength(count)),
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of base64 encoding and decoding.
+
+    use super::*;
+
+    /// Padded and unpadded text decodes the same.
+    #[test]
+    fn padding() {
+        assert_eq!(decode("aGk=").unwrap(), b"hi");
+        assert_eq!(decode("aGk").unwrap(), b"hi");
+        assert_eq!(decode("aA==").unwrap(), b"h");
+        assert_eq!(decode("aA").unwrap(), b"h");
+        assert_eq!(decode("aGV5").unwrap(), b"hey");
+        assert_eq!(decode("").unwrap(), b"");
+    }
+
+    /// The url safe alphabet is accepted as well as the standard one.
+    #[test]
+    fn url_safe() {
+        assert_eq!(decode("+/+/").unwrap(), [0xfb, 0xff, 0xbf]);
+        assert_eq!(decode("-_-_").unwrap(), [0xfb, 0xff, 0xbf]);
+    }
+
+    /// Whitespace is ignored, such as line breaks of wrapped text.
+    #[test]
+    fn whitespace() {
+        assert_eq!(decode("aG\nV5\r\n aGk=\n").unwrap(), b"heyhi");
+    }
+
+    /// Invalid characters and lengths are reported.
+    #[test]
+    fn invalid() {
+        assert_eq!(decode("aGV5a"), Err(DecodeError::InvalidLength(1)));
+        assert_eq!(
+            decode("aG*5"),
+            Err(DecodeError::InvalidCharacter {
+                character: '*',
+                index: 2
+            })
+        );
+    }
+
+    /// Encoded bytes decode back to themselves.
+    #[test]
+    fn round_trip() {
+        assert_eq!(encode(b"hi"), "aGk=");
+        assert_eq!(encode(b"h"), "aA==");
+        assert_eq!(encode(b"hey"), "aGV5");
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        for len in 0..bytes.len() {
+            assert_eq!(decode(&encode(&bytes[..len])).unwrap(), &bytes[..len]);
+        }
+    }
+}
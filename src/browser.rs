@@ -0,0 +1,87 @@
+//! Browser of config files in a directory.
+
+use ::std::path::{Path, PathBuf};
+
+use ::rfd::AsyncFileDialog;
+use ::serde::Deserialize;
+
+/// Config file found in a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserEntry {
+    /// Path of config file.
+    pub path: PathBuf,
+    /// Executable of config, [None] if the file could not be read.
+    pub exe: Option<String>,
+}
+
+impl BrowserEntry {
+    /// Name of config file.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Fields of a config read for previews, such that configs do not have to
+/// be fully valid to be listed.
+#[derive(Debug, Deserialize)]
+struct Preview {
+    /// Executable of config.
+    #[serde(default)]
+    exe: String,
+}
+
+/// Read preview of config at path.
+async fn preview(path: &Path) -> Option<String> {
+    let content = ::tokio::fs::read_to_string(path).await.ok()?;
+    match ::toml::from_str::<Preview>(&content) {
+        Ok(preview) => Some(preview.exe),
+        Err(err) => {
+            ::log::warn!("could not read config {path:?}\n{err}");
+            None
+        }
+    }
+}
+
+/// List config files of directory sorted by name, subdirectories are not
+/// searched. Only files with a `.toml` extension are listed, as configs are
+/// read as toml.
+///
+/// # Errors
+/// If directory cannot be read.
+pub async fn scan(dir: PathBuf) -> Result<Vec<BrowserEntry>, String> {
+    let mut read_dir = ::tokio::fs::read_dir(&dir)
+        .await
+        .map_err(|err| format!("could not read directory {dir:?}\n{err}"))?;
+    let mut entries = Vec::new();
+    loop {
+        let entry = match read_dir.next_entry().await {
+            Ok(Some(entry)) => entry,
+            Ok(None) => break,
+            Err(err) => return Err(format!("could not read directory {dir:?}\n{err}")),
+        };
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "toml")
+            || !::tokio::fs::metadata(&path)
+                .await
+                .is_ok_and(|metadata| metadata.is_file())
+        {
+            continue;
+        }
+        let exe = preview(&path).await;
+        entries.push(BrowserEntry { path, exe });
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// Pick directory to browse using a dialog, [None] if nothing was picked.
+pub async fn pick_dir() -> Option<PathBuf> {
+    AsyncFileDialog::new()
+        .set_title("Browse Configs")
+        .pick_folder()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
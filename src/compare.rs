@@ -0,0 +1,252 @@
+//! Comparison of configs.
+
+use ::std::path::PathBuf;
+
+use crate::{arg::Arg, config::Config};
+
+/// Change of a value between two configs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Value is only set in the second config.
+    Added(String),
+    /// Value is only set in the first config.
+    Removed(String),
+    /// Value differs, first and then second.
+    Changed(String, String),
+}
+
+/// Difference of a field between two configs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// Field that differs, such as `exe` or `env HOME`.
+    pub field: String,
+    /// How field differs.
+    pub change: Change,
+}
+
+/// Comparison of current config against one loaded from a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    /// Path config compared against was loaded from.
+    pub path: PathBuf,
+    /// Differences from current config to the loaded one.
+    pub differences: Vec<Difference>,
+}
+
+/// Differences between executable, working directory, shell, wrapper,
+/// arguments and environment of left and right. Arguments are compared
+/// token by token and environment variables by name.
+pub fn diff(left: &Config, right: &Config) -> Vec<Difference> {
+    /// Push difference of field if left and right differ.
+    fn value(
+        differences: &mut Vec<Difference>,
+        field: &str,
+        left: Option<String>,
+        right: Option<String>,
+    ) {
+        let change = match (left, right) {
+            (Some(left), Some(right)) if left != right => Change::Changed(left, right),
+            (Some(left), None) => Change::Removed(left),
+            (None, Some(right)) => Change::Added(right),
+            _ => return,
+        };
+        differences.push(Difference {
+            field: field.to_owned(),
+            change,
+        });
+    }
+
+    let mut differences = Vec::new();
+    value(
+        &mut differences,
+        "exe",
+        Some(left.exe.clone()).filter(|exe| !exe.is_empty()),
+        Some(right.exe.clone()).filter(|exe| !exe.is_empty()),
+    );
+    value(
+        &mut differences,
+        "cwd",
+        left.cwd
+            .as_ref()
+            .map(|cwd| cwd.to_string_lossy().into_owned()),
+        right
+            .cwd
+            .as_ref()
+            .map(|cwd| cwd.to_string_lossy().into_owned()),
+    );
+    value(
+        &mut differences,
+        "shell",
+        Some(left.shell.to_string()),
+        Some(right.shell.to_string()),
+    );
+    value(
+        &mut differences,
+        "wrapper",
+        Some(::shell_words::join(&left.wrapper)).filter(|wrapper| !wrapper.is_empty()),
+        Some(::shell_words::join(&right.wrapper)).filter(|wrapper| !wrapper.is_empty()),
+    );
+
+    differences.extend(diff_args(&left.arg, &right.arg));
+
+    for (name, left_value) in &left.env {
+        let right_value = right
+            .env
+            .iter()
+            .find(|(right_name, _)| right_name == name)
+            .map(|(_, value)| value.clone());
+        value(
+            &mut differences,
+            &format!("env {name}"),
+            Some(left_value.clone()),
+            right_value,
+        );
+    }
+    for (name, right_value) in &right.env {
+        if !left.env.iter().any(|(left_name, _)| left_name == name) {
+            value(
+                &mut differences,
+                &format!("env {name}"),
+                None,
+                Some(right_value.clone()),
+            );
+        }
+    }
+
+    differences
+}
+
+/// Arguments removed from left and added in right, using a longest common
+/// subsequence such that arguments shared by both are not reported.
+fn diff_args(left: &[Arg], right: &[Arg]) -> Vec<Difference> {
+    // lengths[i][j] is the length of the longest common subsequence of
+    // left[i..] and right[j..].
+    let mut lengths = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i][j] = if left[i] == right[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let difference = |index: usize, change: Change| Difference {
+        field: format!("arg {index}"),
+        change,
+    };
+    let mut differences = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            i += 1;
+            j += 1;
+        } else if j < right.len() && (i == left.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            differences.push(difference(j, Change::Added(right[j].value.clone())));
+            j += 1;
+        } else {
+            differences.push(difference(i, Change::Removed(left[i].value.clone())));
+            i += 1;
+        }
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of config comparison.
+
+    use super::*;
+
+    /// Arguments of values.
+    fn args(values: &[&str]) -> Vec<Arg> {
+        values
+            .iter()
+            .map(|value| Arg::from((*value).to_owned()))
+            .collect()
+    }
+
+    /// Argument difference at index.
+    fn arg(index: usize, change: Change) -> Difference {
+        Difference {
+            field: format!("arg {index}"),
+            change,
+        }
+    }
+
+    /// Identical arguments have no differences.
+    #[test]
+    fn identical() {
+        let left = args(&["-a", "-b", "file"]);
+        assert!(diff_args(&left, &left).is_empty());
+        assert!(diff_args(&[], &[]).is_empty());
+    }
+
+    /// Inserted arguments are reported as added at their index in right.
+    #[test]
+    fn insert() {
+        assert_eq!(
+            diff_args(&args(&["-a", "file"]), &args(&["-a", "-v", "file", "out"])),
+            [
+                arg(1, Change::Added("-v".into())),
+                arg(3, Change::Added("out".into())),
+            ]
+        );
+    }
+
+    /// Deleted arguments are reported as removed at their index in left.
+    #[test]
+    fn delete() {
+        assert_eq!(
+            diff_args(&args(&["-a", "-v", "file"]), &args(&["-a", "file"])),
+            [arg(1, Change::Removed("-v".into()))]
+        );
+        assert_eq!(
+            diff_args(&args(&["-a"]), &[]),
+            [arg(0, Change::Removed("-a".into()))]
+        );
+    }
+
+    /// Reordered arguments keep the longest common subsequence, moved ones
+    /// are removed and added.
+    #[test]
+    fn reorder() {
+        assert_eq!(
+            diff_args(&args(&["a", "b", "c"]), &args(&["c", "a", "b"])),
+            [
+                arg(0, Change::Added("c".into())),
+                arg(2, Change::Removed("c".into())),
+            ]
+        );
+    }
+
+    /// Fields and environment variables are compared by name.
+    #[test]
+    fn fields() {
+        let left = Config {
+            exe: "tool".into(),
+            env: vec![("A".into(), "1".into()), ("B".into(), "2".into())],
+            ..Config::default()
+        };
+        let right = Config {
+            exe: "other".into(),
+            env: vec![("B".into(), "3".into()), ("C".into(), "4".into())],
+            ..Config::default()
+        };
+        let field = |field: &str, change| Difference {
+            field: field.to_owned(),
+            change,
+        };
+        assert_eq!(
+            diff(&left, &right),
+            [
+                field("exe", Change::Changed("tool".into(), "other".into())),
+                field("env A", Change::Removed("1".into())),
+                field("env B", Change::Changed("2".into(), "3".into())),
+                field("env C", Change::Added("4".into())),
+            ]
+        );
+        assert!(diff(&left, &left).is_empty());
+    }
+}
@@ -1,12 +1,41 @@
 //! [Config] impl.
 
-use ::std::{path::PathBuf, process::ExitStatus};
+use ::std::{
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    time::{Duration, Instant},
+};
 
-use ::clap::{Args, ValueHint};
+use ::clap::{ArgAction, Args, ValueHint};
+use ::iced::futures::{
+    FutureExt, SinkExt, Stream, StreamExt,
+    channel::mpsc::{self, Sender},
+    future::{BoxFuture, join},
+};
 use ::rfd::AsyncFileDialog;
 use ::serde::{Deserialize, Serialize};
+use ::tokio::sync::watch;
 
-use crate::Message;
+use crate::{
+    Message,
+    arg::Arg,
+    base64,
+    env::{self, ConditionalEnv, FileEnv},
+    expand::{self, ExpandError},
+    highlight::HighlightRule,
+    launch::Launch,
+    output::OutputLine,
+    palette::{self, ColorPalette},
+    post_action::PostAction,
+    process::{self, DEFAULT_KILL_GRACE_SECS, KillSignal, OutputFiles, Shutdown},
+    profile::Profile,
+    quote::Quoting,
+    status::Level,
+    template,
+    timestamp::Timestamp,
+};
 
 ///  Error raised on save failures.
 #[derive(Debug, ::thiserror::Error)]
@@ -31,6 +60,15 @@ pub enum SaveError {
         source: ::std::io::Error,
     },
 
+    /// Path was found not to be writable before writing.
+    #[error("cannot write config to {path:?}, {reason}")]
+    NotWritable {
+        /// Path that cannot be written.
+        path: PathBuf,
+        /// Why path cannot be written.
+        reason: String,
+    },
+
     /// No file was selected.
     #[error("no file selected using dialog")]
     NoneSelected,
@@ -38,11 +76,20 @@ pub enum SaveError {
 
 impl From<SaveError> for Message {
     fn from(value: SaveError) -> Self {
-        Message::SetStatus(match value {
-            SaveError::Serialize { source: _, path: _ } => "could not serialize config".into(),
-            SaveError::Write { path, source: _ } => format!("could not write {path:?}"),
-            SaveError::NoneSelected => "no path entered".into(),
-        })
+        match value {
+            SaveError::Serialize { source: _, path: _ } => {
+                Message::SetStatusLevel("could not serialize config".into(), Level::Error)
+            }
+            SaveError::Write { path, source: _ } => {
+                Message::SetStatusLevel(format!("could not write {path:?}"), Level::Error)
+            }
+            SaveError::NotWritable { path, reason } => {
+                Message::SetStatusLevel(format!("cannot write {path:?}, {reason}"), Level::Error)
+            }
+            SaveError::NoneSelected => {
+                Message::SetStatusLevel("no path entered".into(), Level::Warning)
+            }
+        }
     }
 }
 
@@ -72,15 +119,91 @@ pub enum LoadError {
     /// No file was selected in dialog.
     #[error("no file selected using dialog")]
     NoneSelected,
+
+    /// Remote config could not be fetched.
+    #[cfg(feature = "remote")]
+    #[error("could not fetch {url}\n{source}")]
+    Fetch {
+        /// Url that was fetched.
+        url: String,
+        /// Request error.
+        #[source]
+        source: ::reqwest::Error,
+    },
+
+    /// Server responded to fetch of remote config with an error.
+    #[error("fetching {url} failed with status {status}")]
+    Status {
+        /// Url that was fetched.
+        url: String,
+        /// Http status code.
+        status: u16,
+    },
+
+    /// Remote config was requested, but support was not compiled in.
+    #[error("cannot fetch {0}, remote configs require the 'remote' feature")]
+    RemoteDisabled(String),
+
+    /// Inline config is not valid base64 encoded utf-8.
+    #[error("could not decode inline config\n{0}")]
+    Decode(String),
+
+    /// Inline config could not be deserialized.
+    #[error("could not parse inline config as toml\n{0}")]
+    DeserializeInline(#[source] ::toml::de::Error),
 }
 
 impl From<LoadError> for Message {
     fn from(value: LoadError) -> Self {
-        Message::SetStatus(match value {
-            LoadError::Deserialize { path, source: _ } => format!("could not deserialze {path:?}"),
-            LoadError::Read { path, source: _ } => format!("could not read {path:?}"),
-            LoadError::NoneSelected => "no file selected".into(),
-        })
+        match value {
+            LoadError::Deserialize { path, source: _ } => {
+                Message::SetStatusLevel(format!("could not deserialze {path:?}"), Level::Error)
+            }
+            LoadError::Read { path, source: _ } => {
+                Message::SetStatusLevel(format!("could not read {path:?}"), Level::Error)
+            }
+            LoadError::NoneSelected => {
+                Message::SetStatusLevel("no file selected".into(), Level::Warning)
+            }
+            #[cfg(feature = "remote")]
+            LoadError::Fetch { url, source: _ } => {
+                Message::SetStatusLevel(format!("could not fetch {url}"), Level::Error)
+            }
+            LoadError::Status { url, status } => Message::SetStatusLevel(
+                format!("fetching {url} failed with status {status}"),
+                Level::Error,
+            ),
+            LoadError::RemoteDisabled(url) => Message::SetStatusLevel(
+                format!("cannot fetch {url}, built without remote support"),
+                Level::Error,
+            ),
+            LoadError::Decode(_) => {
+                Message::SetStatusLevel("could not decode inline config".into(), Level::Error)
+            }
+            LoadError::DeserializeInline(_) => {
+                Message::SetStatusLevel("could not parse inline config".into(), Level::Error)
+            }
+        }
+    }
+}
+
+/// Result of a run with captured output, see [Config::run_captured_async].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunResult {
+    /// Exit status of executable.
+    pub status: ExitStatus,
+    /// Captured stdout of executable, if captured.
+    pub stdout: Option<Vec<u8>>,
+    /// Captured stderr of executable, if captured.
+    pub stderr: Option<Vec<u8>>,
+    /// Time from executable being started until it exited.
+    pub duration: Duration,
+}
+
+impl RunResult {
+    /// Exit code of executable, see [exit_code].
+    pub fn exit_code(&self) -> i32 {
+        exit_code(self.status)
     }
 }
 
@@ -92,9 +215,255 @@ pub struct Config {
     #[arg(long, short, default_value_t, value_hint = ValueHint::FilePath)]
     #[serde(skip_serializing_if = "String::is_empty")]
     pub exe: String,
-    /// Application arguments.
+    /// Application arguments, arguments limited to other platforms are
+    /// skipped when running. May be repeated, values starting with `-` are
+    /// accepted. `{{now}}` and `{{now:FORMAT}}` are replaced by the time of
+    /// the run in UTC, see [expand::now].
+    #[arg(
+        long = "arg",
+        short = 'a',
+        num_args = 1,
+        allow_hyphen_values = true,
+        action = ArgAction::Append
+    )]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub arg: Vec<Arg>,
+    /// Name executable is invoked as, passed as its first argument instead
+    /// of the executable path. Used by multi-call binaries. Only supported
+    /// on unix, and ignored when running through a shell or wrapper.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arg0: Option<String>,
+    /// How executable is launched, when flatpak or snap the executable is an
+    /// app id or snap name launched using `flatpak run` or `snap run`.
+    #[arg(long, value_enum, default_value_t)]
+    #[serde(skip_serializing_if = "Launch::is_direct")]
+    pub launch: Launch,
+    /// Command the executable is launched through, such as `gamemoderun` or
+    /// `strace -f`, each value is a separate word. Runs directly if empty.
+    #[arg(long, num_args = 1, allow_hyphen_values = true, action = ArgAction::Append)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub arg: Vec<String>,
+    pub wrapper: Vec<String>,
+    /// Debugger executable is ran under, such as `gdb` or `lldb`. Arguments
+    /// are passed as the debugger expects, see [debugger_args]. Debuggers
+    /// are interactive, so stdio is inherited from the ui rather than
+    /// captured. Only supported when [Config::launch] is direct, as the
+    /// launcher and not the app would be debugged.
+    #[arg(long, value_hint = ValueHint::CommandName)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debugger: Option<String>,
+    /// Run executable and arguments as a command line using the system shell,
+    /// arguments are appended separated by spaces, quoted according to
+    /// [Config::quoting] if set and as is otherwise.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub shell: bool,
+    /// Shell used instead of the system shell when running through a shell,
+    /// such as `bash` or `pwsh`.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_program: Option<String>,
+    /// Arguments passed to shell before the command line, `-c` if empty and
+    /// a shell program is set, otherwise those of the system shell.
+    #[arg(
+        long = "shell-arg",
+        num_args = 1,
+        allow_hyphen_values = true,
+        action = ArgAction::Append
+    )]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shell_args: Vec<String>,
+    /// Quoting rules used for the command line when running through the
+    /// system shell and when displaying it, POSIX rules are used for display
+    /// if not set.
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quoting: Option<Quoting>,
+    /// Working directory of executable.
+    #[arg(long, value_hint = ValueHint::DirPath)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Create working directory and its parents before running if it does
+    /// not exist.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub create_cwd: bool,
+    /// Environment variables set for executable as `NAME=VALUE`, values may
+    /// reference other variables as `$NAME`, `${NAME}` or `%NAME%`, and
+    /// `$$` is a literal `$`.
+    #[arg(long = "env", value_parser = env::parse)]
+    #[serde(with = "crate::env", skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<(String, String)>,
+    /// Variables removed from the environment inherited by the executable,
+    /// such as `LD_PRELOAD`. Variables set by [Config::env] are still set.
+    #[arg(long = "env-unset", value_name = "NAME")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env_unset: Vec<String>,
+    /// Directories `PATH` is set to for executable, replacing the inherited
+    /// and configured `PATH`, for reproducible launches. The executable,
+    /// shell and launcher are searched for in these directories as well.
+    #[arg(long = "path-entry", value_name = "DIR", value_hint = ValueHint::DirPath)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Vec<PathBuf>>,
+    /// Locale set for executable as both `LC_ALL` and `LANG`, such as `C`
+    /// for deterministic output. Variables of [Config::env] take precedence.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Run executable with stdin closed, such that it reads no input instead
+    /// of inheriting stdin. Runs from the ui read no input unless input is
+    /// made interactive there.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub stdin_null: bool,
+    /// Trim whitespace of arguments and drop those left empty, such as ones
+    /// produced by stray blank lines. Off by default such that intentionally
+    /// empty arguments are kept.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub trim_args: bool,
+    /// Treat leading `NAME=VALUE` arguments as environment variables, as a
+    /// shell would, up to the first other argument or a `--`, if no
+    /// executable is set. The first remaining argument is then used as the
+    /// executable.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub inline_env: bool,
+    /// Fail instead of keeping references to unset environment variables in
+    /// executable and working directory, or `{{now}}` placeholders with
+    /// invalid formats in arguments, as is.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub strict_expand: bool,
+    /// Expand arguments containing glob patterns to matching paths relative
+    /// to working directory. Ignored when running through the system shell,
+    /// which expands patterns itself.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub glob: bool,
+    /// Fail instead of keeping glob patterns that match nothing as is.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub strict_glob: bool,
+    /// Exit codes treated as success, if none are given only 0 is, such that
+    /// the default remains `[0]`. When 0 is not among them and the
+    /// executable exits with it, running with `--skip` exits with 1.
+    #[arg(
+        long = "success-code",
+        value_delimiter = ',',
+        allow_negative_numbers = true
+    )]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub success_codes: Vec<i32>,
+    /// Signal sent to ask process to exit when killed, before it is killed
+    /// forcefully. On windows the process is asked to close regardless of
+    /// which signal is chosen.
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kill_signal: Option<KillSignal>,
+    /// Seconds to wait for process to exit after kill signal is sent, before
+    /// it is killed forcefully.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kill_grace_secs: Option<u64>,
+    /// File mode creation mask of process, given as octal on the command
+    /// line, for instance `022`. Ignored on windows.
+    #[arg(long, value_parser = parse_umask)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub umask: Option<u32>,
+    /// File each run is recorded to as a line of json, including command,
+    /// environment, exit code and duration.
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_log: Option<PathBuf>,
+    /// Record only names of environment variables to the audit log, such
+    /// that secret values are not written to it.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub audit_redact_env: bool,
+    /// File both stdout and stderr of process are written to, in addition to
+    /// being shown. May contain the placeholders `{timestamp}`, `{exe}` and
+    /// `{n}`, the run counter, which are resolved when ran.
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_log: Option<PathBuf>,
+    /// File stdout of process is written to, placeholders are resolved as for
+    /// `--output-log`.
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stdout_file: Option<PathBuf>,
+    /// File stderr of process is written to, placeholders are resolved as for
+    /// `--output-log`.
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr_file: Option<PathBuf>,
+    /// Report CPU time and peak memory of process once it exits, not
+    /// including hooks or other processes. On windows CPU time of runs from
+    /// the ui includes processes started by it. Only supported on unix and
+    /// windows, on other platforms nothing is reported and runs from the ui
+    /// show a warning.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub resource_usage: bool,
+    /// Ask for confirmation before each run from the ui, for executables
+    /// that should not be ran by accident.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub confirm_before_run: bool,
+    /// Accent color of ui as red, green and blue, given as hex on the
+    /// command line, for instance `#3c8dbc`.
+    #[arg(long, value_parser = palette::parse_hex)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accent: Option<[u8; 3]>,
+    /// Named colors of ui replacing the ones of the selected theme, applied
+    /// before [Config::accent].
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "ColorPalette::is_empty")]
+    pub palette: ColorPalette,
+    /// Rules highlighting lines of output in the ui, the first matching rule
+    /// decides the color of a line. If empty, lines containing common error
+    /// and warning words are highlighted.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlight: Vec<HighlightRule>,
+    /// Named profiles, which may replace values of config when selected.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profile: BTreeMap<String, Profile>,
+    /// Environment variables only set on a platform and/or under a profile.
+    /// Applied in order after [Config::env] and the env of the active
+    /// profile, replacing variables with the same name.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env_when: Vec<ConditionalEnv>,
+    /// Environment variables with values read from files just before each
+    /// run, for secrets that should not be stored in the config. Set after
+    /// all other variables.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env_from_file: Vec<FileEnv>,
+    /// Name of profile applied by [Config::with_profile], deciding which of
+    /// [Config::env_when] apply.
+    #[arg(skip)]
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+    /// Config ran before executable, if it fails the executable is not ran.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_run: Option<Box<Config>>,
+    /// Config ran after executable, regardless of how it exited.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_run: Option<Box<Config>>,
+    /// Actions taken in order once executable has exited successfully.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_success: Vec<PostAction>,
+    /// Actions taken in order once executable has failed or could not be
+    /// ran.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<PostAction>,
 }
 
 impl Config {
@@ -102,8 +471,13 @@ impl Config {
     ///
     /// # Errors
     /// If config cannot be serialized [SaveError::Serialize] is returned.
+    /// If path is found not to be writable before writing
+    /// [SaveError::NotWritable] is returned.
     /// If serialized config cannot be written [SaveError::Write] is returned.
     pub async fn save(self, path: PathBuf) -> Result<PathBuf, SaveError> {
+        if let Err(reason) = check_writable(&path).await {
+            return Err(SaveError::NotWritable { path, reason });
+        }
         match ::toml::to_string_pretty(&self) {
             Ok(content) => match ::tokio::fs::write(&path, &content).await {
                 Ok(_) => Ok(path),
@@ -113,18 +487,39 @@ impl Config {
         }
     }
 
-    /// Load config.
+    /// Load config from base64 encoded toml, as passed inline on the command
+    /// line.
+    ///
+    /// # Errors
+    /// If text is not base64 encoded utf-8 [LoadError::Decode] is returned.
+    /// If config cannot be deserialized [LoadError::DeserializeInline] is
+    /// returned.
+    pub fn from_inline(text: &str) -> Result<Config, LoadError> {
+        let bytes = base64::decode(text).map_err(|err| LoadError::Decode(err.to_string()))?;
+        let content = String::from_utf8(bytes).map_err(|err| LoadError::Decode(err.to_string()))?;
+        ::toml::from_str(&content).map_err(LoadError::DeserializeInline)
+    }
+
+    /// Load config from a file, or from an http url if built with the
+    /// `remote` feature.
     ///
     /// # Errors
     /// If config serialized config cannot be read [LoadError::Read] is returned.
     /// If config cannot be deserialized [LoadError::Deserialize] is returned.
+    /// If path is a url that cannot be fetched [LoadError::Status],
+    /// [LoadError::RemoteDisabled] or a fetch error is returned.
     pub async fn load(path: PathBuf) -> Result<(Config, PathBuf), LoadError> {
-        match ::tokio::fs::read_to_string(&path).await {
-            Ok(content) => match ::toml::from_str(&content) {
-                Ok(config) => Ok((config, path)),
-                Err(source) => Err(LoadError::Deserialize { path, source }),
-            },
-            Err(source) => Err(LoadError::Read { path, source }),
+        let content = if is_url(&path) {
+            fetch(path.to_string_lossy().into_owned()).await?
+        } else {
+            match ::tokio::fs::read_to_string(&path).await {
+                Ok(content) => content,
+                Err(source) => return Err(LoadError::Read { path, source }),
+            }
+        };
+        match ::toml::from_str(&content) {
+            Ok(config) => Ok((config, path)),
+            Err(source) => Err(LoadError::Deserialize { path, source }),
         }
     }
 
@@ -160,21 +555,1335 @@ impl Config {
         }
     }
 
-    /// Run this config in an async context.
+    /// Config loaded on top of base, such as the values given on the
+    /// command line, see [Config::merge]. Values of configs loaded earlier
+    /// are not kept, as they are only ever merged into a copy of base.
+    pub fn load_onto(base: &Self, loaded: Self) -> Self {
+        let mut config = base.clone();
+        config.merge(loaded);
+        config
+    }
+
+    /// Merge other into config, values set in other replace those of
+    /// config while empty values are ignored and flags are only ever set.
+    pub fn merge(&mut self, other: Config) {
+        let Self {
+            exe,
+            arg,
+            arg0,
+            launch,
+            wrapper,
+            debugger,
+            shell,
+            shell_program,
+            shell_args,
+            quoting,
+            cwd,
+            create_cwd,
+            env,
+            env_unset,
+            path,
+            locale,
+            stdin_null,
+            trim_args,
+            inline_env,
+            strict_expand,
+            glob,
+            strict_glob,
+            success_codes,
+            kill_signal,
+            kill_grace_secs,
+            umask,
+            audit_log,
+            audit_redact_env,
+            output_log,
+            stdout_file,
+            stderr_file,
+            resource_usage,
+            confirm_before_run,
+            accent,
+            palette,
+            highlight,
+            profile,
+            env_when,
+            env_from_file,
+            active_profile,
+            pre_run,
+            post_run,
+            on_success,
+            on_failure,
+        } = other;
+
+        if !exe.is_empty() {
+            self.exe = exe;
+        }
+
+        if !arg.is_empty() {
+            self.arg = arg;
+        }
+
+        if arg0.is_some() {
+            self.arg0 = arg0;
+        }
+
+        if !launch.is_direct() {
+            self.launch = launch;
+        }
+
+        if !wrapper.is_empty() {
+            self.wrapper = wrapper;
+        }
+
+        if debugger.is_some() {
+            self.debugger = debugger;
+        }
+
+        self.shell |= shell;
+
+        if shell_program.is_some() {
+            self.shell_program = shell_program;
+        }
+
+        if !shell_args.is_empty() {
+            self.shell_args = shell_args;
+        }
+
+        if quoting.is_some() {
+            self.quoting = quoting;
+        }
+
+        if cwd.is_some() {
+            self.cwd = cwd;
+        }
+
+        self.create_cwd |= create_cwd;
+
+        if !env.is_empty() {
+            self.env = env;
+        }
+
+        if !env_unset.is_empty() {
+            self.env_unset = env_unset;
+        }
+
+        if path.is_some() {
+            self.path = path;
+        }
+
+        if locale.is_some() {
+            self.locale = locale;
+        }
+
+        self.stdin_null |= stdin_null;
+
+        self.trim_args |= trim_args;
+
+        self.inline_env |= inline_env;
+
+        self.strict_expand |= strict_expand;
+
+        self.glob |= glob;
+
+        self.strict_glob |= strict_glob;
+
+        if !success_codes.is_empty() {
+            self.success_codes = success_codes;
+        }
+
+        if kill_signal.is_some() {
+            self.kill_signal = kill_signal;
+        }
+
+        if kill_grace_secs.is_some() {
+            self.kill_grace_secs = kill_grace_secs;
+        }
+
+        if umask.is_some() {
+            self.umask = umask;
+        }
+
+        if audit_log.is_some() {
+            self.audit_log = audit_log;
+        }
+
+        self.audit_redact_env |= audit_redact_env;
+
+        if output_log.is_some() {
+            self.output_log = output_log;
+        }
+
+        if stdout_file.is_some() {
+            self.stdout_file = stdout_file;
+        }
+
+        if stderr_file.is_some() {
+            self.stderr_file = stderr_file;
+        }
+
+        self.resource_usage |= resource_usage;
+
+        self.confirm_before_run |= confirm_before_run;
+
+        if accent.is_some() {
+            self.accent = accent;
+        }
+
+        self.palette.merge(palette);
+
+        if !highlight.is_empty() {
+            self.highlight = highlight;
+        }
+
+        if !profile.is_empty() {
+            self.profile = profile;
+        }
+
+        if !env_when.is_empty() {
+            self.env_when = env_when;
+        }
+
+        if !env_from_file.is_empty() {
+            self.env_from_file = env_from_file;
+        }
+
+        if active_profile.is_some() {
+            self.active_profile = active_profile;
+        }
+
+        if pre_run.is_some() {
+            self.pre_run = pre_run;
+        }
+
+        if post_run.is_some() {
+            self.post_run = post_run;
+        }
+
+        if !on_success.is_empty() {
+            self.on_success = on_success;
+        }
+
+        if !on_failure.is_empty() {
+            self.on_failure = on_failure;
+        }
+    }
+
+    /// Build the command described by this config, hooks are not included.
+    pub fn build_command(&self) -> ::std::process::Command {
+        let Self {
+            exe,
+            arg,
+            arg0,
+            launch,
+            wrapper,
+            debugger,
+            shell,
+            shell_program,
+            shell_args,
+            quoting,
+            cwd,
+            create_cwd: _,
+            env,
+            env_unset,
+            path,
+            locale,
+            stdin_null,
+            trim_args: _,
+            inline_env: _,
+            strict_expand: _,
+            glob: _,
+            strict_glob: _,
+            success_codes: _,
+            kill_signal: _,
+            kill_grace_secs: _,
+            umask,
+            audit_log: _,
+            audit_redact_env: _,
+            output_log: _,
+            stdout_file: _,
+            stderr_file: _,
+            resource_usage: _,
+            confirm_before_run: _,
+            accent: _,
+            palette: _,
+            highlight: _,
+            profile: _,
+            env_when: _,
+            env_from_file: _,
+            active_profile: _,
+            pre_run: _,
+            post_run: _,
+            on_success: _,
+            on_failure: _,
+        } = self;
+        let args = arg
+            .iter()
+            .filter(|arg| arg.is_active())
+            .map(|arg| arg.value.as_str());
+        let debugger_words = debugger.as_deref().map(|debugger| {
+            ::std::iter::once(debugger).chain(debugger_args(debugger).iter().copied())
+        });
+        let mut words = wrapper
+            .iter()
+            .map(String::as_str)
+            .chain(launch.prefix().iter().copied())
+            .chain(debugger_words.into_iter().flatten())
+            .chain(::std::iter::once(exe.as_str()))
+            .chain(args);
+
+        #[cfg(not(unix))]
+        let _ = arg0;
+
+        let mut command = if *shell {
+            let line = match quoting {
+                Some(quoting) => quoting.join(words),
+                None => words.collect::<Vec<_>>().join(" "),
+            };
+            shell_command(shell_program.as_deref(), shell_args, &line)
+        } else {
+            let mut command = ::std::process::Command::new(words.next().unwrap_or_default());
+            command.args(words);
+            #[cfg(unix)]
+            if let Some(arg0) = arg0
+                && wrapper.is_empty()
+                && launch.is_direct()
+                && debugger.is_none()
+            {
+                use ::std::os::unix::process::CommandExt;
+
+                command.arg0(arg0);
+            }
+            command
+        };
+
+        if let Some(cwd) = cwd {
+            command.current_dir(cwd);
+        }
+
+        for name in env_unset {
+            command.env_remove(name);
+        }
+
+        if let Some(locale) = locale {
+            command.env("LC_ALL", locale).env("LANG", locale);
+        }
+
+        command.envs(env.iter().map(|(name, value)| (name, value)));
+
+        if path.is_some() {
+            match self.search_path() {
+                Some(path) => {
+                    command.env("PATH", path);
+                }
+                None => ::log::warn!("PATH entries cannot be joined, keeping PATH as is"),
+            }
+        }
+
+        if *stdin_null && debugger.is_none() {
+            command.stdin(Stdio::null());
+        }
+
+        #[cfg(unix)]
+        if let Some(umask) = *umask {
+            use ::nix::sys::stat::{Mode, umask as set_umask};
+            use ::std::os::unix::process::CommandExt;
+
+            let mode = Mode::from_bits_truncate((umask & 0o777) as _);
+            // SAFETY: umask is async-signal-safe and nothing is allocated.
+            unsafe {
+                command.pre_exec(move || {
+                    set_umask(mode);
+                    Ok(())
+                });
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = umask;
+
+        command
+    }
+
+    /// Config with profile of name applied, if name is [None] or no such
+    /// profile exists the config is returned as is.
+    pub fn with_profile(&self, name: Option<&str>) -> Self {
+        let mut config = self.clone();
+        if let Some(name) = name
+            && let Some(profile) = self.profile.get(name)
+        {
+            profile.apply(&mut config);
+            config.active_profile = Some(name.to_owned());
+        }
+        config
+    }
+
+    /// Config with variables of [Config::env_when] active under
+    /// [Config::active_profile] applied to [Config::env], such that its
+    /// environment is kept when serialized, which the active profile is not.
+    pub fn resolve_conditions(mut self) -> Self {
+        self.env = env::with_conditions(&self.env, &self.env_when, self.active_profile.as_deref());
+        self.env_when.clear();
+        self
+    }
+
+    /// Config with argument options applied, see
+    /// [Config::apply_arg_options], environment variables and `~` expanded
+    /// in executable and working directory, active variables of
+    /// [Config::env_when] applied and references resolved in [Config::env],
+    /// `{{now}}` placeholders in arguments replaced by the current time in
+    /// UTC, see [expand::now], and glob patterns in arguments expanded if
+    /// [Config::glob] is set.
     ///
     /// # Errors
-    /// If the executable cannot be ran.
-    pub async fn run_async(self) -> std::io::Result<ExitStatus> {
-        let Self { exe, arg } = self;
-        ::tokio::process::Command::new(exe).args(arg).status().await
+    /// If [Config::strict_expand] is set and a reference or the format of a
+    /// `{{now}}` placeholder cannot be expanded, if arguments cannot be
+    /// expanded, see [Config::expand_args], if running through a shell
+    /// program that cannot be found, or if a debugger is set while not
+    /// launched directly.
+    pub fn expand_paths(&self) -> Result<Self, ExpandError> {
+        if self.debugger.is_some() && !self.launch.is_direct() {
+            return Err(ExpandError::DebugLaunched(self.launch));
+        }
+        let mut config = self.clone();
+        config.apply_arg_options();
+        config.exe = expand::expand(&config.exe, self.strict_expand)?;
+        if let Some(cwd) = self.cwd.as_deref().and_then(Path::to_str) {
+            config.cwd = Some(PathBuf::from(expand::expand(cwd, self.strict_expand)?));
+        }
+        config.env = env::resolve(
+            &env::with_conditions(&self.env, &self.env_when, self.active_profile.as_deref()),
+            self.strict_expand,
+        )?;
+        let now = Timestamp::now();
+        for arg in config.arg.iter_mut().filter(|arg| arg.is_active()) {
+            arg.value = expand::now(&arg.value, now, self.strict_expand)?;
+        }
+        config.arg = config.expand_args()?;
+        if self.shell
+            && let Some(shell_program) = &self.shell_program
+            && !program_exists_in(shell_program, self.search_path().as_deref())
+        {
+            return Err(ExpandError::ShellNotFound(shell_program.clone()));
+        }
+        Ok(config)
+    }
+
+    /// Trim active arguments and drop those left empty if
+    /// [Config::trim_args] is set, and move leading assignments to the
+    /// environment if [Config::inline_env] is set and no executable is, see
+    /// [env::take_assignments]. Configs converted from the ui already have
+    /// this applied, applying it again has no effect.
+    pub fn apply_arg_options(&mut self) {
+        if self.trim_args {
+            self.arg.retain_mut(|arg| {
+                if !arg.is_active() {
+                    return true;
+                }
+                arg.value = arg.value.trim().to_owned();
+                !arg.value.is_empty()
+            });
+        }
+        if self.inline_env && self.exe.is_empty() {
+            let mut words = self
+                .arg
+                .iter()
+                .take_while(|arg| arg.is_active())
+                .map(|arg| arg.value.clone())
+                .collect::<Vec<_>>();
+            let count = words.len();
+            let assignments = env::take_assignments(&mut words);
+            self.arg.drain(..count - words.len());
+            env::apply_overrides(&mut self.env, &assignments);
+            if self.arg.first().is_some_and(|arg| arg.value == "--") {
+                self.arg.remove(0);
+            }
+            if self.arg.first().is_some_and(Arg::is_active) {
+                self.exe = self.arg.remove(0).value;
+            }
+        }
+    }
+
+    /// Arguments with glob patterns replaced by matching paths relative to
+    /// working directory, if [Config::glob] is set and not running through
+    /// the system shell. Disabled arguments and arguments for other platforms
+    /// are kept as is.
+    ///
+    /// # Errors
+    /// If a pattern cannot be parsed, or if [Config::strict_glob] is set and
+    /// a pattern matches nothing.
+    pub fn expand_args(&self) -> Result<Vec<Arg>, ExpandError> {
+        if !self.glob || self.shell {
+            return Ok(self.arg.clone());
+        }
+        let mut args = Vec::with_capacity(self.arg.len());
+        for arg in &self.arg {
+            if !arg.is_active() {
+                args.push(arg.clone());
+                continue;
+            }
+            args.extend(
+                expand::glob(&arg.value, self.cwd.as_deref(), self.strict_glob)?
+                    .into_iter()
+                    .map(|value| Arg {
+                        value,
+                        ..arg.clone()
+                    }),
+            );
+        }
+        Ok(args)
+    }
+
+    /// Build command with paths expanded, see [Config::expand_paths]. If
+    /// [Config::create_cwd] is set a missing working directory is created.
+    /// Values of [Config::env_from_file] are read.
+    ///
+    /// # Errors
+    /// If paths cannot be expanded, if working directory cannot be created
+    /// or if a value cannot be read from file.
+    pub fn build_expanded_command(&self) -> Result<::std::process::Command, ExpandError> {
+        let config = self.expand_paths()?;
+        if config.create_cwd
+            && let Some(cwd) = &config.cwd
+            && !cwd.is_dir()
+        {
+            ::std::fs::create_dir_all(cwd).map_err(|source| ExpandError::CreateCwd {
+                path: cwd.clone(),
+                source,
+            })?;
+            ::log::info!("created working directory {cwd:?}");
+        }
+        let mut command = config.build_command();
+        for entry in &config.env_from_file {
+            command.env(&entry.name, entry.read()?);
+        }
+        Ok(command)
+    }
+
+    /// Check if executable can be found, either as a path or in `PATH` as
+    /// given by [Config::search_path], once environment variables are
+    /// expanded. When not launched directly
+    /// the program used to launch it is checked instead.
+    ///
+    /// # Errors
+    /// If [Config::strict_expand] is set and a reference cannot be expanded.
+    pub fn exe_exists(&self) -> Result<bool, ExpandError> {
+        let search_path = self.search_path();
+        if let Some(program) = self.launch.program() {
+            return Ok(program_exists_in(program, search_path.as_deref()));
+        }
+        Ok(program_exists_in(
+            &expand::expand(&self.exe, self.strict_expand)?,
+            search_path.as_deref(),
+        ))
+    }
+
+    /// `PATH` programs are searched for in, [Config::path] joined if set and
+    /// the `PATH` of the application otherwise. [None] if neither is
+    /// available, or if entries of [Config::path] cannot be joined.
+    pub fn search_path(&self) -> Option<OsString> {
+        match &self.path {
+            Some(path) => ::std::env::join_paths(path).ok(),
+            None => ::std::env::var_os("PATH"),
+        }
+    }
+
+    /// Replace placeholders in output file paths of config and hooks,
+    /// `{timestamp}` by the current time, `{exe}` by the file stem of the
+    /// executable and `{n}` by run. Unknown placeholders are kept as is and
+    /// their names returned.
+    pub fn resolve_output_paths(&mut self, run: u64) -> Vec<String> {
+        let timestamp = Timestamp::now().compact();
+        let exe = Path::new(&self.exe)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut unknown = Vec::new();
+
+        for path in [
+            &mut self.output_log,
+            &mut self.stdout_file,
+            &mut self.stderr_file,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            let Some(value) = path.to_str() else {
+                continue;
+            };
+            let (rendered, mut missing) = template::render(value, |name| match name {
+                "timestamp" => Some(timestamp.clone()),
+                "exe" => Some(exe.clone()),
+                "n" => Some(run.to_string()),
+                _ => None,
+            });
+            *path = PathBuf::from(rendered);
+            unknown.append(&mut missing);
+        }
+
+        for hook in [&mut self.pre_run, &mut self.post_run]
+            .into_iter()
+            .flatten()
+        {
+            unknown.append(&mut hook.resolve_output_paths(run));
+        }
+
+        unknown
+    }
+
+    /// Files output of this config is written to, relative paths are
+    /// resolved against the working directory.
+    pub fn output_files(&self) -> OutputFiles {
+        let resolve = |path: &Option<PathBuf>| {
+            path.as_ref().map(|path| match &self.cwd {
+                Some(cwd) => cwd.join(path),
+                None => path.clone(),
+            })
+        };
+        OutputFiles {
+            log: resolve(&self.output_log),
+            stdout: resolve(&self.stdout_file),
+            stderr: resolve(&self.stderr_file),
+        }
+    }
+
+    /// Check if process of this config finishing with status succeeded,
+    /// according to [Config::success_codes].
+    pub fn is_success(&self, status: ExitStatus) -> bool {
+        is_success(&self.success_codes, status)
+    }
+
+    /// How processes of this config are stopped when killed.
+    pub fn shutdown(&self) -> Shutdown {
+        Shutdown {
+            signal: self.kill_signal,
+            grace: Duration::from_secs(self.kill_grace_secs.unwrap_or(DEFAULT_KILL_GRACE_SECS)),
+        }
+    }
+
+    /// Command line this config runs, quoted according to
+    /// [Config::quoting] for display.
+    pub fn command_line(&self) -> String {
+        let command = self.build_command();
+        let words = ::std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>();
+        self.quoting
+            .unwrap_or_default()
+            .join(words.iter().map(|word| word.as_ref()))
+    }
+
+    /// Run this config in an async context, with [Config::pre_run] ran
+    /// before and [Config::post_run] after. Only the exit status is kept,
+    /// use [Config::run_captured_async] for output and timing.
+    ///
+    /// # Errors
+    /// If the executable cannot be ran, or if the pre-run config fails.
+    pub fn run_async(mut self) -> BoxFuture<'static, std::io::Result<ExitStatus>> {
+        async move {
+            let result = match self.pre_run.take() {
+                Some(pre_run) => {
+                    let success_codes = pre_run.success_codes.clone();
+                    match pre_run.run_async().await {
+                        Ok(status) if is_success(&success_codes, status) => {
+                            ::log::info!("pre-run finished with {status}");
+                            self.status_async().await
+                        }
+                        Ok(status) => Err(pre_run_failed(status)),
+                        Err(err) => Err(err),
+                    }
+                }
+                None => self.status_async().await,
+            };
+
+            if let Some(post_run) = self.post_run.take() {
+                match post_run.run_async().await {
+                    Ok(status) => ::log::info!("post-run finished with {status}"),
+                    Err(err) => ::log::error!("post-run failed\n{err}"),
+                }
+            }
+
+            result
+        }
+        .boxed()
+    }
+
+    /// Run this config in an async context capturing stdout and stderr of
+    /// the executable, hooks are ran as by [Config::run_async] and their
+    /// output is not captured.
+    ///
+    /// This is the preferred entry point when using the crate as a library.
+    ///
+    /// # Errors
+    /// If the executable cannot be ran, or if the pre-run config fails.
+    pub async fn run_captured_async(mut self) -> std::io::Result<RunResult> {
+        let result = match self.pre_run.take() {
+            Some(pre_run) => {
+                let success_codes = pre_run.success_codes.clone();
+                match pre_run.run_async().await {
+                    Ok(status) if is_success(&success_codes, status) => {
+                        ::log::info!("pre-run finished with {status}");
+                        self.output_async().await
+                    }
+                    Ok(status) => Err(pre_run_failed(status)),
+                    Err(err) => Err(err),
+                }
+            }
+            None => self.output_async().await,
+        };
+
+        if let Some(post_run) = self.post_run.take() {
+            match post_run.run_async().await {
+                Ok(status) => ::log::info!("post-run finished with {status}"),
+                Err(err) => ::log::error!("post-run failed\n{err}"),
+            }
+        }
+
+        result
+    }
+
+    /// Run executable of this config without hooks in an async context,
+    /// capturing its output.
+    async fn output_async(&self) -> std::io::Result<RunResult> {
+        let command = self
+            .build_expanded_command()
+            .map_err(::std::io::Error::other)?;
+        let start = Instant::now();
+        let output = ::tokio::process::Command::from(command).output().await?;
+        Ok(RunResult {
+            status: output.status,
+            stdout: Some(output.stdout),
+            stderr: Some(output.stderr),
+            duration: start.elapsed(),
+        })
+    }
+
+    /// Run executable of this config without hooks in an async context.
+    async fn status_async(&self) -> std::io::Result<ExitStatus> {
+        let command = self
+            .build_expanded_command()
+            .map_err(::std::io::Error::other)?;
+        ::tokio::process::Command::from(command).status().await
+    }
+
+    /// Run executable of this config without hooks.
+    fn status(&self) -> std::io::Result<ExitStatus> {
+        let mut command = self
+            .build_expanded_command()
+            .map_err(::std::io::Error::other)?;
+        if self.debugger.is_none() {
+            self.output_files().redirect(&mut command)?;
+        }
+        let (status, usage) = process::wait_blocking(command.spawn()?, self.resource_usage)?;
+        if let Some(usage) = usage {
+            ::log::info!("resource usage: {usage}");
+        }
+        Ok(status)
+    }
+
+    /// Run this config capturing stdout and stderr, captured lines are
+    /// yielded as [Message::Output] followed by [Message::RunFinished] once
+    /// the process exits. Hook statuses are reported as output.
+    ///
+    /// If interactive and [Config::stdin_null] is not set, stdin of the
+    /// executable is piped and [Message::InputOpened] is yielded, otherwise
+    /// it reads no input. Hooks never read input.
+    ///
+    /// The running process is stopped whenever the generation of kill is
+    /// increased.
+    pub fn run_captured(
+        &self,
+        kill: watch::Receiver<u64>,
+        interactive: bool,
+    ) -> impl Stream<Item = Message> + use<> {
+        let config = self.clone();
+        ::iced::stream::channel(64, move |mut sender: Sender<Message>| async move {
+            let (messages, mut received) = mpsc::unbounded();
+            let run = config.capture_with(kill, interactive, move |message| {
+                _ = messages.unbounded_send(message);
+            });
+            let forward = async {
+                while let Some(message) = received.next().await {
+                    _ = sender.send(message).await;
+                }
+            };
+            let (status, ()) = join(run, forward).await;
+            _ = sender.send(Message::RunFinished(status)).await;
+        })
+    }
+
+    /// Run this config and its hooks capturing stdout and stderr, calling
+    /// on_line with every captured line as soon as it is read. Hook statuses
+    /// are passed as lines as well. Stdin of the executable reads no input.
+    ///
+    /// This is the streaming entry point for using the crate as a library,
+    /// lines may be kept, filtered or forwarded as needed.
+    ///
+    /// The running process is stopped whenever the generation of kill is
+    /// increased.
+    ///
+    /// # Errors
+    /// If the executable cannot be ran, or if the pre-run config fails.
+    pub async fn run_with_callback(
+        &self,
+        kill: watch::Receiver<u64>,
+        mut on_line: impl FnMut(OutputLine),
+    ) -> Result<ExitStatus, String> {
+        self.capture_with(kill, false, |message| {
+            if let Message::Output(line) = message {
+                on_line(line);
+            }
+        })
+        .await
+    }
+
+    /// Run config and hooks, passing messages yielded while running to
+    /// on_message.
+    async fn capture_with(
+        &self,
+        kill: watch::Receiver<u64>,
+        interactive: bool,
+        mut on_message: impl FnMut(Message),
+    ) -> Result<ExitStatus, String> {
+        let (mut sender, mut receiver) = mpsc::channel(64);
+        let config = self.clone();
+        // Sender is dropped once the run finishes, ending the loop below.
+        let run = async move { config.capture(&mut sender, kill, interactive).await };
+        let receive = async {
+            while let Some(message) = receiver.next().await {
+                on_message(message);
+            }
+        };
+        let (status, ()) = join(run, receive).await;
+        status
+    }
+
+    /// Run this config in the background without capturing output or
+    /// running hooks, output is written to output files if set and
+    /// discarded otherwise. Yields [Message::BackgroundStarted] once the
+    /// process is spawned and [Message::BackgroundFinished] once it exits.
+    ///
+    /// The running process is stopped whenever the generation of kill is
+    /// increased.
+    pub fn run_background(
+        &self,
+        id: u64,
+        kill: watch::Receiver<u64>,
+    ) -> impl Stream<Item = Message> + use<> {
+        let config = self.clone();
+        ::iced::stream::channel(4, move |mut sender: Sender<Message>| async move {
+            let status = async {
+                let mut command = config.build_expanded_command().map_err(|err| {
+                    ::log::error!("{err}");
+                    err.to_string()
+                })?;
+                command.stdout(Stdio::null()).stderr(Stdio::null());
+                config
+                    .output_files()
+                    .redirect(&mut command)
+                    .map_err(|err| format!("could not create output file\n{err}"))?;
+                process::background_command(command, config.shutdown(), id, kill, &sender).await
+            }
+            .await;
+            _ = sender.send(Message::BackgroundFinished(id, status)).await;
+        })
+    }
+
+    /// Run config and hooks, forwarding captured output to sender.
+    fn capture(
+        mut self,
+        sender: &mut Sender<Message>,
+        kill: watch::Receiver<u64>,
+        interactive: bool,
+    ) -> BoxFuture<'_, Result<ExitStatus, String>> {
+        async move {
+            let result = match self.pre_run.take() {
+                Some(pre_run) => {
+                    let success_codes = pre_run.success_codes.clone();
+                    match pre_run.capture(sender, kill.clone(), false).await {
+                        Ok(status) if is_success(&success_codes, status) => {
+                            _ = sender
+                                .send(Message::Output(OutputLine::from(format!(
+                                    "pre-run finished with {status}\n"
+                                ))))
+                                .await;
+                            self.capture_command(kill.clone(), interactive, sender)
+                                .await
+                        }
+                        Ok(status) => Err(pre_run_failed(status).to_string()),
+                        Err(err) => Err(err),
+                    }
+                }
+                None => {
+                    self.capture_command(kill.clone(), interactive, sender)
+                        .await
+                }
+            };
+
+            if let Some(post_run) = self.post_run.take() {
+                let line = match post_run.capture(sender, kill, false).await {
+                    Ok(status) => format!("post-run finished with {status}\n"),
+                    Err(err) => format!("post-run failed\n{err}\n"),
+                };
+                _ = sender.send(Message::Output(OutputLine::from(line))).await;
+            }
+
+            result
+        }
+        .boxed()
+    }
+
+    /// Run executable of this config without hooks, capturing output.
+    async fn capture_command(
+        &self,
+        kill: watch::Receiver<u64>,
+        interactive: bool,
+        sender: &Sender<Message>,
+    ) -> Result<ExitStatus, String> {
+        let command = self.build_expanded_command().map_err(|err| {
+            ::log::error!("{err}");
+            err.to_string()
+        })?;
+        let result = if let Some(debugger) = &self.debugger {
+            _ = sender
+                .clone()
+                .send(Message::Output(OutputLine::from(format!(
+                    "running under {debugger}, using the terminal the ui was started from\n"
+                ))))
+                .await;
+            process::inherit_command(command, self.shutdown(), self.resource_usage, kill).await
+        } else {
+            process::capture_command(
+                command,
+                self.shutdown(),
+                self.output_files(),
+                interactive && !self.stdin_null,
+                self.resource_usage,
+                kill,
+                sender,
+            )
+            .await
+        };
+        let (status, usage) = result?;
+        if let Some(usage) = usage {
+            _ = sender
+                .clone()
+                .send(Message::Output(OutputLine::from(format!(
+                    "resource usage: {usage}\n"
+                ))))
+                .await;
+        }
+        Ok(status)
     }
 
     /// Run config.
     ///
     /// # Errors
-    /// If the executable cannot be ran.
-    pub fn run(self) -> std::io::Result<ExitStatus> {
-        let Self { exe, arg } = self;
-        ::std::process::Command::new(exe).args(arg).status()
+    /// If the executable cannot be ran, or if the pre-run config fails.
+    pub fn run(mut self) -> std::io::Result<ExitStatus> {
+        let result = match self.pre_run.take() {
+            Some(pre_run) => {
+                let success_codes = pre_run.success_codes.clone();
+                match pre_run.run() {
+                    Ok(status) if is_success(&success_codes, status) => {
+                        ::log::info!("pre-run finished with {status}");
+                        self.status()
+                    }
+                    Ok(status) => Err(pre_run_failed(status)),
+                    Err(err) => Err(err),
+                }
+            }
+            None => self.status(),
+        };
+
+        if let Some(post_run) = self.post_run.take() {
+            match post_run.run() {
+                Ok(status) => ::log::info!("post-run finished with {status}"),
+                Err(err) => ::log::error!("post-run failed\n{err}"),
+            }
+        }
+
+        result
+    }
+}
+
+/// Check if path is an http or https url, such paths are loaded over the
+/// network and cannot be saved to.
+pub fn is_url(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|path| path.starts_with("http://") || path.starts_with("https://"))
+}
+
+/// Fetch remote config content.
+///
+/// # Errors
+/// If the request fails or the server responds with an error.
+#[cfg(feature = "remote")]
+async fn fetch(url: String) -> Result<String, LoadError> {
+    let response = match ::reqwest::get(&url).await {
+        Ok(response) => response,
+        Err(source) => return Err(LoadError::Fetch { url, source }),
+    };
+    let status = response.status();
+    if !status.is_success() {
+        return Err(LoadError::Status {
+            url,
+            status: status.as_u16(),
+        });
+    }
+    match response.text().await {
+        Ok(content) => Ok(content),
+        Err(source) => Err(LoadError::Fetch { url, source }),
+    }
+}
+
+/// Fetch remote config content, without remote support this always fails.
+///
+/// # Errors
+/// Always, with [LoadError::RemoteDisabled].
+#[cfg(not(feature = "remote"))]
+async fn fetch(url: String) -> Result<String, LoadError> {
+    Err(LoadError::RemoteDisabled(url))
+}
+
+/// Used to skip serializing false fields.
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Parse a file mode creation mask given as octal.
+fn parse_umask(value: &str) -> Result<u32, String> {
+    match u32::from_str_radix(value, 8) {
+        Ok(umask) if umask <= 0o777 => Ok(umask),
+        Ok(_) => Err(format!("umask '{value}' is larger than 777")),
+        Err(err) => Err(format!("invalid octal umask '{value}'\n{err}")),
+    }
+}
+
+/// Build a command running line using program with args, `-c` if args are
+/// empty. If no program is given the system shell is used, `sh -c` on unix
+/// and `cmd /C` on windows.
+fn shell_command(program: Option<&str>, args: &[String], line: &str) -> ::std::process::Command {
+    if let Some(program) = program {
+        let mut command = ::std::process::Command::new(program);
+        if args.is_empty() {
+            command.arg("-c");
+        } else {
+            command.args(args);
+        }
+        command.arg(line);
+        return command;
+    }
+
+    #[cfg(windows)]
+    {
+        use ::std::os::windows::process::CommandExt;
+
+        let mut command = ::std::process::Command::new("cmd");
+        if args.is_empty() {
+            command.arg("/C");
+        } else {
+            command.args(args);
+        }
+        command.raw_arg(line);
+        command
+    }
+
+    #[cfg(not(windows))]
+    {
+        let mut command = ::std::process::Command::new("sh");
+        if args.is_empty() {
+            command.arg("-c");
+        } else {
+            command.args(args);
+        }
+        command.arg(line);
+        command
+    }
+}
+
+/// Arguments placed between debugger and executable such that arguments
+/// after the executable are passed to it, `--args` for gdb and `--` for
+/// lldb. Other debuggers are given the executable directly.
+pub fn debugger_args(debugger: &str) -> &'static [&'static str] {
+    let name = Path::new(debugger)
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if name.ends_with("gdb") {
+        &["--args"]
+    } else if name.ends_with("lldb") {
+        &["--"]
+    } else {
+        &[]
+    }
+}
+
+/// Check that path may be written, its directory existing and neither it
+/// nor its directory being read-only. Writability may change before the
+/// path is written, so writing may still fail.
+///
+/// # Errors
+/// If path is known not to be writable, why is returned.
+pub async fn check_writable(path: &Path) -> Result<(), String> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    match ::tokio::fs::metadata(dir).await {
+        Ok(metadata) if !metadata.is_dir() => return Err(format!("{dir:?} is not a directory")),
+        Ok(metadata) if !dir_writable(dir, &metadata) => {
+            return Err(format!("directory {dir:?} is read-only"));
+        }
+        Ok(_) => {}
+        Err(err) if err.kind() == ::std::io::ErrorKind::NotFound => {
+            return Err(format!("directory {dir:?} does not exist"));
+        }
+        // Other failures are left to the write.
+        Err(_) => {}
+    }
+    match ::tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.permissions().readonly() => Err("file is read-only".to_owned()),
+        _ => Ok(()),
+    }
+}
+
+/// Check if files may be created in dir by the current user.
+fn dir_writable(dir: &Path, metadata: &::std::fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use ::nix::unistd::{AccessFlags, access};
+
+        _ = metadata;
+        access(dir, AccessFlags::W_OK).is_ok()
+    }
+
+    #[cfg(not(unix))]
+    {
+        _ = dir;
+        !metadata.permissions().readonly()
+    }
+}
+
+/// Check if program can be found, either as a path or in `PATH`.
+pub fn program_exists(program: &str) -> bool {
+    program_exists_in(program, ::std::env::var_os("PATH").as_deref())
+}
+
+/// Check if program can be found, either as a path or in search path, which
+/// is formatted as `PATH`.
+pub fn program_exists_in(program: &str, search_path: Option<&OsStr>) -> bool {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file();
+    }
+    let Some(paths) = search_path else {
+        return false;
+    };
+    ::std::env::split_paths(paths).any(|dir| {
+        let candidate = dir.join(program);
+        candidate.is_file() || (cfg!(windows) && candidate.with_extension("exe").is_file())
+    })
+}
+
+/// Check if status is a success according to success codes, if there are
+/// none only 0 is a success.
+pub fn is_success(success_codes: &[i32], status: ExitStatus) -> bool {
+    if success_codes.is_empty() {
+        status.success()
+    } else {
+        status
+            .code()
+            .is_some_and(|code| success_codes.contains(&code))
+    }
+}
+
+/// Error used when a pre-run config does not succeed.
+fn pre_run_failed(status: ExitStatus) -> ::std::io::Error {
+    ::std::io::Error::other(format!("pre-run finished with {status}"))
+}
+
+/// Map an exit status to a code suitable for exiting the runner with.
+///
+/// Processes terminated by a signal are mapped to `128 + signal` on unix, in
+/// the same way shells report them.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+
+    #[cfg(unix)]
+    {
+        use ::std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of merging, loading and expanding configs.
+
+    use super::*;
+
+    /// Config parsed from toml.
+    fn config(toml: &str) -> Config {
+        ::toml::from_str(toml).unwrap()
+    }
+
+    /// Argument values of config.
+    fn args(config: &Config) -> Vec<&str> {
+        config.arg.iter().map(|arg| arg.value.as_str()).collect()
+    }
+
+    /// Values set in the merged config replace those of the config merged
+    /// into, values not set are kept.
+    #[test]
+    fn merge_replaces_set_values() {
+        let mut merged = config(
+            r#"
+            exe = "a"
+            arg = ["--a"]
+            cwd = "/a"
+            env = { A = "a" }
+            "#,
+        );
+        merged.merge(config(
+            r#"
+            exe = "b"
+            env = { B = "b" }
+            pre_run = { exe = "before" }
+            "#,
+        ));
+        assert_eq!(merged.exe, "b");
+        assert_eq!(args(&merged), ["--a"]);
+        assert_eq!(merged.cwd.as_deref(), Some(Path::new("/a")));
+        assert_eq!(merged.env, [("B".to_owned(), "b".to_owned())]);
+        assert_eq!(
+            merged.pre_run.map(|config| config.exe).as_deref(),
+            Some("before")
+        );
+    }
+
+    /// Flags are set by either config and never cleared by merging.
+    #[test]
+    fn merge_only_sets_flags() {
+        let mut merged = config("shell = true");
+        merged.merge(config("glob = true"));
+        assert!(merged.shell);
+        assert!(merged.glob);
+        merged.merge(config("shell = false"));
+        assert!(merged.shell);
+    }
+
+    /// Loading a config clears values set by configs loaded before it,
+    /// keeping only those of base.
+    #[test]
+    fn load_onto_clears_earlier_loads() {
+        let base = config(r#"arg = ["--cli"]"#);
+        let first = Config::load_onto(
+            &base,
+            config(
+                r#"
+                exe = "a"
+                shell = true
+                audit_log = "audit.jsonl"
+                post_run = { exe = "after" }
+                "#,
+            ),
+        );
+        assert!(first.shell);
+        assert!(first.post_run.is_some());
+
+        let second = Config::load_onto(&base, config(r#"exe = "b""#));
+        assert_eq!(second.exe, "b");
+        assert_eq!(args(&second), ["--cli"]);
+        assert!(!second.shell);
+        assert!(second.audit_log.is_none());
+        assert!(second.post_run.is_none());
+    }
+
+    /// Directory of files named names, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        /// Create directory unique to test containing files with names.
+        fn new(test: &str, names: &[&str]) -> Self {
+            let dir = ::std::env::temp_dir()
+                .join(format!("command-runner-{test}-{}", ::std::process::id()));
+            ::std::fs::create_dir_all(&dir).unwrap();
+            for name in names {
+                ::std::fs::write(dir.join(name), "").unwrap();
+            }
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            _ = ::std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Config globbing in dir with args.
+    fn glob_config(dir: &TempDir, arg: &[&str]) -> Config {
+        Config {
+            glob: true,
+            cwd: Some(dir.0.clone()),
+            arg: arg.iter().map(|arg| Arg::from((*arg).to_owned())).collect(),
+            ..Config::default()
+        }
+    }
+
+    /// Patterns expand to matching paths relative to working directory,
+    /// sorted by name.
+    #[test]
+    fn glob_sorted() {
+        let dir = TempDir::new("glob-sorted", &["c.txt", "a.txt", "b.txt", "a.log"]);
+        let config = glob_config(&dir, &["-v", "*.txt", "a.*"]);
+        assert_eq!(
+            args(&config.expand_paths().unwrap()),
+            ["-v", "a.txt", "b.txt", "c.txt", "a.log", "a.txt"]
+        );
+
+        let config = Config {
+            shell: true,
+            ..config
+        };
+        assert_eq!(
+            args(&config.expand_paths().unwrap()),
+            ["-v", "*.txt", "a.*"]
+        );
+    }
+
+    /// Patterns matching nothing are kept as is, unless strict.
+    #[test]
+    fn glob_no_match() {
+        let dir = TempDir::new("glob-no-match", &["a.txt"]);
+        let config = glob_config(&dir, &["*.log"]);
+        assert_eq!(args(&config.expand_paths().unwrap()), ["*.log"]);
+
+        let config = Config {
+            strict_glob: true,
+            ..config
+        };
+        assert!(matches!(
+            config.expand_paths(),
+            Err(ExpandError::NoMatch(pattern)) if pattern == "*.log"
+        ));
+    }
+
+    /// A literal argument containing glob characters is kept as is when it
+    /// matches nothing, also if a file is named by it.
+    #[test]
+    fn glob_literal() {
+        let dir = TempDir::new("glob-literal", &["file[1].txt"]);
+        let config = glob_config(&dir, &["file[1].txt", "--level=?"]);
+        assert_eq!(
+            args(&config.expand_paths().unwrap()),
+            ["file[1].txt", "--level=?"]
+        );
     }
 }
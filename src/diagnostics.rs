@@ -0,0 +1,73 @@
+//! [Diagnostics] impl.
+
+use ::std::{fmt::Display, path::PathBuf};
+
+use crate::{config::program_exists, paths};
+
+/// Information about the environment the application runs in, meant to be
+/// included in bug reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Version of application.
+    pub version: &'static str,
+    /// Operating system.
+    pub os: &'static str,
+    /// CPU architecture.
+    pub arch: &'static str,
+    /// Directories of `PATH`, in order.
+    pub path: Vec<PathBuf>,
+    /// Wine was found in `PATH`.
+    pub wine: bool,
+    /// `WINEPREFIX`, if set.
+    pub wine_prefix: Option<String>,
+    /// Directory ui state is stored in, if any.
+    pub data_dir: Option<PathBuf>,
+}
+
+impl Diagnostics {
+    /// Collect diagnostics of the current environment.
+    pub fn collect() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            os: ::std::env::consts::OS,
+            arch: ::std::env::consts::ARCH,
+            path: ::std::env::var_os("PATH")
+                .map(|paths| ::std::env::split_paths(&paths).collect())
+                .unwrap_or_default(),
+            wine: program_exists("wine"),
+            wine_prefix: ::std::env::var("WINEPREFIX").ok(),
+            data_dir: paths::data_dir(),
+        }
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        let Self {
+            version,
+            os,
+            arch,
+            path,
+            wine,
+            wine_prefix,
+            data_dir,
+        } = self;
+        writeln!(f, "version: {version}")?;
+        writeln!(f, "os: {os} ({arch})")?;
+        writeln!(f, "wine: {}", if *wine { "found" } else { "not found" })?;
+        writeln!(
+            f,
+            "wine prefix: {}",
+            wine_prefix.as_deref().unwrap_or("unset")
+        )?;
+        match data_dir {
+            Some(data_dir) => writeln!(f, "data directory: {}", data_dir.display())?,
+            None => writeln!(f, "data directory: none")?,
+        }
+        writeln!(f, "PATH:")?;
+        for dir in path {
+            writeln!(f, "  {}", dir.display())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! External editor support.
+
+use ::std::{path::PathBuf, process::ExitStatus};
+
+use crate::{Message, status::Level};
+
+/// Error raised when a file cannot be opened in an external editor.
+#[derive(Debug, ::thiserror::Error)]
+pub enum EditError {
+    /// Editor variable could not be split into a command.
+    #[error("could not parse editor '{editor}'\n{source}")]
+    Parse {
+        /// Editor that was to be parsed.
+        editor: String,
+        /// Parse error.
+        #[source]
+        source: ::shell_words::ParseError,
+    },
+
+    /// Editor could not be ran.
+    #[error("could not run editor '{editor}'\n{source}")]
+    Run {
+        /// Editor that could not be ran.
+        editor: String,
+        /// IO error.
+        #[source]
+        source: ::std::io::Error,
+    },
+
+    /// Editor exited unsuccessfully.
+    #[error("editor '{editor}' finished with {status}")]
+    Failed {
+        /// Editor that failed.
+        editor: String,
+        /// Exit status of editor.
+        status: ExitStatus,
+    },
+
+    /// Path could not be opened with default application.
+    #[error("could not open {path:?}\n{source}")]
+    Open {
+        /// Path that could not be opened.
+        path: PathBuf,
+        /// IO error.
+        #[source]
+        source: ::std::io::Error,
+    },
+}
+
+impl From<EditError> for Message {
+    fn from(value: EditError) -> Self {
+        let status = match value {
+            EditError::Parse { editor, source: _ } => format!("could not parse editor '{editor}'"),
+            EditError::Run { editor, source: _ } => format!("could not run editor '{editor}'"),
+            EditError::Failed { editor, status } => {
+                format!("editor '{editor}' finished with {status}")
+            }
+            EditError::Open { path, source: _ } => format!("could not open {path:?}"),
+        };
+        Message::SetStatusLevel(status, Level::Error)
+    }
+}
+
+/// How a file was opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edited {
+    /// File was edited using `$VISUAL` or `$EDITOR`, which has exited.
+    Waited(PathBuf),
+    /// File was opened with the default application, which may still be
+    /// running.
+    Opened(PathBuf),
+}
+
+/// Editor set by environment, `$VISUAL` is preferred over `$EDITOR`.
+fn editor() -> Option<String> {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|key| ::std::env::var(key).ok())
+        .find(|editor| !editor.trim().is_empty())
+}
+
+/// Open path in an external editor, waiting for it to exit. If no editor is
+/// set the path is opened using the default application without waiting.
+///
+/// # Errors
+/// If the editor cannot be parsed, ran, or exits unsuccessfully, or if the
+/// path cannot be opened using the default application.
+pub async fn edit(path: PathBuf) -> Result<Edited, EditError> {
+    let Some(editor) = editor() else {
+        return match ::open::that_detached(&path) {
+            Ok(()) => Ok(Edited::Opened(path)),
+            Err(source) => Err(EditError::Open { path, source }),
+        };
+    };
+
+    let mut words = match ::shell_words::split(&editor) {
+        Ok(words) => words.into_iter(),
+        Err(source) => return Err(EditError::Parse { editor, source }),
+    };
+    let Some(program) = words.next() else {
+        return Err(EditError::Run {
+            editor,
+            source: ::std::io::ErrorKind::NotFound.into(),
+        });
+    };
+
+    match ::tokio::process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .await
+    {
+        Ok(status) if status.success() => Ok(Edited::Waited(path)),
+        Ok(status) => Err(EditError::Failed { editor, status }),
+        Err(source) => Err(EditError::Run { editor, source }),
+    }
+}
@@ -0,0 +1,494 @@
+//! Environment variables of configs.
+
+use ::std::{fmt, path::PathBuf};
+
+use ::serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+};
+
+use crate::{
+    arg::TargetOs,
+    expand::{self, ExpandError},
+};
+
+/// Environment variable only set on a platform and/or under a profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConditionalEnv {
+    /// Variable name.
+    pub name: String,
+    /// Variable value, may reference other variables.
+    pub value: String,
+    /// Platform variable is set on.
+    #[serde(default, skip_serializing_if = "TargetOs::is_any")]
+    pub os: TargetOs,
+    /// Profile variable is set under, any if [None].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl ConditionalEnv {
+    /// Check if variable applies on the current platform under profile.
+    pub fn is_active(&self, profile: Option<&str>) -> bool {
+        self.os.is_current()
+            && self
+                .profile
+                .as_deref()
+                .is_none_or(|required| profile == Some(required))
+    }
+}
+
+/// Environment variable with a value read from a file just before each run,
+/// such that secrets mounted as files are never stored in configs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEnv {
+    /// Variable name.
+    pub name: String,
+    /// File value is read from, a single trailing line break is removed.
+    pub from_file: PathBuf,
+}
+
+impl FileEnv {
+    /// Read value of variable.
+    ///
+    /// # Errors
+    /// If the file cannot be read or is not valid utf-8.
+    pub fn read(&self) -> Result<String, ExpandError> {
+        let mut value = ::std::fs::read_to_string(&self.from_file).map_err(|source| {
+            ExpandError::ReadFileEnv {
+                name: self.name.clone(),
+                path: self.from_file.clone(),
+                source,
+            }
+        })?;
+        if value.ends_with('\n') {
+            value.pop();
+            if value.ends_with('\r') {
+                value.pop();
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// Env with conditional variables active under profile applied in order,
+/// replacing variables of env with the same name and appending the rest.
+pub fn with_conditions(
+    env: &[(String, String)],
+    conditional: &[ConditionalEnv],
+    profile: Option<&str>,
+) -> Vec<(String, String)> {
+    let mut env = env.to_vec();
+    let active = conditional
+        .iter()
+        .filter(|entry| entry.is_active(profile))
+        .map(|entry| (entry.name.clone(), entry.value.clone()))
+        .collect::<Vec<_>>();
+    apply_overrides(&mut env, &active);
+    env
+}
+
+/// Parse a `NAME=VALUE` assignment.
+///
+/// # Errors
+/// If value has no `=` or the name is empty.
+pub fn parse(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_owned(), value.to_owned()))
+        }
+        _ => Err(format!("'{value}' is not of the form NAME=VALUE")),
+    }
+}
+
+/// Parse text of `NAME=VALUE` assignments, one per line, blank lines are
+/// skipped.
+///
+/// # Errors
+/// If a line is not an assignment, the line is returned.
+pub fn parse_lines(text: &str) -> Result<Vec<(String, String)>, String> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse(line).map_err(|_| line.to_owned()))
+        .collect()
+}
+
+/// Remove leading `NAME=VALUE` words as a shell would treat them, stopping
+/// at the first other word. A `--` following assignments ends them and is
+/// removed as well.
+pub fn take_assignments(words: &mut Vec<String>) -> Vec<(String, String)> {
+    let mut assignments = Vec::new();
+    let mut taken = 0;
+    for word in words.iter() {
+        if word == "--" && !assignments.is_empty() {
+            taken += 1;
+            break;
+        }
+        let Some((name, value)) = word.split_once('=') else {
+            break;
+        };
+        if !is_name(name) {
+            break;
+        }
+        assignments.push((name.to_owned(), value.to_owned()));
+        taken += 1;
+    }
+    words.drain(..taken);
+    assignments
+}
+
+/// Check if name is a valid shell variable name.
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Format env as `NAME=VALUE` assignments, one per line.
+pub fn to_lines(env: &[(String, String)]) -> String {
+    env.iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Apply overrides to env, replacing values of variables set by both and
+/// appending the rest.
+pub fn apply_overrides(env: &mut Vec<(String, String)>, overrides: &[(String, String)]) {
+    for (name, value) in overrides {
+        match env.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => existing.clone_from(value),
+            None => env.push((name.clone(), value.clone())),
+        }
+    }
+}
+
+/// Variables of env not set to the same value in base, such that applying
+/// them to base as overrides results in env, save for removed variables.
+pub fn overrides(base: &[(String, String)], env: &[(String, String)]) -> Vec<(String, String)> {
+    env.iter()
+        .filter(|entry| !base.contains(entry))
+        .cloned()
+        .collect()
+}
+
+/// Resolve `$VAR`, `${VAR}` and `%VAR%` references in values of env, `$$`
+/// is kept as a literal `$`.
+///
+/// References resolve against earlier entries of env, the latest one with
+/// the name, and then against the environment of the application. As such
+/// `PATH=$PATH:dir` extends the inherited `PATH` unless set earlier, and
+/// entries cannot reference each other in a cycle.
+///
+/// # Errors
+/// If strict and a reference cannot be resolved.
+pub fn resolve(
+    env: &[(String, String)],
+    strict: bool,
+) -> Result<Vec<(String, String)>, ExpandError> {
+    // Only the start of values may be a `~`.
+    let home = ::dirs::home_dir().and_then(|home| home.into_os_string().into_string().ok());
+    let mut resolved = Vec::<(String, String)>::with_capacity(env.len());
+    for (name, value) in env {
+        let lookup = |reference: &str| {
+            resolved
+                .iter()
+                .rfind(|(name, _)| name == reference)
+                .map(|(_, value)| value.clone())
+                .or_else(|| ::std::env::var(reference).ok())
+        };
+        let value = value
+            .split("$$")
+            .enumerate()
+            .map(|(part_index, part)| {
+                let home = home.as_deref().filter(|_| part_index == 0);
+                expand::expand_with(part, strict, home, lookup)
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .join("$");
+        resolved.push((name.clone(), value));
+    }
+    Ok(resolved)
+}
+
+/// Serialize env as a map, keeping order.
+///
+/// # Errors
+/// If serializer fails.
+pub fn serialize<S>(env: &[(String, String)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(env.len()))?;
+    for (name, value) in env {
+        map.serialize_entry(name, value)?;
+    }
+    map.end()
+}
+
+/// Deserialize env from a map, keeping order.
+///
+/// # Errors
+/// If deserializer fails or content is not a map of strings.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<(String, String)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    /// Visitor collecting map entries in order.
+    struct EnvVisitor;
+
+    impl<'de> Visitor<'de> for EnvVisitor {
+        type Value = Vec<(String, String)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of environment variables")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut env = Vec::with_capacity(map.size_hint().unwrap_or_default());
+            while let Some(entry) = map.next_entry()? {
+                env.push(entry);
+            }
+            Ok(env)
+        }
+    }
+
+    deserializer.deserialize_map(EnvVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of environment handling.
+
+    use super::*;
+    use crate::{config::Config, profile::Profile};
+
+    /// Owned words of str slices.
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| (*word).to_owned()).collect()
+    }
+
+    /// Owned pair of name and value.
+    fn pair(name: &str, value: &str) -> (String, String) {
+        (name.to_owned(), value.to_owned())
+    }
+
+    /// Conditional variable, matching a platform and profile.
+    fn conditional(name: &str, value: &str, os: TargetOs, profile: Option<&str>) -> ConditionalEnv {
+        ConditionalEnv {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            os,
+            profile: profile.map(String::from),
+        }
+    }
+
+    /// Platform tests run on, and one they do not.
+    const fn platforms() -> (TargetOs, TargetOs) {
+        if cfg!(windows) {
+            (TargetOs::Windows, TargetOs::Unix)
+        } else {
+            (TargetOs::Unix, TargetOs::Windows)
+        }
+    }
+
+    /// Environment of config is the base env, then env of the selected
+    /// profile and last active variables of `env_when`.
+    #[test]
+    fn merge_order() {
+        let config = Config {
+            env: vec![pair("A", "base"), pair("B", "base"), pair("C", "base")],
+            env_when: vec![conditional("C", "when", TargetOs::Any, None)],
+            profile: [(
+                "p".to_owned(),
+                Profile {
+                    env: vec![pair("B", "profile"), pair("C", "profile")],
+                    ..Profile::default()
+                },
+            )]
+            .into(),
+            ..Config::default()
+        };
+        let config = config.with_profile(Some("p"));
+        let env = with_conditions(
+            &config.env,
+            &config.env_when,
+            config.active_profile.as_deref(),
+        );
+        assert_eq!(
+            env,
+            [pair("A", "base"), pair("B", "profile"), pair("C", "when")]
+        );
+    }
+
+    /// Variables limited to a platform or profile only apply on it.
+    #[test]
+    fn conditions() {
+        let (current, other) = platforms();
+        let conditional = [
+            conditional("CURRENT", "1", current, None),
+            conditional("OTHER", "1", other, None),
+            conditional("PROFILE", "1", TargetOs::Any, Some("p")),
+            conditional("BOTH", "1", current, Some("p")),
+        ];
+        assert_eq!(
+            with_conditions(&[], &conditional, None),
+            [pair("CURRENT", "1")]
+        );
+        assert_eq!(
+            with_conditions(&[], &conditional, Some("p")),
+            [
+                pair("CURRENT", "1"),
+                pair("PROFILE", "1"),
+                pair("BOTH", "1")
+            ]
+        );
+        assert_eq!(
+            with_conditions(&[], &conditional, Some("q")),
+            [pair("CURRENT", "1")]
+        );
+    }
+
+    /// Later entries replace earlier ones in place, new names are appended.
+    #[test]
+    fn later_replaces_earlier() {
+        let conditional = [
+            conditional("A", "first", TargetOs::Any, None),
+            conditional("B", "new", TargetOs::Any, None),
+            conditional("A", "second", TargetOs::Any, None),
+        ];
+        assert_eq!(
+            with_conditions(&[pair("A", "base"), pair("Z", "base")], &conditional, None),
+            [pair("A", "second"), pair("Z", "base"), pair("B", "new")]
+        );
+    }
+
+    /// Assignments are taken up to the first other word.
+    #[test]
+    fn take_assignments_stops_at_word() {
+        let mut args = words(&["A=1", "B=", "tool", "C=3"]);
+        let taken = take_assignments(&mut args);
+        assert_eq!(taken, [pair("A", "1"), pair("B", "")]);
+        assert_eq!(args, words(&["tool", "C=3"]));
+    }
+
+    /// Only the first `=` separates name from value.
+    #[test]
+    fn take_assignments_value_with_equals() {
+        let mut args = words(&["A=b=c", "tool"]);
+        assert_eq!(take_assignments(&mut args), [pair("A", "b=c")]);
+        assert_eq!(args, words(&["tool"]));
+    }
+
+    /// A `--` following assignments is removed, later words are kept.
+    #[test]
+    fn take_assignments_separator_after_assignments() {
+        let mut args = words(&["A=1", "--", "B=2", "tool"]);
+        assert_eq!(take_assignments(&mut args), [pair("A", "1")]);
+        assert_eq!(args, words(&["B=2", "tool"]));
+    }
+
+    /// A `--` without assignments before it is kept.
+    #[test]
+    fn take_assignments_separator_without_assignments() {
+        let mut args = words(&["--", "tool"]);
+        assert!(take_assignments(&mut args).is_empty());
+        assert_eq!(args, words(&["--", "tool"]));
+    }
+
+    /// Words that are not valid shell assignments end assignments.
+    #[test]
+    fn take_assignments_invalid_names() {
+        for word in ["1A=x", "=x", "A-B=x", "A B=x", "--opt=x"] {
+            let mut args = words(&[word, "tool"]);
+            assert!(take_assignments(&mut args).is_empty(), "{word}");
+            assert_eq!(args, words(&[word, "tool"]));
+        }
+        let mut args = words(&["_A1=x"]);
+        assert_eq!(take_assignments(&mut args), [pair("_A1", "x")]);
+    }
+
+    /// All words may be taken, and no words gives no assignments.
+    #[test]
+    fn take_assignments_all_or_none() {
+        let mut args = words(&["A=1", "B=2"]);
+        assert_eq!(
+            take_assignments(&mut args),
+            [pair("A", "1"), pair("B", "2")]
+        );
+        assert!(args.is_empty());
+
+        let mut args = Vec::new();
+        assert!(take_assignments(&mut args).is_empty());
+    }
+
+    /// Name of a variable not set in the environment tests run in.
+    const UNSET: &str = "COMMAND_RUNNER_TEST_UNSET";
+
+    /// References resolve against earlier entries, which may themselves
+    /// reference others.
+    #[test]
+    fn resolve_chained() {
+        let env = [pair("A", "a"), pair("B", "${A}/b"), pair("C", "$B/c:%A%")];
+        assert_eq!(
+            resolve(&env, true).unwrap(),
+            [pair("A", "a"), pair("B", "a/b"), pair("C", "a/b/c:a")]
+        );
+    }
+
+    /// A variable referencing its own name gets the inherited value, or that
+    /// of an earlier entry with the name, never that of a later one.
+    #[test]
+    fn resolve_self_reference() {
+        let path = ::std::env::var("PATH").unwrap_or_default();
+        let env = [pair("PATH", "$PATH:x"), pair("PATH", "later")];
+        assert_eq!(
+            resolve(&env, false).unwrap(),
+            [pair("PATH", &format!("{path}:x")), pair("PATH", "later")]
+        );
+
+        let env = [pair("A", "a"), pair("A", "$A:x")];
+        assert_eq!(
+            resolve(&env, true).unwrap(),
+            [pair("A", "a"), pair("A", "a:x")]
+        );
+    }
+
+    /// Entries referencing each other do not form a cycle, as only earlier
+    /// entries are referenced.
+    #[test]
+    fn resolve_no_cycle() {
+        let env = [pair(UNSET, "${B}x"), pair("B", &format!("${{{UNSET}}}y"))];
+        assert_eq!(
+            resolve(&env, false).unwrap(),
+            [pair(UNSET, "${B}x"), pair("B", "${B}xy")]
+        );
+    }
+
+    /// `$$` is a literal `$` and does not start a reference.
+    #[test]
+    fn resolve_escaped() {
+        let env = [pair("A", "a"), pair("B", "$$A $A $$$A $$")];
+        assert_eq!(
+            resolve(&env, true).unwrap(),
+            [pair("A", "a"), pair("B", "$A a $a $")]
+        );
+    }
+
+    /// Missing references are kept as is unless strict.
+    #[test]
+    fn resolve_missing() {
+        let env = [pair("A", &format!("${UNSET}/a"))];
+        assert_eq!(
+            resolve(&env, false).unwrap(),
+            [pair("A", &format!("${UNSET}/a"))]
+        );
+        assert!(matches!(
+            resolve(&env, true),
+            Err(ExpandError::Unset(name)) if name == UNSET
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+//! [Event] impl.
+
+use ::std::{fmt::Debug, sync::Arc};
+
+use crate::status::Level;
+
+/// Structured event emitted by [Cli::update][crate::Cli::update], observed
+/// using [Cli::on_event][crate::Cli::on_event].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Status line was set.
+    Status {
+        /// Status text.
+        text: String,
+        /// Severity of status.
+        level: Level,
+    },
+    /// A run was started in the foreground.
+    RunStarted {
+        /// Command line of run.
+        command_line: String,
+    },
+    /// A run in the foreground finished.
+    RunFinished {
+        /// Exit code of process, [None] if it could not be ran or was
+        /// killed by a signal.
+        exit_code: Option<i32>,
+        /// Whether the run is considered successful.
+        success: bool,
+        /// Reason the process could not be ran, if any.
+        error: Option<String>,
+    },
+}
+
+/// Callback observing events.
+#[derive(Clone)]
+pub struct EventHook(Arc<dyn Fn(&Event) + Send + Sync>);
+
+impl EventHook {
+    /// Create a hook calling f with every event.
+    pub fn new(f: impl Fn(&Event) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Pass event to hook.
+    pub fn emit(&self, event: &Event) {
+        (self.0)(event)
+    }
+}
+
+impl Debug for EventHook {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.debug_tuple("EventHook").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,341 @@
+//! Environment variable and glob expansion.
+
+use ::std::path::{MAIN_SEPARATOR, Path, PathBuf};
+
+use crate::{launch::Launch, timestamp::Timestamp};
+
+/// Error raised when a value cannot be expanded.
+#[derive(Debug, ::thiserror::Error)]
+pub enum ExpandError {
+    /// Referenced variable is not set.
+    #[error("environment variable {0} is not set")]
+    Unset(String),
+
+    /// Home directory could not be found.
+    #[error("could not find home directory")]
+    NoHome,
+
+    /// Glob pattern could not be parsed.
+    #[error("invalid glob pattern '{pattern}'\n{source}")]
+    Pattern {
+        /// Pattern that could not be parsed.
+        pattern: String,
+        /// Parse error.
+        #[source]
+        source: ::glob::PatternError,
+    },
+
+    /// Shell program could not be found.
+    #[error("shell program '{0}' could not be found")]
+    ShellNotFound(String),
+
+    /// Working directory could not be created.
+    #[error("could not create working directory '{path}'\n{source}")]
+    CreateCwd {
+        /// Working directory.
+        path: PathBuf,
+        /// Io error.
+        #[source]
+        source: ::std::io::Error,
+    },
+
+    /// Glob pattern matched no paths.
+    #[error("glob pattern '{0}' matched nothing")]
+    NoMatch(String),
+
+    /// Value of environment variable could not be read from file.
+    #[error("could not read value of {name} from {path:?}\n{source}")]
+    ReadFileEnv {
+        /// Variable name.
+        name: String,
+        /// File value is read from.
+        path: PathBuf,
+        /// Io error.
+        #[source]
+        source: ::std::io::Error,
+    },
+
+    /// Time placeholder has an unsupported format.
+    #[error("unsupported specifier {specifier} in {placeholder}")]
+    NowFormat {
+        /// Placeholder with the format.
+        placeholder: String,
+        /// Unsupported or incomplete specifier.
+        specifier: String,
+    },
+
+    /// Debugger is set for an executable that is not launched directly.
+    #[error("cannot run under a debugger when launched using {0}, launch directly instead")]
+    DebugLaunched(Launch),
+}
+
+/// Expand a leading `~` and `$VAR`, `${VAR}` or `%VAR%` references in value
+/// using the environment of the application.
+///
+/// # Errors
+/// If strict and a reference cannot be resolved.
+pub fn expand(value: &str, strict: bool) -> Result<String, ExpandError> {
+    let home = ::dirs::home_dir().and_then(|home| home.into_os_string().into_string().ok());
+    expand_with(value, strict, home.as_deref(), |name| {
+        ::std::env::var(name).ok()
+    })
+}
+
+/// Expand a leading `~` and `$VAR`, `${VAR}` or `%VAR%` references in value,
+/// using home for `~` and resolving variables with lookup. Unresolved
+/// references are kept as is unless strict.
+///
+/// # Errors
+/// If strict and a reference cannot be resolved.
+pub fn expand_with(
+    value: &str,
+    strict: bool,
+    home: Option<&str>,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> Result<String, ExpandError> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    if let Some(after) = rest.strip_prefix('~')
+        && (after.is_empty() || after.starts_with(['/', '\\']))
+    {
+        match home {
+            Some(home) => expanded.push_str(home),
+            None if strict => return Err(ExpandError::NoHome),
+            None => expanded.push('~'),
+        }
+        rest = after;
+    }
+
+    while let Some(index) = rest.find(['$', '%']) {
+        expanded.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let Some((name, len)) = parse_reference(rest) else {
+            expanded.push_str(&rest[..1]);
+            rest = &rest[1..];
+            continue;
+        };
+
+        match lookup(name) {
+            Some(value) => expanded.push_str(&value),
+            None if strict => return Err(ExpandError::Unset(name.to_owned())),
+            None => expanded.push_str(&rest[..len]),
+        }
+        rest = &rest[len..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// Parse variable reference at start of value, returning the name and
+/// length of the reference.
+fn parse_reference(value: &str) -> Option<(&str, usize)> {
+    if let Some(after) = value.strip_prefix("${") {
+        let end = after.find('}')?;
+        let name = &after[..end];
+        is_name(name).then_some((name, end + 3))
+    } else if let Some(after) = value.strip_prefix('$') {
+        let end = after
+            .find(|c: char| !is_name_char(c))
+            .unwrap_or(after.len());
+        let name = &after[..end];
+        is_name(name).then_some((name, end + 1))
+    } else if let Some(after) = value.strip_prefix('%') {
+        let end = after.find('%')?;
+        let name = &after[..end];
+        (!name.is_empty() && !name.contains(char::is_whitespace)).then_some((name, end + 2))
+    } else {
+        None
+    }
+}
+
+/// Check if c may be part of a `$` variable name.
+const fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Check if name is a valid `$` variable name.
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| !c.is_ascii_digit()) && name.chars().all(is_name_char)
+}
+
+/// Replace `{{now}}` and `{{now:FORMAT}}` placeholders in value by now in
+/// UTC, formatted as RFC 3339 or using a strftime style format, see
+/// [Timestamp::format]. Unless strict, placeholders with invalid formats are
+/// kept as is and warned about.
+///
+/// # Errors
+/// If strict and a placeholder has an invalid format.
+pub fn now(value: &str, now: Timestamp, strict: bool) -> Result<String, ExpandError> {
+    /// Start of placeholders.
+    const START: &str = "{{now";
+
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(index) = rest.find(START) {
+        expanded.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let after = &rest[START.len()..];
+
+        let (format, len) = if after.starts_with("}}") {
+            (None, START.len() + 2)
+        } else if let Some(format) = after.strip_prefix(':')
+            && let Some(end) = format.find("}}")
+        {
+            (Some(&format[..end]), START.len() + 1 + end + 2)
+        } else {
+            expanded.push_str(START);
+            rest = after;
+            continue;
+        };
+
+        match format.map_or_else(|| Ok(now.to_string()), |format| now.format(format)) {
+            Ok(formatted) => expanded.push_str(&formatted),
+            Err(specifier) if strict => {
+                return Err(ExpandError::NowFormat {
+                    placeholder: rest[..len].to_owned(),
+                    specifier,
+                });
+            }
+            Err(specifier) => {
+                ::log::warn!(
+                    "unsupported specifier {specifier} in {placeholder}, keeping it as is",
+                    placeholder = &rest[..len],
+                );
+                expanded.push_str(&rest[..len]);
+            }
+        }
+        rest = &rest[len..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Check if value contains glob pattern characters.
+fn is_pattern(value: &str) -> bool {
+    value.contains(['*', '?', '['])
+}
+
+/// Expand value as a glob pattern relative to cwd, matches are relative to
+/// cwd if the pattern is. Values without pattern characters are returned as
+/// is, as are patterns without matches unless strict.
+///
+/// # Errors
+/// If the pattern cannot be parsed, or if strict and nothing matches.
+pub fn glob(value: &str, cwd: Option<&Path>, strict: bool) -> Result<Vec<String>, ExpandError> {
+    if !is_pattern(value) {
+        return Ok(vec![value.to_owned()]);
+    }
+
+    let base = cwd.filter(|_| Path::new(value).is_relative());
+    let pattern = match base {
+        Some(base) => format!(
+            "{base}{MAIN_SEPARATOR}{value}",
+            base = ::glob::Pattern::escape(&base.to_string_lossy()),
+        ),
+        None => value.to_owned(),
+    };
+    let paths = ::glob::glob(&pattern).map_err(|source| ExpandError::Pattern {
+        pattern: value.to_owned(),
+        source,
+    })?;
+
+    let matches = paths
+        .filter_map(|path| match path {
+            Ok(path) => Some(path),
+            Err(err) => {
+                ::log::warn!("could not read glob match\n{err}");
+                None
+            }
+        })
+        .map(
+            |path| match base.and_then(|base| path.strip_prefix(base).ok()) {
+                Some(relative) => relative.to_string_lossy().into_owned(),
+                None => path.to_string_lossy().into_owned(),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    if !matches.is_empty() {
+        Ok(matches)
+    } else if strict {
+        Err(ExpandError::NoMatch(value.to_owned()))
+    } else {
+        Ok(vec![value.to_owned()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of variable references and time placeholders.
+
+    use super::*;
+
+    /// Placeholders are replaced by the time in UTC, invalid formats are
+    /// kept unless strict.
+    #[test]
+    fn now_placeholders() {
+        let time = Timestamp::from_unix(1_700_000_000);
+        assert_eq!(
+            now("at {{now}}, {{now:%F}} {{now", time, false).unwrap(),
+            "at 2023-11-14T22:13:20Z, 2023-11-14 {{now"
+        );
+        assert_eq!(now("{{now:%Q}}", time, false).unwrap(), "{{now:%Q}}");
+        assert!(matches!(
+            now("{{now:%Q}}", time, true),
+            Err(ExpandError::NowFormat { placeholder, .. }) if placeholder == "{{now:%Q}}"
+        ));
+    }
+
+    /// Lookup of variables used by tests.
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "A" => Some("a".to_owned()),
+            "LONG_NAME" => Some("long".to_owned()),
+            _ => None,
+        }
+    }
+
+    /// References in each syntax are replaced by their values.
+    #[test]
+    fn references() {
+        assert_eq!(
+            expand_with("$A/${A}b/%A%/$LONG_NAME.txt", true, None, lookup).unwrap(),
+            "a/ab/a/long.txt"
+        );
+        assert_eq!(
+            expand_with("~/$A", true, Some("/home"), lookup).unwrap(),
+            "/home/a"
+        );
+        assert_eq!(expand_with("a~/$A", true, None, lookup).unwrap(), "a~/a");
+    }
+
+    /// Missing variables are kept as is unless strict.
+    #[test]
+    fn missing() {
+        assert_eq!(
+            expand_with("$B ${B} %B% $A", false, None, lookup).unwrap(),
+            "$B ${B} %B% a"
+        );
+        assert!(matches!(
+            expand_with("x${B}", true, None, lookup),
+            Err(ExpandError::Unset(name)) if name == "B"
+        ));
+        assert!(matches!(
+            expand_with("~/x", true, None, lookup),
+            Err(ExpandError::NoHome)
+        ));
+    }
+
+    /// A `$` or `%` that does not start a reference is kept, such as a
+    /// trailing one.
+    #[test]
+    fn not_references() {
+        for value in ["a$", "a%", "$1", "${A", "100% $ %", "${}"] {
+            assert_eq!(expand_with(value, true, None, lookup).unwrap(), value);
+        }
+        assert_eq!(expand_with("$A$", true, None, lookup).unwrap(), "a$");
+    }
+}
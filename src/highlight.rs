@@ -0,0 +1,109 @@
+//! Highlighting of output lines.
+
+use ::iced::{Color, theme::Palette};
+use ::regex::Regex;
+use ::serde::{Deserialize, Serialize};
+
+use crate::{
+    palette::parse_hex,
+    status::{self, Level},
+};
+
+/// Rule highlighting output lines matching a pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    /// Regular expression lines are matched against.
+    pub pattern: String,
+    /// Severity of matching lines, deciding their color.
+    #[serde(default = "default_level")]
+    pub level: Level,
+    /// Color of matching lines given as hex, such as `#3c8dbc`, replacing
+    /// the color of level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// Matching lines are warnings unless stated otherwise.
+const fn default_level() -> Level {
+    Level::Warning
+}
+
+/// Rules used when a config has none, catching common error and warning
+/// words.
+pub fn default_rules() -> Vec<HighlightRule> {
+    [
+        (r"(?i)\b(error|fatal|panicked)\b", Level::Error),
+        (r"(?i)\bwarn(ing)?\b", Level::Warning),
+    ]
+    .into_iter()
+    .map(|(pattern, level)| HighlightRule {
+        pattern: pattern.to_owned(),
+        level,
+        color: None,
+    })
+    .collect()
+}
+
+/// Color lines are highlighted with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineColor {
+    /// Color of severity in the current theme.
+    Level(Level),
+    /// Fixed color.
+    Fixed(Color),
+}
+
+impl LineColor {
+    /// Resolve color according to palette.
+    pub fn resolve(self, palette: &Palette) -> Color {
+        match self {
+            LineColor::Fixed(color) => color,
+            LineColor::Level(Level::Info) => palette.primary,
+            LineColor::Level(Level::Success) => palette.success,
+            LineColor::Level(Level::Warning) => status::warning_color(),
+            LineColor::Level(Level::Error) => palette.danger,
+        }
+    }
+}
+
+/// Compiled highlight rules, the first matching rule decides the color of
+/// a line.
+#[derive(Debug, Clone, Default)]
+pub struct Highlighter {
+    /// Patterns with the color of matching lines.
+    rules: Vec<(Regex, LineColor)>,
+}
+
+impl Highlighter {
+    /// Compile rules, rules with invalid patterns or colors are skipped and
+    /// described by the returned messages.
+    pub fn new(rules: &[HighlightRule]) -> (Self, Vec<String>) {
+        let mut invalid = Vec::new();
+        let rules = rules
+            .iter()
+            .filter_map(|rule| {
+                let regex = Regex::new(&rule.pattern)
+                    .map_err(|err| invalid.push(format!("invalid highlight pattern\n{err}")))
+                    .ok()?;
+                let color = match rule.color.as_deref().map(parse_hex) {
+                    Some(Ok([r, g, b])) => LineColor::Fixed(Color::from_rgb8(r, g, b)),
+                    Some(Err(err)) => {
+                        invalid.push(format!("invalid highlight color\n{err}"));
+                        return None;
+                    }
+                    None => LineColor::Level(rule.level),
+                };
+                Some((regex, color))
+            })
+            .collect();
+        (Self { rules }, invalid)
+    }
+
+    /// Color of line, [None] if no rule matches.
+    pub fn color(&self, line: &str) -> Option<LineColor> {
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(line))
+            .map(|(_, color)| *color)
+    }
+}
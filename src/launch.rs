@@ -0,0 +1,94 @@
+//! [Launch] impl.
+
+use ::std::{fmt::Display, process::Stdio};
+
+use ::clap::ValueEnum;
+use ::serde::{Deserialize, Serialize};
+
+use crate::config::program_exists;
+
+/// How the executable is launched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Launch {
+    /// Executable is a path or a program in `PATH`.
+    #[default]
+    Direct,
+    /// Executable is a flatpak app id, launched using `flatpak run`.
+    Flatpak,
+    /// Executable is a snap name, launched using `snap run`.
+    Snap,
+}
+
+impl Launch {
+    /// All launch modes.
+    pub const ALL: [Launch; 3] = [Launch::Direct, Launch::Flatpak, Launch::Snap];
+
+    /// Check if executable is launched directly.
+    pub const fn is_direct(&self) -> bool {
+        matches!(self, Launch::Direct)
+    }
+
+    /// Program and arguments placed before the executable, an app id or
+    /// snap name, such that arguments after it are passed to the app.
+    pub const fn prefix(self) -> &'static [&'static str] {
+        match self {
+            Launch::Direct => &[],
+            Launch::Flatpak => &["flatpak", "run"],
+            Launch::Snap => &["snap", "run"],
+        }
+    }
+
+    /// Program used to launch executable, if any.
+    pub const fn program(self) -> Option<&'static str> {
+        match self {
+            Launch::Direct => None,
+            Launch::Flatpak => Some("flatpak"),
+            Launch::Snap => Some("snap"),
+        }
+    }
+
+    /// Check if program used to launch executable is installed.
+    pub fn is_available(self) -> bool {
+        self.program().is_none_or(program_exists)
+    }
+
+    /// Installed apps that may be launched, flatpak app ids or snap names.
+    ///
+    /// # Errors
+    /// If the program used to launch apps cannot be ran or fails.
+    pub async fn installed(self) -> Result<Vec<String>, String> {
+        let (program, args, skip): (_, &[&str], _) = match self {
+            Launch::Direct => return Ok(Vec::new()),
+            Launch::Flatpak => ("flatpak", &["list", "--app", "--columns=application"], 0),
+            // First line is a header.
+            Launch::Snap => ("snap", &["list"], 1),
+        };
+        let output = ::tokio::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .await
+            .map_err(|err| format!("could not run {program}\n{err}"))?;
+        if !output.status.success() {
+            return Err(format!("{program} finished with {}", output.status));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .skip(skip)
+            .filter_map(|line| line.split_whitespace().next())
+            .map(String::from)
+            .collect())
+    }
+}
+
+impl Display for Launch {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match self {
+            Launch::Direct => "Direct",
+            Launch::Flatpak => "Flatpak",
+            Launch::Snap => "Snap",
+        })
+    }
+}
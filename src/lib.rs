@@ -1,64 +1,341 @@
 #![doc = include_str!("../README.md")]
 
-use ::std::{convert::identity, path::PathBuf};
+use ::std::{
+    convert::identity,
+    ffi::OsString,
+    fmt::Display,
+    path::PathBuf,
+    process::{ExitStatus, Stdio},
+    time::{Duration, Instant, SystemTime},
+};
 
-use ::clap::{Parser, ValueEnum};
+use ::clap::{Parser, ValueEnum, ValueHint};
 use ::color_eyre::Report;
 use ::iced::{
     Alignment::Center,
-    Element, Font,
+    Color, Element, Font,
     Length::Fill,
-    Task,
+    Subscription, Task,
+    alignment::{Horizontal, Vertical},
     futures::FutureExt,
-    widget::{self, Column, Row, button, text, text_editor, text_input},
+    keyboard::{self, Key, key::Named},
+    widget::{
+        self, Column, Row, button, pick_list, scrollable, text, text_editor,
+        text_editor::{Binding, KeyPress},
+        text_input,
+        tooltip::Position,
+    },
 };
+use ::log::LevelFilter;
 use ::rfd::AsyncFileDialog;
+use ::serde::{Deserialize, Serialize};
+
+use crate::{
+    action::Action,
+    arg::Arg,
+    audit::AuditRecord,
+    browser::BrowserEntry,
+    compare::{Change, Comparison},
+    config::Config,
+    diagnostics::Diagnostics,
+    editor::Edited,
+    event::{Event, EventHook},
+    highlight::Highlighter,
+    launch::Launch,
+    library::Library,
+    output::OutputLine,
+    paste::PasteMode,
+    pick::Pick,
+    post_action::PostAction,
+    process::{Input, InputSender},
+    profile::ProfileChoice,
+    provenance::Source,
+    queue::RunQueue,
+    report::ReportEntry,
+    state::{Background, DEFAULT_DEBUGGER, Pending, Queued, State, TaggedArg},
+    stats::{DurationChart, Stats},
+    status::Level,
+    switcher::Switcher,
+    timestamp::Timestamp,
+    token::{Highlight, TokenHighlighter},
+    ui_state::UiState,
+};
+
+pub mod action;
+
+pub mod arg;
+
+pub mod audit;
+
+pub mod base64;
+
+pub mod browser;
 
-use crate::{config::Config, state::State};
+pub mod compare;
 
 pub mod config;
 
+pub mod diagnostics;
+
+pub mod editor;
+
+pub mod env;
+
+pub mod event;
+
+pub mod expand;
+
+pub mod highlight;
+
+pub mod launch;
+
+pub mod library;
+
+pub mod notify;
+
+pub mod output;
+
+pub mod palette;
+
+pub mod paste;
+
+pub mod paths;
+
+pub mod pick;
+
+pub mod post_action;
+
+pub mod process;
+
+pub mod profile;
+
+pub mod provenance;
+
+pub mod quote;
+
+pub mod queue;
+
+pub mod report;
+
 pub mod state;
 
+pub mod stats;
+
+pub mod status;
+
+pub mod switcher;
+
+pub mod template;
+
+pub mod timestamp;
+
+pub mod token;
+
+pub mod ui_state;
+
+pub mod usage;
+
 /// Application inted for use to run other applications in a wine envirnoment.
 #[derive(Debug, Parser)]
 #[command(author, version, long_about = None)]
 pub struct Cli {
-    /// Theme to use for application.
-    #[arg(value_enum, long, short, default_value_t)]
-    theme: Theme,
+    /// Theme to use for application, overrides the theme selected in the
+    /// ui for this session. If neither is set the dark theme is used.
+    #[arg(value_enum, long, short)]
+    theme: Option<Theme>,
 
-    /// Load config from file.
+    /// Load config from file, or from an http url if built with the `remote`
+    /// feature. Remote configs are read only.
+    ///
+    /// Executable and arguments given on the command line are replaced by
+    /// those of the config if it has any, other values given on the command
+    /// line are kept unless set by the config.
     #[arg(long = "config", short)]
     config_path: Option<PathBuf>,
 
-    /// Load config and do not open ui.
-    #[arg(
-        long,
-        conflicts_with = "exe",
-        conflicts_with = "arg",
-        conflicts_with = "theme",
-        requires = "config_path"
-    )]
+    /// Load config from base64 encoded toml given inline, such that no file
+    /// is needed. Useful for desktop entries and shortcuts.
+    ///
+    /// Values given on the command line are kept unless set by the config.
+    #[arg(long, value_name = "BASE64", conflicts_with_all = ["config_path", "run_named"])]
+    config_inline: Option<String>,
+
+    /// Read config as toml from stdin, keeping it out of the argument list
+    /// of the process, as done when duplicating a window.
+    ///
+    /// If `--config` is given the config is not loaded from it, instead it
+    /// is the path the config is saved to. Values given on the command line
+    /// are kept unless set by the config.
+    #[arg(long, conflicts_with_all = ["config_inline", "run_named"])]
+    config_stdin: bool,
+
+    /// Profile of the initial config to select, instead of the profile last
+    /// selected for it. With `--skip` the profile is applied to the config
+    /// that is ran.
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Run config without opening ui, the config is loaded from file if
+    /// given, otherwise it is built from the inline config arguments.
+    #[arg(long, conflicts_with = "theme")]
     skip: bool,
 
+    /// Run config with name from the config library without opening ui,
+    /// as if `--skip` was given. Values given on the command line replace
+    /// those of the config.
+    #[arg(long, conflicts_with_all = ["theme", "config_path"])]
+    run_named: Option<String>,
+
+    /// Start running the config once it is loaded, showing the ui with its
+    /// output. Nothing is ran if loading fails or is cancelled. Has no
+    /// effect together with `--skip`, which runs the config without a ui.
+    #[arg(long = "run")]
+    run_on_launch: bool,
+
+    /// Save config to the path it was loaded from or last saved to after
+    /// each successful run.
+    #[arg(long)]
+    auto_save: bool,
+
+    /// Show a desktop notification when a run finishes.
+    #[arg(long)]
+    notify: bool,
+
+    /// Only notify when a run fails.
+    #[arg(long)]
+    notify_failure_only: bool,
+
+    /// Reload and run config when its file changes.
+    #[arg(long)]
+    watch: bool,
+
+    /// When watching, kill and restart a running process on changes instead
+    /// of skipping the run.
+    #[arg(long)]
+    watch_restart: bool,
+
+    /// Reopen the last loaded config on startup when no config is given,
+    /// the choice is remembered between sessions.
+    #[arg(long, overrides_with = "no_reopen")]
+    reopen: bool,
+
+    /// Do not reopen the last loaded config on startup, the choice is
+    /// remembered between sessions.
+    #[arg(long, overrides_with = "reopen")]
+    no_reopen: bool,
+
+    /// Directory ui state, library and queue are stored in. Takes
+    /// precedence over the `COMMAND_RUNNER_DATA_DIR` environment variable,
+    /// which in turn takes precedence over the data directory of the
+    /// platform.
+    #[arg(long, value_hint = ValueHint::DirPath)]
+    data_dir: Option<PathBuf>,
+
     /// Initial application config.
     #[command(flatten)]
     config: Config,
 
+    /// Config as given on the command line, which loaded configs are
+    /// applied to.
+    #[arg(skip)]
+    cli_config: Config,
+
     /// Application state.
     #[arg(skip)]
     state: State,
+
+    /// Ui state persisted between sessions.
+    #[arg(skip)]
+    ui_state: UiState,
+
+    /// Observer of events emitted by [Cli::update].
+    #[arg(skip)]
+    on_event: Option<EventHook>,
 }
 
-/// Application theme.
-#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+/// Application theme, one of the built-in themes of iced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Theme {
     /// Use light theme.
     Light,
-    /// Use dark theme
+    /// Use dark theme.
     #[default]
     Dark,
+    /// Use Dracula theme.
+    Dracula,
+    /// Use Nord theme.
+    Nord,
+    /// Use Solarized light theme.
+    SolarizedLight,
+    /// Use Solarized dark theme.
+    SolarizedDark,
+    /// Use Gruvbox light theme.
+    GruvboxLight,
+    /// Use Gruvbox dark theme.
+    GruvboxDark,
+    /// Use Catppuccin Latte theme.
+    CatppuccinLatte,
+    /// Use Catppuccin Frappé theme.
+    CatppuccinFrappe,
+    /// Use Catppuccin Macchiato theme.
+    CatppuccinMacchiato,
+    /// Use Catppuccin Mocha theme.
+    CatppuccinMocha,
+    /// Use Tokyo Night theme.
+    TokyoNight,
+    /// Use Tokyo Night Storm theme.
+    TokyoNightStorm,
+    /// Use Tokyo Night Light theme.
+    TokyoNightLight,
+    /// Use Kanagawa Wave theme.
+    KanagawaWave,
+    /// Use Kanagawa Dragon theme.
+    KanagawaDragon,
+    /// Use Kanagawa Lotus theme.
+    KanagawaLotus,
+    /// Use Moonfly theme.
+    Moonfly,
+    /// Use Nightfly theme.
+    Nightfly,
+    /// Use Oxocarbon theme.
+    Oxocarbon,
+    /// Use Ferra theme.
+    Ferra,
+}
+
+impl Theme {
+    /// All themes.
+    pub const ALL: [Theme; 22] = [
+        Theme::Light,
+        Theme::Dark,
+        Theme::Dracula,
+        Theme::Nord,
+        Theme::SolarizedLight,
+        Theme::SolarizedDark,
+        Theme::GruvboxLight,
+        Theme::GruvboxDark,
+        Theme::CatppuccinLatte,
+        Theme::CatppuccinFrappe,
+        Theme::CatppuccinMacchiato,
+        Theme::CatppuccinMocha,
+        Theme::TokyoNight,
+        Theme::TokyoNightStorm,
+        Theme::TokyoNightLight,
+        Theme::KanagawaWave,
+        Theme::KanagawaDragon,
+        Theme::KanagawaLotus,
+        Theme::Moonfly,
+        Theme::Nightfly,
+        Theme::Oxocarbon,
+        Theme::Ferra,
+    ];
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        write!(f, "{}", ::iced::Theme::from(*self))
+    }
 }
 
 impl From<Theme> for ::iced::Theme {
@@ -66,39 +343,481 @@ impl From<Theme> for ::iced::Theme {
         match value {
             Theme::Light => ::iced::Theme::Light,
             Theme::Dark => ::iced::Theme::Dark,
+            Theme::Dracula => ::iced::Theme::Dracula,
+            Theme::Nord => ::iced::Theme::Nord,
+            Theme::SolarizedLight => ::iced::Theme::SolarizedLight,
+            Theme::SolarizedDark => ::iced::Theme::SolarizedDark,
+            Theme::GruvboxLight => ::iced::Theme::GruvboxLight,
+            Theme::GruvboxDark => ::iced::Theme::GruvboxDark,
+            Theme::CatppuccinLatte => ::iced::Theme::CatppuccinLatte,
+            Theme::CatppuccinFrappe => ::iced::Theme::CatppuccinFrappe,
+            Theme::CatppuccinMacchiato => ::iced::Theme::CatppuccinMacchiato,
+            Theme::CatppuccinMocha => ::iced::Theme::CatppuccinMocha,
+            Theme::TokyoNight => ::iced::Theme::TokyoNight,
+            Theme::TokyoNightStorm => ::iced::Theme::TokyoNightStorm,
+            Theme::TokyoNightLight => ::iced::Theme::TokyoNightLight,
+            Theme::KanagawaWave => ::iced::Theme::KanagawaWave,
+            Theme::KanagawaDragon => ::iced::Theme::KanagawaDragon,
+            Theme::KanagawaLotus => ::iced::Theme::KanagawaLotus,
+            Theme::Moonfly => ::iced::Theme::Moonfly,
+            Theme::Nightfly => ::iced::Theme::Nightfly,
+            Theme::Oxocarbon => ::iced::Theme::Oxocarbon,
+            Theme::Ferra => ::iced::Theme::Ferra,
         }
     }
 }
 
+/// Log level of application, changeable at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Log info, warnings and errors.
+    Info,
+    /// Also log debug messages, these are compiled out of release builds.
+    Debug,
+}
+
+impl LogLevel {
+    /// Selectable log levels.
+    const ALL: [LogLevel; 2] = [LogLevel::Info, LogLevel::Debug];
+
+    /// Current log level, if it is one of the selectable ones.
+    fn current() -> Option<Self> {
+        match ::log::max_level() {
+            LevelFilter::Info => Some(LogLevel::Info),
+            LevelFilter::Debug | LevelFilter::Trace => Some(LogLevel::Debug),
+            LevelFilter::Off | LevelFilter::Error | LevelFilter::Warn => None,
+        }
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(value: LogLevel) -> Self {
+        match value {
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match self {
+            LogLevel::Info => "Info",
+            LogLevel::Debug => "Debug",
+        })
+    }
+}
+
 /// Application message.
 #[derive(Debug, Clone)]
 pub enum Message {
     /// Set the active theme.
     SetTheme(Theme),
+    /// Set max log level, this applies to all logging of the process.
+    SetLogLevel(LogLevel),
     /// Set the executable in use.
     SetExe(String),
+    /// Enter was pressed in executable input.
+    ExeSubmitted,
+    /// Set whether Enter in executable input runs.
+    SetRunOnSubmit(bool),
     /// Edit arguments.
     EditArgs(widget::text_editor::Action),
+    /// Edit scratch text.
+    EditScratch(widget::text_editor::Action),
+    /// Show or hide scratch text area.
+    SetShowScratch(bool),
+    /// Edit environment variables.
+    EditEnv(widget::text_editor::Action),
+    /// Show or hide environment variables.
+    SetShowEnv(bool),
+    /// Set whether arguments are edited token by token.
+    SetTokenEditor(bool),
+    /// Select next argument token.
+    NextToken,
+    /// Select previous argument token.
+    PreviousToken,
+    /// Split argument token at cursor, or join tokens around it.
+    SplitJoinToken,
     /// Set status line.
     SetStatus(String),
+    /// Show or hide overflow row of actions.
+    ToggleOverflow,
+    /// Show or hide action in bottom button row.
+    SetActionShown(Action, bool),
+    /// Set status line with a severity, info and success are shown as
+    /// toasts.
+    SetStatusLevel(String, Level),
+    /// Remove expired toasts.
+    ExpireToasts,
+    /// Abort pending dialog or load, if any.
+    CancelPending,
+    /// Pending dialog or load with id finished.
+    PendingFinished(u64),
     /// Update config.
     UpdateConfig(Box<(Config, PathBuf)>),
     /// Load config file.
     LoadConfig(PathBuf),
+    /// Loading a config failed, such that a run requested on launch is not
+    /// started by a later reload.
+    LoadFailed,
     /// Save config.
     SaveConfig(Box<(Config, PathBuf)>),
     /// Open executable dialog.
     ExeDialog,
+    /// Open dialog to pick files to add as arguments.
+    AddArgFilesDialog,
+    /// Add files as arguments.
+    AddArgFiles(Vec<PathBuf>),
+    /// Pick path for placeholder of argument at index.
+    PickPlaceholder(usize, Pick),
+    /// Fill placeholder of argument at index with path.
+    FillPlaceholder(usize, Pick, PathBuf),
+    /// Config was saved to path.
+    ConfigSaved(PathBuf),
+    /// Set whether config is saved after each successful run.
+    SetAutoSave(bool),
+    /// Set whether a notification is shown when a run finishes.
+    SetNotify(bool),
+    /// Set whether notifications are only shown for failed runs.
+    SetNotifyFailureOnly(bool),
+    /// Set whether config is reloaded and ran when its file changes.
+    SetWatch(bool),
+    /// Set whether a running process is restarted when the config file
+    /// changes.
+    SetWatchRestart(bool),
+    /// Check modification time of config file.
+    CheckConfigFile,
+    /// Config file was last modified at time.
+    ConfigFileModified(SystemTime),
+    /// Edit current config in an external editor, then load it.
+    EditConfig,
     /// Open config dialog.
     LoadConfigDialog,
     /// Save config dialog.
     SaveConfigDialog,
-    /// Run executable.
+    /// Compare config dialog.
+    CompareConfigDialog,
+    /// Load config file and compare current edits with it.
+    CompareConfig(PathBuf),
+    /// Compare current edits with loaded config.
+    ConfigCompared(Box<(Config, PathBuf)>),
+    /// Close comparison.
+    CloseComparison,
+    /// Merge config dialog.
+    MergeConfigDialog,
+    /// Load config file and merge it into current edits.
+    MergeConfig(PathBuf),
+    /// Merge loaded config into current edits.
+    ConfigMerged(Box<(Config, PathBuf)>),
+    /// Copy current config to clipboard as TOML.
+    CopyConfig,
+    /// Show or hide config library, loading it if needed.
+    SetShowLibrary(bool),
+    /// Config library was loaded.
+    LibraryLoaded(Box<Library>),
+    /// Load named config of library.
+    LoadLibraryEntry(String),
+    /// Set name current config is saved to the library as.
+    SetLibraryName(String),
+    /// Save current config to the library.
+    SaveLibraryEntry,
+    /// Current config was saved to the library with name.
+    LibraryEntrySaved(String),
+    /// Show or hide statistics of run durations.
+    SetShowStats(bool),
+    /// Show or hide where values of config fields came from.
+    SetShowSources(bool),
+    /// Show or hide config browser, scanning its directory if needed.
+    SetShowBrowser(bool),
+    /// Pick directory listed by config browser.
+    BrowserDirDialog,
+    /// Set directory listed by config browser.
+    SetBrowserDir(PathBuf),
+    /// Scan directory of config browser again.
+    RefreshBrowser,
+    /// Directory of config browser was scanned.
+    BrowserScanned(Vec<BrowserEntry>),
+    /// Run queue was loaded on startup.
+    QueueLoaded(Box<RunQueue>),
+    /// Show or hide run queue.
+    SetShowQueue(bool),
+    /// Add current config to the end of the run queue.
+    AddToQueue,
+    /// Remove config at index from the run queue.
+    RemoveFromQueue(usize),
+    /// Move config at index of the run queue by offset.
+    MoveQueued(usize, isize),
+    /// Run queued configs in order, each being removed once it succeeds.
+    ProcessQueue,
+    /// Remove all configs from the run queue.
+    DiscardQueue,
+    /// Run executable, asking for confirmation first if config requires
+    /// it.
     Run,
-    /// Exit.
+    /// Run executable without asking for confirmation.
+    ConfirmRun,
+    /// Decline running executable.
+    CancelRun,
+    /// Exit, killing running process first if kill on exit is set.
     Exit,
+    /// Set whether running process is killed on exit.
+    SetKillOnExit(bool),
+    /// Select profile of config, and reload content using it, asking for
+    /// confirmation first if it has been edited.
+    SelectProfile(ProfileChoice),
     /// Reload content to initial input.
     Reload,
+    /// Reload content to initial input, asking for confirmation first if it
+    /// has been edited.
+    RequestReload,
+    /// Keep edited content instead of reloading.
+    CancelReload,
+    /// Load last loaded config if enabled and it exists, otherwise reload.
+    ReopenLast,
+    /// Append a line of captured output.
+    Output(OutputLine),
+    /// Output was scrolled.
+    OutputScrolled(scrollable::Viewport),
+    /// Scroll output to the bottom and follow new lines again.
+    OutputToBottom,
+    /// Copy captured output to clipboard.
+    CopyOutput,
+    /// Open quick switcher.
+    OpenSwitcher,
+    /// Open a new window initialized with the current config.
+    DuplicateWindow,
+    /// Show diagnostics panel.
+    ShowDiagnostics,
+    /// Close diagnostics panel.
+    CloseDiagnostics,
+    /// Copy diagnostics to clipboard.
+    CopyDiagnostics,
+    /// Close quick switcher.
+    CloseSwitcher,
+    /// Set text matched by quick switcher.
+    SetSwitcherQuery(String),
+    /// Move selection of quick switcher by offset.
+    MoveSwitcherSelection(isize),
+    /// Load selected config of quick switcher.
+    SubmitSwitcher,
+    /// Read clipboard and append it to arguments.
+    PasteArgs,
+    /// Append clipboard text to arguments.
+    ClipboardArgs(String),
+    /// Set how pasted arguments are split.
+    SetPasteMode(PasteMode),
+    /// Save output dialog.
+    SaveOutputDialog,
+    /// Save captured output to path.
+    SaveOutput(PathBuf),
+    /// Select path to export report of finished runs to.
+    ExportReportDialog,
+    /// Export report of finished runs to path, as JSON if it ends with
+    /// `.json` and as Markdown otherwise.
+    ExportReport(PathBuf),
+    /// Set whether command line is written to output before running.
+    SetEchoCommand(bool),
+    /// Set whether input can be typed to runs.
+    SetInteractiveInput(bool),
+    /// Set whether output is saved as raw bytes.
+    SetSaveRawOutput(bool),
+    /// Set whether ANSI escape sequences are stripped from exported output.
+    SetStripAnsi(bool),
+    /// Set whether lines of output are highlighted.
+    SetHighlightOutput(bool),
+    /// Focus next input.
+    FocusNext,
+    /// Focus previous input.
+    FocusPrevious,
+    /// Kill running process.
+    Kill,
+    /// Process finished running.
+    RunFinished(Result<ExitStatus, String>),
+    /// Stdin of running process was opened.
+    InputOpened(InputSender),
+    /// Set line of input for running process.
+    SetInputLine(String),
+    /// Send line to stdin of running process.
+    SendInput(String),
+    /// Close stdin of running process.
+    CloseInput,
+    /// Run most recently ran config again, ignoring current edits.
+    RepeatLast,
+    /// Kill running process and run its config again once it has exited,
+    /// or run if no process is running.
+    Restart,
+    /// Run executable in the background.
+    RunBackground,
+    /// Background process with id was spawned with pid.
+    BackgroundStarted(u64, u32),
+    /// Background process with id finished running.
+    BackgroundFinished(u64, Result<ExitStatus, String>),
+    /// Kill background process with id, or remove it from the queue.
+    KillBackground(u64),
+    /// Set max amount of concurrent background processes, empty for no limit.
+    SetMaxConcurrent(String),
+    /// Refresh elapsed time of running and background processes.
+    RefreshElapsed,
+    /// Dismiss error banner.
+    DismissBanner,
+    /// Set arguments.
+    SetArgs(String),
+    /// Set whether arguments are edited as a table.
+    SetArgsTable(bool),
+    /// Set whether arguments are trimmed and empty ones dropped.
+    SetTrimArgs(bool),
+    /// Set whether leading assignments of arguments are moved to the
+    /// environment.
+    SetInlineEnv(bool),
+    /// Set whether tagged argument at index is passed.
+    SetArgEnabled(usize, bool),
+    /// Set whether to run through system shell.
+    SetShell(bool),
+    /// Set how executable is launched, listing installed apps if not
+    /// launched directly.
+    SetLaunch(Launch),
+    /// Installed apps of launch mode were listed.
+    LaunchAppsListed(Launch, Vec<String>),
+    /// Set wrapper command as shell quoted text.
+    SetWrapper(String),
+    /// Set name executable is invoked as.
+    SetArg0(String),
+    /// Set locale of executable.
+    SetLocale(String),
+    /// Set names of variables removed from the inherited environment.
+    SetEnvUnset(String),
+    /// Set whether to run under a debugger.
+    SetUseDebugger(bool),
+    /// Set debugger executable is ran under.
+    SetDebugger(String),
+    /// Set working directory.
+    SetCwd(String),
+    /// Open working directory dialog.
+    CwdDialog,
+    /// Edit table argument at index.
+    EditArg(usize, String),
+    /// Add an empty table argument.
+    AddArg,
+    /// Remove table argument at index.
+    RemoveArg(usize),
+    /// Move table argument at index up.
+    MoveArgUp(usize),
+    /// Move table argument at index down.
+    MoveArgDown(usize),
+    /// Set ui state.
+    SetUiState(Box<UiState>),
+}
+
+/// Interval at which config file is checked for changes when watching.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Time config file must be unchanged before a change is acted upon.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Time over which toasts fade out before expiring.
+const TOAST_FADE: Duration = Duration::from_millis(500);
+
+/// Interval at which toasts are faded and expired while any are shown.
+const TOAST_TICK: Duration = Duration::from_millis(50);
+
+/// Wrap content in a tooltip showing help.
+fn tip<'a>(
+    content: impl Into<Element<'a, Message>>,
+    help: &'a str,
+    position: Position,
+) -> Element<'a, Message> {
+    widget::tooltip(
+        content,
+        widget::container(text(help).size(12))
+            .padding(4)
+            .style(widget::container::rounded_box),
+        position,
+    )
+    .into()
+}
+
+/// Check if a display server is available to open windows on. Only
+/// detected on unix platforms using X11 or Wayland, elsewhere a display is
+/// assumed.
+fn has_display() -> bool {
+    if cfg!(all(unix, not(target_os = "macos"))) {
+        ["DISPLAY", "WAYLAND_DISPLAY"]
+            .into_iter()
+            .any(|var| ::std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+    } else {
+        true
+    }
+}
+
+/// Id of executable input.
+fn exe_input_id() -> text_input::Id {
+    text_input::Id::new("exe")
+}
+
+/// Id of output scrollable.
+fn output_scrollable_id() -> scrollable::Id {
+    scrollable::Id::new("output")
+}
+
+/// Id of quick switcher input.
+fn switcher_input_id() -> text_input::Id {
+    text_input::Id::new("switcher")
+}
+
+/// Key bindings of argument editor when editing token by token.
+fn token_key_binding(key_press: KeyPress) -> Option<Binding<Message>> {
+    match key_press.key.as_ref() {
+        Key::Named(Named::Tab) if key_press.modifiers.shift() => {
+            Some(Binding::Custom(Message::PreviousToken))
+        }
+        Key::Named(Named::Tab) => Some(Binding::Custom(Message::NextToken)),
+        Key::Named(Named::Enter) if key_press.modifiers.is_empty() => {
+            Some(Binding::Custom(Message::SplitJoinToken))
+        }
+        _ => Binding::from_key_press(key_press),
+    }
+}
+
+/// Start another instance of the application with args, writing config to
+/// its stdin, see [Cli::config_stdin].
+///
+/// # Errors
+/// If the executable of the application cannot be found or started, or if
+/// config cannot be written to it.
+async fn duplicate_window(
+    args: Vec<OsString>,
+    config: String,
+) -> Result<::tokio::process::Child, String> {
+    use ::tokio::io::AsyncWriteExt;
+
+    let exe = ::std::env::current_exe()
+        .map_err(|err| format!("could not find executable of application\n{err}"))?;
+    let mut child = ::tokio::process::Command::new(&exe)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("could not start {exe:?}\n{err}"))?;
+    // Stdin is closed when dropped, ending the config.
+    if let Some(mut stdin) = child.stdin.take()
+        && let Err(err) = stdin.write_all(config.as_bytes()).await
+    {
+        _ = child.start_kill();
+        return Err(format!("could not pass config to {exe:?}\n{err}"));
+    }
+    Ok(child)
+}
+
+/// Warning for unknown placeholders of output file paths.
+fn unknown_placeholders(names: &[String]) -> String {
+    let names = names
+        .iter()
+        .map(|name| format!("{{{name}}}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("unknown placeholders in output file path kept as is: {names}")
 }
 
 impl From<String> for Message {
@@ -108,196 +827,3259 @@ impl From<String> for Message {
 }
 
 impl Cli {
-    /// Run Application.
+    /// Run Application, returning the code the runner should exit with.
+    ///
+    /// When `--skip` is given the code is that of the executed process,
+    /// otherwise it is 0. If no display is available and a config is given
+    /// it is ran as if `--skip` was given.
     ///
     /// # Errors
     /// On fatal application errors.
-    pub fn run(mut self) -> ::color_eyre::Result<()> {
+    pub fn run(mut self) -> ::color_eyre::Result<i32> {
+        if let Some(data_dir) = &self.data_dir {
+            paths::set_data_dir(data_dir.clone());
+        }
+        self.skip |= self.run_named.is_some();
+        self.cli_config = self.config.clone();
+        if let Some(config_inline) = &self.config_inline {
+            let config = Config::from_inline(config_inline)?;
+            self.config.merge(config);
+        }
+        if self.config_stdin {
+            let content = ::std::io::read_to_string(::std::io::stdin())?;
+            self.config.merge(::toml::from_str(&content)?);
+            self.state.config_path = self.config_path.take().filter(|path| !config::is_url(path));
+        }
+        if self.config_inline.is_some() || self.config_stdin {
+            self.state.profile = self.profile.take().filter(|name| {
+                let exists = self.config.profile.contains_key(name);
+                if !exists {
+                    ::log::warn!("no profile named {name:?}, using default");
+                }
+                exists
+            });
+        }
+        if !self.skip && !has_display() {
+            if self.config_path.is_none() && self.config.exe.is_empty() {
+                return Err(::color_eyre::eyre::eyre!(
+                    "no display available, use --skip with --exe or --config to run without a window"
+                ));
+            }
+            ::log::warn!("no display available, running config without a window");
+            self.skip = true;
+        }
         if self.skip {
-            let config =
-                ::std::fs::read_to_string(self.config_path.unwrap_or_else(|| unreachable!()))?;
-            let config = ::toml::from_str::<Config>(&config)?;
-            config.run()?;
-            Ok(())
+            // A profile given along with an inline config or one read from
+            // stdin has already been checked to exist.
+            let profile = self.profile.take().or_else(|| self.state.profile.take());
+            let config = if let Some(name) = &self.run_named {
+                let mut library = ::tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(Library::load())?;
+                match library.take_with(name, self.config) {
+                    Some(config) => config,
+                    None if library.configs.is_empty() => {
+                        return Err(::color_eyre::eyre::eyre!(
+                            "no config named {name:?}, the library is empty"
+                        ));
+                    }
+                    None => {
+                        return Err(::color_eyre::eyre::eyre!(
+                            "no config named {name:?} in library, available configs are: {}",
+                            library.names().join(", ")
+                        ));
+                    }
+                }
+            } else {
+                let loaded = match self.config_path {
+                    Some(config_path) if config::is_url(&config_path) => Some(
+                        ::tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()?
+                            .block_on(Config::load(config_path))?
+                            .0,
+                    ),
+                    Some(config_path) => Some(::toml::from_str::<Config>(
+                        &::std::fs::read_to_string(config_path)?,
+                    )?),
+                    None => None,
+                };
+                // Values given on the command line are kept unless set by
+                // the loaded config, as when loading it in the ui.
+                let mut config = self.config;
+                if let Some(loaded) = loaded {
+                    config.merge(loaded);
+                }
+                config
+            };
+            let mut config = match profile {
+                Some(name) if config.profile.contains_key(&name) => {
+                    config.with_profile(Some(&name))
+                }
+                Some(name) if config.profile.is_empty() => {
+                    return Err(::color_eyre::eyre::eyre!(
+                        "no profile named {name:?}, the config has no profiles"
+                    ));
+                }
+                Some(name) => {
+                    return Err(::color_eyre::eyre::eyre!(
+                        "no profile named {name:?}, available profiles are: {}",
+                        config
+                            .profile
+                            .keys()
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+                None => config,
+            };
+            if config.exe.is_empty() {
+                return Err(::color_eyre::eyre::eyre!(
+                    "no executable given, use --exe or --config"
+                ));
+            }
+            let unknown = config.resolve_output_paths(1);
+            if !unknown.is_empty() {
+                ::log::warn!("{}", unknown_placeholders(&unknown));
+            }
+            let audit = config.audit_log.clone().map(|path| (path, config.clone()));
+            let success_codes = config.success_codes.clone();
+            let on_success = ::std::mem::take(&mut config.on_success);
+            let on_failure = ::std::mem::take(&mut config.on_failure);
+            let started = SystemTime::now();
+            let result = config.run();
+            if let Some((path, config)) = audit {
+                let record = AuditRecord::new(
+                    &config,
+                    started,
+                    result
+                        .as_ref()
+                        .map(|status| *status)
+                        .map_err(ToString::to_string),
+                );
+                if let Err(err) = record.append_blocking(path) {
+                    ::log::warn!("{err}");
+                }
+            }
+            let actions = if result
+                .as_ref()
+                .is_ok_and(|status| config::is_success(&success_codes, *status))
+            {
+                on_success
+            } else {
+                on_failure
+            };
+            if !actions.is_empty() {
+                ::tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(async {
+                        for action in actions {
+                            if let Err(err) = action.run().await {
+                                ::log::warn!("{err}");
+                            }
+                        }
+                    });
+            }
+            let status = result?;
+            // Codes treated as success are reported as such to the caller,
+            // as is failure when the code itself is 0.
+            if config::is_success(&success_codes, status) {
+                Ok(0)
+            } else {
+                Ok(match config::exit_code(status) {
+                    0 => 1,
+                    code => code,
+                })
+            }
         } else {
+            self.state.provenance.record(&self.config, Source::Cli);
             iced::application("Run Command", Self::update, Self::view)
-                .theme(|cli| ::iced::Theme::from(cli.theme))
-                .window_size((500.0, 200.0))
+                .theme(Self::theme)
+                .subscription(Self::subscription)
+                .window_size((500.0, 400.0))
                 .centered()
+                .exit_on_close_request(false)
                 .executor::<::tokio::runtime::Runtime>()
                 .run_with(|| {
                     let task = if let Some(path) = self.config_path.take() {
                         Message::LoadConfig(path)
-                    } else {
+                    } else if self.config_inline.is_some() || self.config_stdin {
                         Message::Reload
+                    } else {
+                        Message::ReopenLast
                     };
-                    (self, Task::done(task))
+                    // Ui state is loaded first, as it decides what to reopen
+                    // and is updated by loading a config.
+                    let load_ui_state = Task::future(UiState::load()).then(|result| match result {
+                        Ok(ui_state) => Task::done(Message::SetUiState(Box::new(ui_state))),
+                        Err(err) => {
+                            ::log::error!("{err}");
+                            Task::none()
+                        }
+                    });
+                    let load_queue = Task::future(RunQueue::load()).then(|result| match result {
+                        Ok(run_queue) => Task::done(Message::QueueLoaded(Box::new(run_queue))),
+                        Err(err) => {
+                            ::log::error!("{err}");
+                            Task::done(err.into())
+                        }
+                    });
+                    (
+                        self,
+                        Task::batch([
+                            load_ui_state.chain(Task::done(task)),
+                            load_queue,
+                            text_input::focus(exe_input_id()),
+                        ]),
+                    )
                 })
-                .map_err(Report::from)
+                .map_err(Report::from)?;
+            Ok(0)
+        }
+    }
+
+    /// Observe status changes, started runs and finished runs when
+    /// embedding the application, replacing any earlier observer. Events are
+    /// emitted by [Cli::update] as the messages causing them are handled.
+    pub fn on_event(mut self, f: impl Fn(&Event) + Send + Sync + 'static) -> Self {
+        self.on_event = Some(EventHook::new(f));
+        self
+    }
+
+    /// Pass event to observer, if any.
+    fn emit(&self, event: Event) {
+        if let Some(on_event) = &self.on_event {
+            on_event.emit(&event);
         }
     }
 
-    /// Update application state.
+    /// Update application state, emitting [Event]s observed using
+    /// [Cli::on_event].
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::SetTheme(theme) => {
-                self.theme = theme;
-                Task::done(
-                    format!("set theme to {theme}", theme = ::iced::Theme::from(theme)).into(),
-                )
+                self.theme = Some(theme);
+                self.ui_state.theme = Some(theme);
+                Task::batch([
+                    self.save_ui_state(),
+                    Task::done(format!("set theme to {theme}").into()),
+                ])
+            }
+            Message::SetLogLevel(level) => {
+                ::log::set_max_level(level.into());
+                ::log::info!("log level set to {level}");
+                Task::none()
             }
             Message::SetExe(exe) => {
                 self.state.exe = exe;
                 Task::done(format!("selected {exe}", exe = self.state.exe).into())
             }
-            Message::Run => {
-                let config = match self.state.to_config() {
+            Message::Run => match self.state.to_config() {
+                Ok(config) if config.confirm_before_run && self.state.kill.is_none() => {
+                    self.state.confirm_run = true;
+                    Task::none()
+                }
+                Ok(_) => Task::done(Message::ConfirmRun),
+                Err(err) => Task::done(err.into()),
+            },
+            Message::CancelRun => {
+                self.state.confirm_run = false;
+                Task::done("run cancelled".to_owned().into())
+            }
+            Message::ConfirmRun => {
+                self.state.confirm_run = false;
+                if self.state.kill.is_some() {
+                    return Task::done("a process is already running".to_owned().into());
+                }
+                match self.state.to_config() {
+                    Ok(config) => self.run_config(config),
+                    Err(err) => Task::done(err.into()),
+                }
+            }
+            Message::RepeatLast => {
+                if self.state.kill.is_some() {
+                    return Task::done("a process is already running".to_owned().into());
+                }
+                match self.state.last_run.clone() {
+                    Some(config) => self.run_config(config),
+                    None => Task::done("nothing has been ran yet".to_owned().into()),
+                }
+            }
+            Message::Restart => {
+                if self.state.kill.is_none() {
+                    return Task::done(Message::Run);
+                }
+                self.state.relaunch = true;
+                Task::done(Message::Kill)
+            }
+            Message::RunBackground => {
+                let mut config = match self.state.to_config() {
                     Ok(config) => config,
                     Err(err) => return Task::done(err.into()),
                 };
-                Task::future(config.run_async()).then(|result| match result {
-                    Ok(status) => Task::done(format!("process finished with {status}").into()),
-                    Err(msg) => {
-                        ::log::error!("failed to run process\n{msg}");
-                        Task::done(msg.to_string().into())
-                    }
-                })
+                self.state.run_count += 1;
+                let unknown = config.resolve_output_paths(self.state.run_count);
+                let id = self.state.next_background_id;
+                self.state.next_background_id += 1;
+                self.state.background_queue.push_back(Queued { id, config });
+                let task = self.start_queued();
+                if unknown.is_empty() {
+                    task
+                } else {
+                    Task::batch([
+                        task,
+                        Task::done(Message::SetStatusLevel(
+                            unknown_placeholders(&unknown),
+                            Level::Warning,
+                        )),
+                    ])
+                }
             }
-            Message::ExeDialog => Task::future(
-                AsyncFileDialog::new()
-                    .set_file_name(&self.state.exe)
-                    .set_title("Select Executable")
-                    .pick_file()
-                    .map(|handle| {
-                        let handle = handle
-                            .ok_or_else(|| Message::SetStatus("no executable selected".into()))?;
-                        let path = handle.path();
-                        handle
-                            .path()
-                            .to_str()
-                            .map(String::from)
-                            .ok_or_else(|| Message::SetStatus(format!("{path:?} is not unicode")))
-                    })
-                    .map(|exe| exe.map_or_else(identity, Message::SetExe)),
-            ),
-            Message::EditArgs(action) => {
-                self.state.args.perform(action);
+            Message::BackgroundStarted(id, pid) => {
+                if let Some(background) = self
+                    .state
+                    .background
+                    .iter_mut()
+                    .find(|background| background.id == id)
+                {
+                    background.pid = Some(pid);
+                }
                 Task::none()
             }
-            Message::SetStatus(status) => {
-                self.state.status = status;
+            Message::BackgroundFinished(id, result) => {
+                let Some(index) = self
+                    .state
+                    .background
+                    .iter()
+                    .position(|background| background.id == id)
+                else {
+                    return Task::none();
+                };
+                let background = self.state.background.remove(index);
+                self.state.report.push(ReportEntry::new(
+                    background.command.clone(),
+                    result.clone(),
+                    background.started.elapsed(),
+                    "",
+                ));
+                let start_queued = self.start_queued();
+                let pid = background
+                    .pid
+                    .map_or_else(|| "?".to_owned(), |pid| pid.to_string());
+                let (message, level) = match result {
+                    Ok(status) => (
+                        format!("background process {pid} finished with {status}"),
+                        if config::is_success(&background.success_codes, status) {
+                            Level::Success
+                        } else {
+                            Level::Warning
+                        },
+                    ),
+                    Err(err) => (format!("background process failed\n{err}"), Level::Error),
+                };
+                self.state.push_output(
+                    format!(
+                        "[{pid}] {command}: {message}\n",
+                        command = background.command
+                    )
+                    .as_bytes(),
+                );
+                if let Some(exit) = self.exit_when_stopped() {
+                    return exit;
+                }
+                Task::batch([
+                    Task::done(Message::SetStatusLevel(message, level)),
+                    start_queued,
+                ])
+            }
+            Message::KillBackground(id) => {
+                if let Some(index) = self
+                    .state
+                    .background_queue
+                    .iter()
+                    .position(|queued| queued.id == id)
+                {
+                    self.state.background_queue.remove(index);
+                    return Task::done("removed queued background run".to_owned().into());
+                }
+                match self
+                    .state
+                    .background
+                    .iter()
+                    .find(|background| background.id == id)
+                {
+                    Some(background) => {
+                        background.kill.send_modify(|generation| *generation += 1);
+                        Task::done("killing background process".to_owned().into())
+                    }
+                    None => Task::none(),
+                }
+            }
+            Message::SetMaxConcurrent(max_concurrent) => {
+                let max_concurrent = max_concurrent.trim();
+                if max_concurrent.is_empty() {
+                    self.ui_state.max_concurrent = None;
+                } else if let Ok(max_concurrent) = max_concurrent.parse::<usize>()
+                    && max_concurrent > 0
+                {
+                    self.ui_state.max_concurrent = Some(max_concurrent);
+                } else {
+                    return Task::none();
+                }
+                let start_queued = self.start_queued();
+                Task::batch([self.save_ui_state(), start_queued])
+            }
+            Message::RefreshElapsed => Task::none(),
+            Message::Kill => match &self.state.kill {
+                Some(kill) => {
+                    kill.send_modify(|generation| *generation += 1);
+                    Task::done("killing process".to_owned().into())
+                }
+                None => Task::done("no process is running".to_owned().into()),
+            },
+            Message::InputOpened(input) => {
+                self.state.input = Some(input);
                 Task::none()
             }
-            Message::Exit => ::iced::exit(),
-            Message::Reload => {
-                let Self {
-                    theme: _,
-                    config: Config { exe, arg },
-                    state,
-                    config_path: _,
-                    skip: _,
-                } = self;
-                state.args = widget::text_editor::Content::with_text(&::shell_words::join(arg));
-                state.exe = exe.clone();
-
+            Message::SetInputLine(input_line) => {
+                self.state.input_line = input_line;
                 Task::none()
             }
-            Message::UpdateConfig(config) => {
-                let (Config { exe, arg }, path_buf) = *config;
-
-                if !exe.is_empty() {
-                    self.config.exe = exe;
+            Message::SendInput(line) => {
+                let Some(input) = &self.state.input else {
+                    return Task::none();
+                };
+                let echo = format!("> {line}\n");
+                if input.send(Input::Line(line)).is_err() {
+                    self.state.input = None;
+                    return Task::done(Message::SetStatusLevel(
+                        "process is no longer reading input".into(),
+                        Level::Warning,
+                    ));
                 }
-
-                if !arg.is_empty() {
-                    self.config.arg = arg;
+                self.state.input_line.clear();
+                self.state.push_output(echo.as_bytes());
+                Task::none()
+            }
+            Message::CloseInput => {
+                if let Some(input) = self.state.input.take() {
+                    _ = input.send(Input::Close);
                 }
-
-                Task::batch(
-                    [
-                        format!("loaded config {path_buf:?}").into(),
-                        Message::Reload,
-                    ]
-                    .map(Task::done),
-                )
+                Task::none()
             }
-            Message::LoadConfig(path_buf) => {
-                Task::future(Config::load(path_buf)).then(|result| match result {
-                    Ok(config) => Task::done(Message::UpdateConfig(Box::new(config))),
-                    Err(err) => {
-                        ::log::error!("{err}");
-                        Task::done(err.into())
+            Message::RunFinished(result) => {
+                self.state.input = None;
+                let notify = self.notify_finished(&result);
+                let audit = self.audit_finished(&result);
+                self.report_finished(&result);
+                let queue = self.queue_finished(&result);
+                let post_actions = self.post_actions(&result);
+                let task = match result {
+                    Ok(status) => {
+                        self.state.kill = None;
+                        let message = format!("process finished with {status}");
+                        let success = self.run_succeeded(status);
+                        self.emit(Event::RunFinished {
+                            exit_code: status.code(),
+                            success,
+                            error: None,
+                        });
+                        let level = if success {
+                            Level::Success
+                        } else {
+                            Level::Error
+                        };
+                        let mut tasks = Vec::new();
+                        if !success {
+                            self.state.error_banner = Some(message.clone());
+                        } else if let Some(config) = &self.state.last_run {
+                            self.ui_state
+                                .push_arg_history(&config.exe, Arg::join_plain(&config.arg));
+                            if let Some(path_buf) = &self.state.config_path {
+                                self.ui_state.record_run(path_buf);
+                            }
+                            tasks.push(self.save_ui_state());
+                            if self.auto_save {
+                                tasks.push(
+                                    self.auto_save_config(self.state.for_save(config.clone())),
+                                );
+                            }
+                        }
+                        tasks.push(Task::done(Message::SetStatusLevel(message, level)));
+                        Task::batch(tasks)
                     }
-                })
-            }
-            Message::SaveConfig(config) => {
-                let (config, path_buf) = *config;
-                Task::future(config.save(path_buf)).then(|result| match result {
-                    Ok(path_buf) => Task::done(format!("saved config to {path_buf:?}").into()),
                     Err(err) => {
-                        ::log::error!("{err}");
-                        Task::done(err.into())
+                        self.state.kill = None;
+                        self.emit(Event::RunFinished {
+                            exit_code: None,
+                            success: false,
+                            error: Some(err.clone()),
+                        });
+                        self.state.error_banner = Some(err.clone());
+                        Task::done(Message::SetStatusLevel(err, Level::Error))
                     }
-                })
+                };
+                let task = Task::batch([task, notify, audit, queue, post_actions]);
+                if self.state.exit_pending {
+                    // Audit, notification and post actions finish before
+                    // exiting.
+                    match self.exit_when_stopped() {
+                        Some(exit) => task.chain(exit),
+                        None => task,
+                    }
+                } else if ::std::mem::take(&mut self.state.restart) {
+                    Task::batch([task, self.load_and_run()])
+                } else if ::std::mem::take(&mut self.state.relaunch) {
+                    Task::batch([task, Task::done(Message::RepeatLast)])
+                } else {
+                    task
+                }
             }
-            Message::LoadConfigDialog => {
-                Task::future(Config::load_dialog()).then(|result| match result {
-                    Ok(path_buf) => Task::done(Message::LoadConfig(path_buf)),
+            Message::DismissBanner => {
+                self.state.error_banner = None;
+                Task::none()
+            }
+            Message::SetArgs(args) => {
+                self.state.set_args(&args);
+                Task::none()
+            }
+            Message::SetTrimArgs(trim_args) => {
+                self.state.trim_args = trim_args;
+                Task::none()
+            }
+            Message::SetInlineEnv(inline_env) => {
+                self.state.inline_env = inline_env;
+                Task::none()
+            }
+            Message::SetArgEnabled(index, enabled) => {
+                if let Some(tagged) = self.state.tagged_args.get_mut(index) {
+                    tagged.arg.enabled = enabled;
+                }
+                Task::none()
+            }
+            Message::SetArgsTable(args_table) => match self.state.set_args_table(args_table) {
+                Ok(()) => Task::none(),
+                Err(err) => Task::done(err.into()),
+            },
+            Message::SetLaunch(launch) => {
+                self.state.launch = launch;
+                self.state.launch_apps.clear();
+                if launch.is_direct() {
+                    return Task::none();
+                }
+                if !launch.is_available() {
+                    return Task::done(Message::SetStatusLevel(
+                        format!("{launch} is not installed"),
+                        Level::Warning,
+                    ));
+                }
+                Task::future(launch.installed()).then(move |result| match result {
+                    Ok(apps) => Task::done(Message::LaunchAppsListed(launch, apps)),
                     Err(err) => {
-                        ::log::error!("{err}");
-                        Task::done(err.into())
+                        ::log::warn!("{err}");
+                        Task::done(Message::SetStatusLevel(
+                            format!("could not list installed {launch} apps"),
+                            Level::Warning,
+                        ))
                     }
                 })
             }
-            Message::SaveConfigDialog => {
-                let config = match self.state.to_config() {
-                    Ok(config) => config,
-                    Err(err) => return Task::done(err.into()),
-                };
-
-                Task::future(async { (config, Config::save_dialog().await) }).then(
-                    |(config, result)| match result {
-                        Ok(path_buf) => {
-                            Task::done(Message::SaveConfig(Box::new((config, path_buf))))
-                        }
-                        Err(err) => Task::done(err.into()),
-                    },
-                )
+            Message::LaunchAppsListed(launch, apps) => {
+                if self.state.launch == launch {
+                    self.state.launch_apps = apps;
+                }
+                Task::none()
             }
-        }
-    }
-
-    /// Render application.
-    pub fn view(&self) -> Element<Message> {
-        Column::new()
+            Message::SetShell(shell) => {
+                self.state.shell = shell;
+                Task::none()
+            }
+            Message::SetWrapper(wrapper) => {
+                self.state.wrapper = wrapper;
+                Task::none()
+            }
+            Message::SetArg0(arg0) => {
+                self.state.arg0 = arg0;
+                Task::none()
+            }
+            Message::SetLocale(locale) => {
+                self.state.locale = locale;
+                Task::none()
+            }
+            Message::SetEnvUnset(env_unset) => {
+                self.state.env_unset = env_unset;
+                Task::none()
+            }
+            Message::SetUseDebugger(use_debugger) => {
+                self.state.use_debugger = use_debugger;
+                Task::none()
+            }
+            Message::SetDebugger(debugger) => {
+                self.state.debugger = debugger;
+                Task::none()
+            }
+            Message::SetCwd(cwd) => {
+                self.state.cwd = cwd;
+                Task::none()
+            }
+            Message::CwdDialog => Task::future(
+                AsyncFileDialog::new()
+                    .set_directory(&self.state.cwd)
+                    .set_title("Select Working Directory")
+                    .pick_folder()
+                    .map(|handle| {
+                        let handle = handle.ok_or_else(|| {
+                            Message::SetStatusLevel(
+                                "no working directory selected".into(),
+                                Level::Warning,
+                            )
+                        })?;
+                        let path = handle.path();
+                        path.to_str()
+                            .map(String::from)
+                            .ok_or_else(|| Message::SetStatus(format!("{path:?} is not unicode")))
+                    })
+                    .map(|cwd| cwd.map_or_else(identity, Message::SetCwd)),
+            ),
+            Message::EditArg(index, value) => {
+                if let Some(arg) = self.state.args_vec.get_mut(index) {
+                    *arg = value;
+                }
+                Task::none()
+            }
+            Message::AddArg => {
+                self.state.args_vec.push(String::new());
+                Task::none()
+            }
+            Message::RemoveArg(index) => {
+                if index < self.state.args_vec.len() {
+                    self.state.args_vec.remove(index);
+                }
+                Task::none()
+            }
+            Message::MoveArgUp(index) => {
+                if let Some(index) = index.checked_sub(1) {
+                    self.state.swap_arg_down(index);
+                }
+                Task::none()
+            }
+            Message::MoveArgDown(index) => {
+                self.state.swap_arg_down(index);
+                Task::none()
+            }
+            Message::SetEchoCommand(echo_command) => {
+                self.ui_state.echo_command = echo_command;
+                self.save_ui_state()
+            }
+            Message::SetInteractiveInput(interactive_input) => {
+                self.ui_state.interactive_input = interactive_input;
+                self.save_ui_state()
+            }
+            Message::SetSaveRawOutput(save_raw_output) => {
+                self.ui_state.save_raw_output = save_raw_output;
+                self.save_ui_state()
+            }
+            Message::SetStripAnsi(strip_ansi) => {
+                self.ui_state.strip_ansi = strip_ansi;
+                self.save_ui_state()
+            }
+            Message::SetHighlightOutput(highlight_output) => {
+                self.ui_state.highlight_output = highlight_output;
+                self.save_ui_state()
+            }
+            Message::FocusNext => widget::focus_next(),
+            Message::FocusPrevious => widget::focus_previous(),
+            Message::SetUiState(ui_state) => {
+                self.ui_state = *ui_state;
+                self.state.output.set_max_bytes(
+                    self.ui_state
+                        .max_output_bytes
+                        .unwrap_or(output::DEFAULT_MAX_OUTPUT_BYTES),
+                );
+                if self.reopen || self.no_reopen {
+                    self.ui_state.reopen_last = self.reopen;
+                    self.save_ui_state()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Output(line) => {
+                self.state.push_output(line.raw());
+                if self.state.output_scrolled_up {
+                    Task::none()
+                } else {
+                    scrollable::snap_to(output_scrollable_id(), scrollable::RelativeOffset::END)
+                }
+            }
+            Message::OutputScrolled(viewport) => {
+                let bottom = viewport.absolute_offset().y + viewport.bounds().height;
+                self.state.output_scrolled_up = bottom + 1.0 < viewport.content_bounds().height;
+                Task::none()
+            }
+            Message::OutputToBottom => {
+                self.state.output_scrolled_up = false;
+                scrollable::snap_to(output_scrollable_id(), scrollable::RelativeOffset::END)
+            }
+            Message::CopyOutput => ::iced::clipboard::write(self.output_text()),
+            Message::SetShowLibrary(show_library) => {
+                self.state.show_library = show_library;
+                if !show_library || self.state.library.is_some() {
+                    return Task::none();
+                }
+                Task::future(Library::load()).then(|result| match result {
+                    Ok(library) => Task::done(Message::LibraryLoaded(Box::new(library))),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                })
+            }
+            Message::LibraryLoaded(library) => {
+                self.state.library = Some(*library);
+                Task::none()
+            }
+            Message::LoadLibraryEntry(name) => {
+                let Some(config) = self
+                    .state
+                    .library
+                    .as_ref()
+                    .and_then(|library| library.configs.get(&name))
+                else {
+                    return Task::done(Message::SetStatusLevel(
+                        format!("no config named {name} in library"),
+                        Level::Error,
+                    ));
+                };
+                self.state.provenance.record(config, Source::File);
+                self.config = Config::load_onto(&self.cli_config, config.clone());
+                // Library entries are saved through the library, not to a file.
+                self.state.config_path = None;
+                self.state.config_modified = None;
+                self.state.profile = None;
+                self.state.pending_profile = None;
+                self.state.library_name.clone_from(&name);
+                Task::batch([
+                    Task::done(Message::SetStatusLevel(
+                        format!("loaded config {name} from library"),
+                        Level::Success,
+                    )),
+                    Task::done(Message::Reload),
+                ])
+            }
+            Message::SetLibraryName(library_name) => {
+                self.state.library_name = library_name;
+                Task::none()
+            }
+            Message::SaveLibraryEntry => {
+                let name = self.state.library_name.trim().to_owned();
+                if name.is_empty() {
+                    return Task::done(Message::SetStatusLevel(
+                        "enter a name to save config to the library as".into(),
+                        Level::Warning,
+                    ));
+                }
+                let config = match self.state.to_config() {
+                    Ok(config) => self.state.for_save(config),
+                    Err(err) => return Task::done(err.into()),
+                };
+                let Some(library) = &mut self.state.library else {
+                    return Task::done(Message::SetStatusLevel(
+                        "library is not loaded".into(),
+                        Level::Error,
+                    ));
+                };
+                library.configs.insert(name.clone(), config);
+                Task::future(library.clone().save()).then(move |result| match result {
+                    Ok(()) => Task::done(Message::LibraryEntrySaved(name.clone())),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                })
+            }
+            Message::LibraryEntrySaved(name) => Task::done(Message::SetStatusLevel(
+                format!("saved config to library as {name}"),
+                Level::Success,
+            )),
+            Message::SetShowStats(show_stats) => {
+                self.state.show_stats = show_stats;
+                Task::none()
+            }
+            Message::SetShowSources(show_sources) => {
+                self.state.show_sources = show_sources;
+                Task::none()
+            }
+            Message::SetShowBrowser(show_browser) => {
+                self.state.show_browser = show_browser;
+                if show_browser && self.state.browser.is_none() {
+                    Task::done(Message::RefreshBrowser)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::BrowserDirDialog => {
+                let task = Task::future(browser::pick_dir()).then(|dir| match dir {
+                    Some(dir) => Task::done(Message::SetBrowserDir(dir)),
+                    None => Task::done(Message::SetStatusLevel(
+                        "no directory selected".into(),
+                        Level::Warning,
+                    )),
+                });
+                self.track_pending("browse dialog", task)
+            }
+            Message::SetBrowserDir(dir) => {
+                self.ui_state.browser_dir = Some(dir);
+                Task::batch([self.save_ui_state(), Task::done(Message::RefreshBrowser)])
+            }
+            Message::RefreshBrowser => {
+                let Some(dir) = self.ui_state.browser_dir.clone() else {
+                    return Task::none();
+                };
+                Task::future(browser::scan(dir)).then(|result| match result {
+                    Ok(entries) => Task::done(Message::BrowserScanned(entries)),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(Message::SetStatusLevel(err, Level::Error))
+                    }
+                })
+            }
+            Message::BrowserScanned(entries) => {
+                self.state.browser = Some(entries);
+                Task::none()
+            }
+            Message::QueueLoaded(run_queue) => {
+                self.state.run_queue = *run_queue;
+                self.state.resume_queue = !self.state.run_queue.configs.is_empty();
+                Task::none()
+            }
+            Message::SetShowQueue(show_queue) => {
+                self.state.show_queue = show_queue;
+                self.state.resume_queue = false;
+                Task::none()
+            }
+            Message::AddToQueue => {
+                // The active profile is not saved with the queue, its
+                // variables are applied before.
+                let config = match self.state.to_config() {
+                    Ok(config) => config.resolve_conditions(),
+                    Err(err) => return Task::done(err.into()),
+                };
+                self.state.run_queue.configs.push(config);
+                Task::batch([
+                    self.save_queue(),
+                    Task::done("added config to queue".to_owned().into()),
+                ])
+            }
+            Message::RemoveFromQueue(index) => {
+                if self.is_queue_locked(index) || index >= self.state.run_queue.configs.len() {
+                    return Task::none();
+                }
+                self.state.run_queue.configs.remove(index);
+                self.save_queue()
+            }
+            Message::MoveQueued(index, offset) => {
+                if self.is_queue_locked(index)
+                    || self.is_queue_locked(index.saturating_add_signed(offset))
+                {
+                    return Task::none();
+                }
+                self.state.run_queue.move_config(index, offset);
+                self.save_queue()
+            }
+            Message::ProcessQueue => {
+                self.state.resume_queue = false;
+                // No new runs are started while exiting.
+                if self.state.exit_pending {
+                    return Task::none();
+                }
+                if self.state.kill.is_some() {
+                    return Task::done("a process is already running".to_owned().into());
+                }
+                let Some(config) = self.state.run_queue.configs.first().cloned() else {
+                    self.state.queue_running = false;
+                    return Task::done("queue is empty".to_owned().into());
+                };
+                self.state.queue_running = true;
+                self.run_config(config)
+            }
+            Message::DiscardQueue => {
+                self.state.resume_queue = false;
+                if self.state.queue_running {
+                    return Task::done("queue is being processed".to_owned().into());
+                }
+                self.state.run_queue.configs.clear();
+                Task::batch([
+                    self.save_queue(),
+                    Task::done("discarded queue".to_owned().into()),
+                ])
+            }
+            Message::CopyConfig => {
+                let config = match self.state.to_config() {
+                    Ok(config) => self.state.for_save(config),
+                    Err(err) => {
+                        return Task::done(Message::SetStatusLevel(err.to_string(), Level::Error));
+                    }
+                };
+                match ::toml::to_string_pretty(&config) {
+                    Ok(content) => ::iced::clipboard::write(content)
+                        .chain(Task::done("copied config".to_owned().into())),
+                    Err(err) => Task::done(Message::SetStatusLevel(
+                        format!("could not serialize config\n{err}"),
+                        Level::Error,
+                    )),
+                }
+            }
+            Message::DuplicateWindow => {
+                let config = match self.state.to_config() {
+                    Ok(config) => self.state.for_save(config),
+                    Err(err) => return Task::done(err.into()),
+                };
+                let content = match ::toml::to_string(&config) {
+                    Ok(content) => content,
+                    Err(err) => {
+                        let status = format!("could not serialize config\n{err}");
+                        ::log::error!("{status}");
+                        return Task::done(Message::SetStatusLevel(status, Level::Error));
+                    }
+                };
+                // The config is passed on stdin as it may hold secrets, which
+                // would be visible to other users in the argument list.
+                let mut args = vec![OsString::from("--config-stdin")];
+                if let Some(config_path) = &self.state.config_path {
+                    args.extend(["--config".into(), config_path.into()]);
+                }
+                if let Some(profile) = &self.state.profile {
+                    args.extend(["--profile".into(), profile.into()]);
+                }
+                if let Some(theme) = self.theme.and_then(|theme| theme.to_possible_value()) {
+                    args.extend(["--theme".into(), theme.get_name().into()]);
+                }
+                if let Some(data_dir) = &self.data_dir {
+                    args.extend(["--data-dir".into(), data_dir.into()]);
+                }
+                Task::future(duplicate_window(args, content)).then(|result| match result {
+                    // Wait for the instance in the background such that it is reaped.
+                    Ok(mut child) => Task::done(Message::SetStatus(
+                        "opened duplicate window".into(),
+                    ))
+                    .chain(Task::future(async move { child.wait().await }).then(|_| Task::none())),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(Message::SetStatusLevel(err, Level::Error))
+                    }
+                })
+            }
+            Message::ShowDiagnostics => {
+                self.state.diagnostics = Some(Diagnostics::collect());
+                Task::none()
+            }
+            Message::CloseDiagnostics => {
+                self.state.diagnostics = None;
+                Task::none()
+            }
+            Message::CopyDiagnostics => match &self.state.diagnostics {
+                Some(diagnostics) => ::iced::clipboard::write(diagnostics.to_string())
+                    .chain(Task::done(Message::SetStatus("copied diagnostics".into()))),
+                None => Task::none(),
+            },
+            Message::OpenSwitcher => {
+                self.state.switcher = Some(Switcher::default());
+                text_input::focus(switcher_input_id())
+            }
+            Message::CloseSwitcher => {
+                self.state.switcher = None;
+                Task::none()
+            }
+            Message::SetSwitcherQuery(query) => {
+                if let Some(switcher) = &mut self.state.switcher {
+                    switcher.query = query;
+                    switcher.selected = 0;
+                }
+                Task::none()
+            }
+            Message::MoveSwitcherSelection(offset) => {
+                if let Some(switcher) = &mut self.state.switcher {
+                    let count = switcher.matches(&self.ui_state.recent_configs).len();
+                    switcher.move_selection(offset, count);
+                }
+                Task::none()
+            }
+            Message::SubmitSwitcher => {
+                let Some(switcher) = self.state.switcher.take() else {
+                    return Task::none();
+                };
+                match switcher
+                    .matches(&self.ui_state.recent_configs)
+                    .get(switcher.selected)
+                {
+                    Some(path) => Task::done(Message::LoadConfig(path.to_path_buf())),
+                    None => Task::done("no matching config".to_owned().into()),
+                }
+            }
+            Message::PasteArgs => ::iced::clipboard::read()
+                .map(|text| Message::ClipboardArgs(text.unwrap_or_default())),
+            Message::ClipboardArgs(text) => {
+                if text.trim().is_empty() {
+                    return Task::done("clipboard is empty".to_owned().into());
+                }
+                match self.ui_state.paste_mode.split(&text) {
+                    Ok(args) => {
+                        self.state.append_args(&args);
+                        Task::done(format!("pasted {} arguments", args.len()).into())
+                    }
+                    Err(err) => Task::done(Message::SetStatusLevel(
+                        format!("could not split clipboard as arguments\n{err}"),
+                        Level::Error,
+                    )),
+                }
+            }
+            Message::SetPasteMode(paste_mode) => {
+                self.ui_state.paste_mode = paste_mode;
+                self.save_ui_state()
+            }
+            Message::SaveOutputDialog => {
+                let task = Task::future(output::save_dialog()).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::SaveOutput(path_buf)),
+                    Err(err) => Task::done(err.into()),
+                });
+                self.track_pending("save output dialog", task)
+            }
+            Message::SaveOutput(path_buf) => {
+                let content = if self.ui_state.save_raw_output {
+                    self.state.output.raw().to_vec()
+                } else {
+                    self.output_text().into_bytes()
+                };
+                Task::future(output::save(content, path_buf)).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::SetStatusLevel(
+                        format!("saved output to {path_buf:?}"),
+                        Level::Success,
+                    )),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                })
+            }
+            Message::ExportReportDialog => {
+                let task = Task::future(report::save_dialog()).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::ExportReport(path_buf)),
+                    Err(err) => Task::done(err.into()),
+                });
+                self.track_pending("export report dialog", task)
+            }
+            Message::ExportReport(path_buf) => {
+                Task::future(self.state.report.clone().save(path_buf)).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::SetStatusLevel(
+                        format!("exported report to {path_buf:?}"),
+                        Level::Success,
+                    )),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                })
+            }
+            Message::ExeDialog => Task::future(
+                AsyncFileDialog::new()
+                    .set_file_name(&self.state.exe)
+                    .set_title("Select Executable")
+                    .pick_file()
+                    .map(|handle| {
+                        let handle = handle.ok_or_else(|| {
+                            Message::SetStatusLevel("no executable selected".into(), Level::Warning)
+                        })?;
+                        let path = handle.path();
+                        handle
+                            .path()
+                            .to_str()
+                            .map(String::from)
+                            .ok_or_else(|| Message::SetStatus(format!("{path:?} is not unicode")))
+                    })
+                    .map(|exe| exe.map_or_else(identity, Message::SetExe)),
+            ),
+            Message::AddArgFilesDialog => Task::future(
+                AsyncFileDialog::new()
+                    .set_title("Add Files")
+                    .pick_files()
+                    .map(|handles| match handles {
+                        Some(handles) => Message::AddArgFiles(
+                            handles
+                                .iter()
+                                .map(|handle| handle.path().to_path_buf())
+                                .collect(),
+                        ),
+                        None => Message::SetStatusLevel("no files selected".into(), Level::Warning),
+                    }),
+            ),
+            Message::AddArgFiles(paths) => {
+                let count = paths.len();
+                let files = paths
+                    .into_iter()
+                    .filter_map(|path| match path.into_os_string().into_string() {
+                        Ok(path) => Some(path),
+                        Err(path) => {
+                            ::log::warn!("skipping {path:?}, it is not unicode");
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let skipped = count - files.len();
+                self.state.append_args(&files);
+
+                let status = if skipped == 0 {
+                    format!("added {count} files")
+                } else {
+                    format!(
+                        "added {added} files, skipped {skipped} which were not unicode",
+                        added = files.len()
+                    )
+                };
+                Task::done(status.into())
+            }
+            Message::PickPlaceholder(index, pick) => {
+                Task::future(pick.dialog()).map(move |path| match path {
+                    Some(path) => Message::FillPlaceholder(index, pick, path),
+                    None => Message::SetStatusLevel("no path selected".into(), Level::Warning),
+                })
+            }
+            Message::FillPlaceholder(index, pick, path) => {
+                let Some(path) = path.to_str() else {
+                    return Task::done(Message::SetStatusLevel(
+                        format!("{path:?} is not unicode"),
+                        Level::Warning,
+                    ));
+                };
+                match self.state.fill_placeholder(index, pick, path) {
+                    Ok(()) => Task::none(),
+                    Err(err) => Task::done(err.into()),
+                }
+            }
+            Message::EditArgs(action) => {
+                self.state.args.perform(action);
+                Task::none()
+            }
+            Message::EditScratch(action) => {
+                self.state.scratch.perform(action);
+                Task::none()
+            }
+            Message::SetShowScratch(show_scratch) => {
+                self.state.show_scratch = show_scratch;
+                Task::none()
+            }
+            Message::EditEnv(action) => {
+                self.state.env.perform(action);
+                Task::none()
+            }
+            Message::SetShowEnv(show_env) => {
+                self.state.show_env = show_env;
+                Task::none()
+            }
+            Message::SetTokenEditor(token_editor) => {
+                self.ui_state.token_editor = token_editor;
+                self.save_ui_state()
+            }
+            Message::NextToken => {
+                self.state.select_token(true);
+                Task::none()
+            }
+            Message::PreviousToken => {
+                self.state.select_token(false);
+                Task::none()
+            }
+            Message::SplitJoinToken => {
+                self.state.split_join_token();
+                Task::none()
+            }
+            Message::SetStatus(status) => Task::done(Message::SetStatusLevel(status, Level::Info)),
+            Message::ToggleOverflow => {
+                self.state.show_overflow = !self.state.show_overflow;
+                Task::none()
+            }
+            Message::SetActionShown(action, shown) => {
+                self.ui_state.set_action_shown(action, shown);
+                self.save_ui_state()
+            }
+            Message::CancelPending => {
+                let Some(pending) = self.state.pending.take() else {
+                    return Task::none();
+                };
+                pending.handle.abort();
+                // A cancelled load on launch is not followed by a run.
+                self.run_on_launch = false;
+                self.state.status.clear();
+                Task::done(Message::SetStatusLevel(
+                    format!("cancelled {}", pending.description),
+                    Level::Info,
+                ))
+            }
+            Message::PendingFinished(id) => {
+                if self
+                    .state
+                    .pending
+                    .as_ref()
+                    .is_some_and(|pending| pending.id == id)
+                {
+                    self.state.pending = None;
+                }
+                Task::none()
+            }
+            Message::SetStatusLevel(status, level) => {
+                let status = output::strip_ansi(&status);
+                self.emit(Event::Status {
+                    text: status.clone(),
+                    level,
+                });
+                match level {
+                    Level::Info => self.state.push_toast(status, level),
+                    Level::Success => {
+                        // Earlier problems are considered resolved.
+                        self.state.status.clear();
+                        self.state.push_toast(status, level);
+                    }
+                    Level::Warning | Level::Error => {
+                        self.state.status = status;
+                        self.state.status_level = level;
+                    }
+                }
+                Task::none()
+            }
+            Message::ExpireToasts => {
+                let now = Instant::now();
+                self.state.toasts.retain(|toast| toast.expires_at > now);
+                Task::none()
+            }
+            Message::Exit => {
+                if !self.ui_state.kill_on_exit
+                    || (self.state.kill.is_none() && self.state.background.is_empty())
+                {
+                    return ::iced::exit();
+                }
+                if let Some(kill) = &self.state.kill {
+                    kill.send_modify(|generation| *generation += 1);
+                }
+                for background in &self.state.background {
+                    background.kill.send_modify(|generation| *generation += 1);
+                }
+                self.state.background_queue.clear();
+                self.state.exit_pending = true;
+                Task::done(Message::SetStatusLevel(
+                    "killing processes before exiting".into(),
+                    Level::Warning,
+                ))
+            }
+            Message::SetKillOnExit(kill_on_exit) => {
+                self.ui_state.kill_on_exit = kill_on_exit;
+                self.save_ui_state()
+            }
+            Message::SetRunOnSubmit(run_on_submit) => {
+                self.ui_state.run_on_submit = run_on_submit;
+                self.save_ui_state()
+            }
+            Message::ExeSubmitted => {
+                if self.ui_state.run_on_submit {
+                    return Task::done(Message::Run);
+                }
+                let config = match self.state.to_config() {
+                    Ok(config) => config,
+                    Err(err) => return Task::done(err.into()),
+                };
+                let (status, level) = if config.exe.is_empty() {
+                    ("no executable given".to_owned(), Level::Warning)
+                } else {
+                    match config.exe_exists() {
+                        Ok(true) => (format!("found executable {}", config.exe), Level::Success),
+                        Ok(false) => (
+                            format!("could not find executable {}", config.exe),
+                            Level::Warning,
+                        ),
+                        Err(err) => (err.to_string(), Level::Error),
+                    }
+                };
+                Task::done(Message::SetStatusLevel(status, level))
+            }
+            Message::RequestReload => {
+                if self
+                    .state
+                    .is_dirty(&self.config.with_profile(self.state.profile.as_deref()))
+                {
+                    self.state.confirm_reload = true;
+                    Task::none()
+                } else {
+                    Task::done(Message::Reload)
+                }
+            }
+            Message::CancelReload => {
+                self.state.confirm_reload = false;
+                self.state.pending_profile = None;
+                Task::none()
+            }
+            Message::SelectProfile(choice) => {
+                // Applied by the reload, once confirmed if there are unsaved
+                // changes.
+                self.state.pending_profile = Some(choice);
+                Task::done(Message::RequestReload)
+            }
+            Message::Reload => {
+                let save_ui_state = match self.state.pending_profile.take() {
+                    Some(ProfileChoice(profile)) => self.select_profile(profile),
+                    None => Task::none(),
+                };
+                let Self {
+                    theme: _,
+                    config,
+                    cli_config: _,
+                    state,
+                    config_path: _,
+                    config_inline: _,
+                    config_stdin: _,
+                    profile: _,
+                    skip: _,
+                    run_named: _,
+                    run_on_launch,
+                    auto_save: _,
+                    notify: _,
+                    notify_failure_only: _,
+                    watch: _,
+                    watch_restart: _,
+                    reopen: _,
+                    no_reopen: _,
+                    data_dir: _,
+                    ui_state: _,
+                    on_event: _,
+                } = self;
+                state.set_config(&config.with_profile(state.profile.as_deref()));
+                state.loaded = config.clone();
+                state.merged = None;
+                state.confirm_reload = false;
+
+                let (highlighter, invalid) = if config.highlight.is_empty() {
+                    Highlighter::new(&highlight::default_rules())
+                } else {
+                    Highlighter::new(&config.highlight)
+                };
+                state.output.highlight(&highlighter);
+                state.highlighter = highlighter;
+                let warn = if invalid.is_empty() {
+                    Task::none()
+                } else {
+                    for err in &invalid {
+                        ::log::warn!("{err}");
+                    }
+                    Task::done(Message::SetStatusLevel(
+                        format!("skipped {} invalid highlight rules", invalid.len()),
+                        Level::Warning,
+                    ))
+                };
+
+                // The first reload on launch follows loading the initial
+                // config, which is then ran if requested.
+                if ::std::mem::take(run_on_launch) {
+                    Task::batch([save_ui_state, warn, Task::done(Message::Run)])
+                } else {
+                    Task::batch([save_ui_state, warn])
+                }
+            }
+            Message::UpdateConfig(config) => {
+                let (config, path_buf) = *config;
+                self.state.provenance.record(&config, Source::File);
+                self.config = Config::load_onto(&self.cli_config, config);
+
+                // Remote configs are read only, saving requires a local path.
+                let remote = config::is_url(&path_buf);
+                self.state.config_path = (!remote).then(|| path_buf.clone());
+                self.state.config_modified = None;
+
+                let save_ui_state = if !remote
+                    && (self.ui_state.push_recent_config(&path_buf)
+                        || self.ui_state.last_config.as_ref() != Some(&path_buf))
+                {
+                    self.ui_state.last_config = Some(path_buf.clone());
+                    self.save_ui_state()
+                } else {
+                    Task::none()
+                };
+
+                let mut status = format!("loaded config {path_buf:?}");
+                let mut level = Level::Success;
+                // A profile given on the command line applies to the first
+                // config loaded.
+                let selected = self
+                    .profile
+                    .take()
+                    .or_else(|| self.ui_state.active_profile.get(&path_buf).cloned());
+                self.state.pending_profile = None;
+                self.state.profile = match selected {
+                    Some(name) if self.config.profile.contains_key(&name) => Some(name),
+                    Some(name) => {
+                        status = format!("{status}, profile {name} does not exist, using default");
+                        level = Level::Warning;
+                        None
+                    }
+                    None => None,
+                };
+                let invalid = self.config.palette.invalid();
+                if !invalid.is_empty() {
+                    for err in &invalid {
+                        ::log::warn!("{err}");
+                    }
+                    status = format!("{status}, {}, using theme colors", invalid.join(", "));
+                    level = Level::Warning;
+                }
+
+                Task::batch([
+                    Task::done(Message::SetStatusLevel(status, level)),
+                    Task::done(Message::Reload),
+                    save_ui_state,
+                ])
+            }
+            Message::LoadConfig(path_buf) => {
+                let description = format!("loading config {path_buf:?}");
+                let task = Task::future(Config::load(path_buf)).then(|result| match result {
+                    Ok(config) => Task::done(Message::UpdateConfig(Box::new(config))),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(Message::LoadFailed).chain(Task::done(err.into()))
+                    }
+                });
+                self.track_pending(description, task)
+            }
+            Message::LoadFailed => {
+                self.run_on_launch = false;
+                Task::none()
+            }
+            Message::SaveConfig(config) => {
+                let (config, path_buf) = *config;
+                Task::future(config.save(path_buf)).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::ConfigSaved(path_buf)),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                })
+            }
+            Message::ConfigSaved(path_buf) => {
+                let status = format!("saved config to {path_buf:?}");
+                self.state.config_path = Some(path_buf);
+                self.state.config_modified = None;
+                Task::done(Message::SetStatusLevel(status, Level::Success))
+            }
+            Message::ReopenLast => Task::done(match &self.ui_state.last_config {
+                Some(path_buf) if self.ui_state.reopen_last && path_buf.is_file() => {
+                    Message::LoadConfig(path_buf.clone())
+                }
+                _ => Message::Reload,
+            }),
+            Message::SetNotify(notify) => {
+                self.notify = notify;
+                Task::none()
+            }
+            Message::SetNotifyFailureOnly(notify_failure_only) => {
+                self.notify_failure_only = notify_failure_only;
+                Task::none()
+            }
+            Message::SetWatch(watch) => {
+                self.watch = watch;
+                self.state.config_modified = None;
+                self.state.change_seen = None;
+                Task::none()
+            }
+            Message::SetWatchRestart(watch_restart) => {
+                self.watch_restart = watch_restart;
+                Task::none()
+            }
+            Message::CheckConfigFile => {
+                let Some(path_buf) = self.state.config_path.clone() else {
+                    return Task::none();
+                };
+                Task::future(async move {
+                    ::tokio::fs::metadata(&path_buf)
+                        .await
+                        .and_then(|metadata| metadata.modified())
+                })
+                .then(|result| match result {
+                    Ok(modified) => Task::done(Message::ConfigFileModified(modified)),
+                    Err(err) => {
+                        ::log::warn!("could not check config file\n{err}");
+                        Task::none()
+                    }
+                })
+            }
+            Message::ConfigFileModified(modified) => {
+                match self.state.config_modified.replace(modified) {
+                    // First check after loading or saving only records time.
+                    None => Task::none(),
+                    Some(previous) if previous != modified => {
+                        self.state.change_seen = Some(Instant::now());
+                        Task::none()
+                    }
+                    Some(_) => match self.state.change_seen {
+                        Some(seen) if seen.elapsed() >= WATCH_DEBOUNCE => {
+                            self.state.change_seen = None;
+                            if self.state.kill.is_none() {
+                                self.load_and_run()
+                            } else if self.watch_restart {
+                                self.state.restart = true;
+                                Task::done(Message::Kill)
+                            } else {
+                                Task::done(
+                                    "config changed while running, skipping run"
+                                        .to_owned()
+                                        .into(),
+                                )
+                            }
+                        }
+                        _ => Task::none(),
+                    },
+                }
+            }
+            Message::SetAutoSave(auto_save) => {
+                self.auto_save = auto_save;
+                Task::none()
+            }
+            Message::EditConfig => {
+                let Some(path_buf) = self.state.config_path.clone() else {
+                    return Task::done("no config loaded or saved to edit".to_owned().into());
+                };
+                Task::future(editor::edit(path_buf)).then(|result| match result {
+                    Ok(Edited::Waited(path_buf)) => Task::done(Message::LoadConfig(path_buf)),
+                    Ok(Edited::Opened(path_buf)) => Task::done(
+                        format!("opened {path_buf:?}, load it again when done editing").into(),
+                    ),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                })
+            }
+            Message::LoadConfigDialog => {
+                let task = Task::future(Config::load_dialog()).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::LoadConfig(path_buf)),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                });
+                self.track_pending("load dialog", task)
+            }
+            Message::CompareConfigDialog => {
+                let task = Task::future(Config::load_dialog()).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::CompareConfig(path_buf)),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                });
+                self.track_pending("compare dialog", task)
+            }
+            Message::CompareConfig(path_buf) => {
+                let description = format!("loading config {path_buf:?}");
+                let task = Task::future(Config::load(path_buf)).then(|result| match result {
+                    Ok(config) => Task::done(Message::ConfigCompared(Box::new(config))),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                });
+                self.track_pending(description, task)
+            }
+            Message::ConfigCompared(config) => {
+                let (other, path) = *config;
+                let config = match self.state.to_config() {
+                    Ok(config) => config,
+                    Err(err) => return Task::done(err.into()),
+                };
+                self.state.comparison = Some(Comparison {
+                    differences: compare::diff(&config, &other),
+                    path,
+                });
+                Task::none()
+            }
+            Message::CloseComparison => {
+                self.state.comparison = None;
+                Task::none()
+            }
+            Message::MergeConfigDialog => {
+                let task = Task::future(Config::load_dialog()).then(|result| match result {
+                    Ok(path_buf) => Task::done(Message::MergeConfig(path_buf)),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                });
+                self.track_pending("merge dialog", task)
+            }
+            Message::MergeConfig(path_buf) => {
+                let description = format!("loading config {path_buf:?}");
+                let task = Task::future(Config::load(path_buf)).then(|result| match result {
+                    Ok(config) => Task::done(Message::ConfigMerged(Box::new(config))),
+                    Err(err) => {
+                        ::log::error!("{err}");
+                        Task::done(err.into())
+                    }
+                });
+                self.track_pending(description, task)
+            }
+            Message::ConfigMerged(config) => {
+                let (fragment, path_buf) = *config;
+                let mut config = match self.state.to_config() {
+                    Ok(config) => config,
+                    Err(err) => return Task::done(err.into()),
+                };
+                config.merge(fragment);
+                self.state.set_config(&config);
+                self.state.merged = Some(config);
+                Task::done(Message::SetStatusLevel(
+                    format!("merged config {path_buf:?}"),
+                    Level::Success,
+                ))
+            }
+            Message::SaveConfigDialog => {
+                let config = match self.state.to_config() {
+                    Ok(config) => self.state.for_save(config),
+                    Err(err) => return Task::done(err.into()),
+                };
+
+                let task = Task::future(async { (config, Config::save_dialog().await) }).then(
+                    |(config, result)| match result {
+                        Ok(path_buf) => {
+                            Task::done(Message::SaveConfig(Box::new((config, path_buf))))
+                        }
+                        Err(err) => Task::done(err.into()),
+                    },
+                );
+                self.track_pending("save dialog", task)
+            }
+        }
+    }
+
+    /// Track task as pending such that it may be cancelled by
+    /// [Message::CancelPending]. Only one task is pending at a time, a task
+    /// pending earlier is aborted and reported as cancelled.
+    fn track_pending(
+        &mut self,
+        description: impl Into<String>,
+        task: Task<Message>,
+    ) -> Task<Message> {
+        let (task, handle) = task.abortable();
+        let id = self.state.next_pending_id;
+        self.state.next_pending_id += 1;
+        let description = description.into();
+        let cancelled = match self.state.pending.replace(Pending {
+            id,
+            description: description.clone(),
+            handle,
+        }) {
+            Some(earlier) => {
+                earlier.handle.abort();
+                // As when cancelled, a replaced load on launch is not
+                // followed by a run.
+                self.run_on_launch = false;
+                let status = format!(
+                    "cancelled {}, replaced by {description}",
+                    earlier.description
+                );
+                ::log::info!("{status}");
+                Task::done(Message::SetStatusLevel(status, Level::Warning))
+            }
+            None => Task::none(),
+        };
+        cancelled.chain(task.chain(Task::done(Message::PendingFinished(id))))
+    }
+
+    /// Silently save config to the current config path, if there is one.
+    fn auto_save_config(&self, config: Config) -> Task<Message> {
+        let Some(path_buf) = self.state.config_path.clone() else {
+            ::log::info!("no config path set, skipping auto save");
+            return Task::none();
+        };
+        Task::future(config.save(path_buf)).then(|result| match result {
+            Ok(path_buf) => {
+                ::log::info!("auto saved config to {path_buf:?}");
+                Task::none()
+            }
+            Err(err) => {
+                ::log::error!("{err}");
+                Task::done(err.into())
+            }
+        })
+    }
+
+    /// Captured output as text for copying or saving.
+    fn output_text(&self) -> String {
+        if self.ui_state.strip_ansi {
+            output::strip_ansi(self.state.output.text())
+        } else {
+            self.state.output.text().to_owned()
+        }
+    }
+
+    /// Run config capturing its output, replacing any previous run.
+    fn run_config(&mut self, mut config: Config) -> Task<Message> {
+        self.state.run_count += 1;
+        // Output paths are rendered anew for each run, repeated runs should
+        // not overwrite the output of earlier ones.
+        self.state.last_run = Some(config.clone());
+        let unknown = config.resolve_output_paths(self.state.run_count);
+        if self.ui_state.echo_command {
+            self.state.push_output(
+                format!(
+                    "[{now}] $ {command_line}\n",
+                    now = Timestamp::now(),
+                    command_line = config.command_line(),
+                )
+                .as_bytes(),
+            );
+        }
+        // Output of earlier runs is kept, but not reported with this one.
+        self.state.run_output_start = self.state.output.end();
+        let (kill, kill_receiver) = ::tokio::sync::watch::channel(0);
+        let mut task =
+            Task::stream(config.run_captured(kill_receiver, self.ui_state.interactive_input));
+        if !unknown.is_empty() {
+            task = Task::batch([
+                task,
+                Task::done(Message::SetStatusLevel(
+                    unknown_placeholders(&unknown),
+                    Level::Warning,
+                )),
+            ]);
+        }
+        if cfg!(not(unix)) && config.arg0.is_some() {
+            task = Task::batch([
+                task,
+                Task::done(
+                    "argv[0] is only supported on unix, ignoring it"
+                        .to_owned()
+                        .into(),
+                ),
+            ]);
+        }
+        if cfg!(not(any(unix, windows))) && config.resource_usage {
+            task = Task::batch([
+                task,
+                Task::done(
+                    "resource usage is only supported on unix and windows, ignoring it"
+                        .to_owned()
+                        .into(),
+                ),
+            ]);
+        }
+        if cfg!(windows) && config.umask.is_some() {
+            task = Task::batch([
+                task,
+                Task::done(
+                    "umask is not supported on windows, ignoring it"
+                        .to_owned()
+                        .into(),
+                ),
+            ]);
+        }
+        self.emit(Event::RunStarted {
+            command_line: config.command_line(),
+        });
+        self.state.kill = Some(kill);
+        self.state.run_started = Some(SystemTime::now());
+        self.state.error_banner = None;
+        task
+    }
+
+    /// Start queued background runs while fewer than
+    /// [UiState::max_concurrent] are running.
+    fn start_queued(&mut self) -> Task<Message> {
+        let mut tasks = Vec::new();
+        while self
+            .ui_state
+            .max_concurrent
+            .is_none_or(|max| self.state.background.len() < max)
+            && let Some(Queued { id, config }) = self.state.background_queue.pop_front()
+        {
+            let (kill, kill_receiver) = ::tokio::sync::watch::channel(0);
+            self.state.background.push(Background {
+                id,
+                command: config.command_line(),
+                pid: None,
+                started: Instant::now(),
+                success_codes: config.success_codes.clone(),
+                kill,
+            });
+            tasks.push(Task::stream(config.run_background(id, kill_receiver)));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Exit task if exiting awaits processes being killed and none are left
+    /// running.
+    fn exit_when_stopped(&self) -> Option<Task<Message>> {
+        (self.state.exit_pending && self.state.kill.is_none() && self.state.background.is_empty())
+            .then(::iced::exit)
+    }
+
+    /// Select profile of config, remembering it for the config path if the
+    /// config was loaded from one.
+    fn select_profile(&mut self, profile: Option<String>) -> Task<Message> {
+        self.state.profile = profile;
+        let Some(path_buf) = &self.state.config_path else {
+            return Task::none();
+        };
+        match &self.state.profile {
+            Some(name) => {
+                self.ui_state
+                    .active_profile
+                    .insert(path_buf.clone(), name.clone());
+            }
+            None => {
+                self.ui_state.active_profile.remove(path_buf);
+            }
+        }
+        self.save_ui_state()
+    }
+
+    /// Persist ui state in the background.
+    fn save_ui_state(&self) -> Task<Message> {
+        Task::future(self.ui_state.clone().save()).then(|result| {
+            if let Err(err) = result {
+                ::log::error!("could not save ui state\n{err}");
+            }
+            Task::none()
+        })
+    }
+
+    /// Selected theme, given on the command line or selected in the ui.
+    fn selected_theme(&self) -> Theme {
+        self.theme.or(self.ui_state.theme).unwrap_or_default()
+    }
+
+    /// Application theme, with colors replaced by palette of config and
+    /// primary color replaced by accent of config if set.
+    pub fn theme(&self) -> ::iced::Theme {
+        let theme = ::iced::Theme::from(self.selected_theme());
+        if self.config.palette.is_empty() && self.config.accent.is_none() {
+            return theme;
+        }
+        let mut palette = self.config.palette.apply(theme.palette());
+        if let Some([r, g, b]) = self.config.accent {
+            palette.primary = Color::from_rgb8(r, g, b);
+        }
+        ::iced::Theme::custom(format!("{theme} Custom"), palette)
+    }
+
+    /// Show a notification about a finished run, if enabled.
+    fn notify_finished(&self, result: &Result<ExitStatus, String>) -> Task<Message> {
+        let success = matches!(result, Ok(status) if self.run_succeeded(*status));
+        if !self.notify || (success && self.notify_failure_only) {
+            return Task::none();
+        }
+        let exe = self
+            .state
+            .last_run
+            .as_ref()
+            .map(|config| {
+                ::std::path::Path::new(&config.exe).file_name().map_or_else(
+                    || config.exe.clone(),
+                    |name| name.to_string_lossy().into_owned(),
+                )
+            })
+            .unwrap_or_else(|| "process".to_owned());
+        let body = match result {
+            Ok(status) => format!("finished with {status}"),
+            Err(err) => err.clone(),
+        };
+        Task::future(notify::notify(exe, body)).then(|()| Task::none())
+    }
+
+    /// Take actions of last run for how it finished, configs are ran in the
+    /// background while other actions are taken in order.
+    fn post_actions(&mut self, result: &Result<ExitStatus, String>) -> Task<Message> {
+        let success = matches!(result, Ok(status) if self.run_succeeded(*status));
+        let Some(config) = &self.state.last_run else {
+            return Task::none();
+        };
+        let actions = if success {
+            config.on_success.clone()
+        } else {
+            config.on_failure.clone()
+        };
+        let (configs, actions): (Vec<_>, Vec<_>) = actions
+            .into_iter()
+            .partition(|action| matches!(action, PostAction::RunConfig(_)));
+        for action in configs {
+            if let PostAction::RunConfig(config) = action {
+                let id = self.state.next_background_id;
+                self.state.next_background_id += 1;
+                self.state.background_queue.push_back(Queued {
+                    id,
+                    config: *config,
+                });
+            }
+        }
+        let start_queued = self.start_queued();
+        if actions.is_empty() {
+            return start_queued;
+        }
+        let actions = Task::future(async move {
+            let mut errors = Vec::new();
+            for action in actions {
+                if let Err(err) = action.run().await {
+                    ::log::warn!("{err}");
+                    errors.push(err);
+                }
+            }
+            errors
+        })
+        .then(|errors| match errors.first() {
+            Some(err) => Task::done(Message::SetStatusLevel(err.clone(), Level::Warning)),
+            None => Task::none(),
+        });
+        Task::batch([start_queued, actions])
+    }
+
+    /// Check if last run finishing with status succeeded.
+    fn run_succeeded(&self, status: ExitStatus) -> bool {
+        self.state
+            .last_run
+            .as_ref()
+            .map_or_else(|| status.success(), |config| config.is_success(status))
+    }
+
+    /// Check if config at index of the run queue is running, in which case
+    /// it may not be moved or removed.
+    const fn is_queue_locked(&self, index: usize) -> bool {
+        self.state.queue_running && index == 0
+    }
+
+    /// Persist run queue in the background.
+    fn save_queue(&self) -> Task<Message> {
+        Task::future(self.state.run_queue.clone().save()).then(|result| match result {
+            Ok(()) => Task::none(),
+            Err(err) => {
+                ::log::error!("{err}");
+                Task::done(err.into())
+            }
+        })
+    }
+
+    /// Advance run queue if it is being processed, the finished config is
+    /// removed if it succeeded and the next one is ran. A failed run stops
+    /// processing, keeping its config first in the queue.
+    fn queue_finished(&mut self, result: &Result<ExitStatus, String>) -> Task<Message> {
+        if !self.state.queue_running {
+            return Task::none();
+        }
+        if !result
+            .as_ref()
+            .is_ok_and(|status| self.run_succeeded(*status))
+        {
+            self.state.queue_running = false;
+            return Task::done(Message::SetStatusLevel(
+                format!(
+                    "queue stopped by failed run, {} configs remaining",
+                    self.state.run_queue.configs.len()
+                ),
+                Level::Warning,
+            ));
+        }
+        if !self.state.run_queue.configs.is_empty() {
+            self.state.run_queue.configs.remove(0);
+        }
+        let save = self.save_queue();
+        if self.state.run_queue.configs.is_empty() {
+            self.state.queue_running = false;
+            return Task::batch([
+                save,
+                Task::done(Message::SetStatusLevel(
+                    "queue finished".into(),
+                    Level::Success,
+                )),
+            ]);
+        }
+        Task::batch([save, Task::done(Message::ProcessQueue)])
+    }
+
+    /// Append finished run to audit log of config, if set.
+    fn audit_finished(&self, result: &Result<ExitStatus, String>) -> Task<Message> {
+        let Some(config) = &self.state.last_run else {
+            return Task::none();
+        };
+        let Some(path_buf) = config.audit_log.clone() else {
+            return Task::none();
+        };
+        let started = self.state.run_started.unwrap_or_else(SystemTime::now);
+        let record = AuditRecord::new(config, started, result.clone());
+        Task::future(record.append(path_buf)).then(|result| match result {
+            Ok(()) => Task::none(),
+            Err(err) => {
+                ::log::warn!("{err}");
+                Task::done(err.into())
+            }
+        })
+    }
+
+    /// Add finished run to report.
+    fn report_finished(&mut self, result: &Result<ExitStatus, String>) {
+        let Some(config) = &self.state.last_run else {
+            return;
+        };
+        let duration = self
+            .state
+            .run_started
+            .and_then(|started| started.elapsed().ok())
+            .unwrap_or_default();
+        let entry = ReportEntry::new(
+            config.command_line(),
+            result.clone(),
+            duration,
+            &output::strip_ansi(self.state.output.text_since(self.state.run_output_start)),
+        );
+        self.state.report.push(entry);
+    }
+
+    /// Load config from its file, then run it.
+    fn load_and_run(&self) -> Task<Message> {
+        let Some(path_buf) = self.state.config_path.clone() else {
+            return Task::done(Message::Run);
+        };
+        Task::future(Config::load(path_buf)).then(|result| match result {
+            // Reload is sent explicitly such that state is updated before run.
+            Ok(config) => Task::done(Message::UpdateConfig(Box::new(config)))
+                .chain(Task::done(Message::Reload))
+                .chain(Task::done(Message::Run)),
+            Err(err) => {
+                ::log::error!("{err}");
+                Task::done(err.into())
+            }
+        })
+    }
+
+    /// Application subscriptions.
+    ///
+    /// Tab and Shift+Tab cycle focus between inputs, Ctrl+R runs and
+    /// Ctrl+Shift+R repeats the last run. Closing
+    /// the window exits. When watching the config file is checked for
+    /// changes.
+    pub fn subscription(&self) -> Subscription<Message> {
+        let keys = keyboard::on_key_press(|key, modifiers| match key.as_ref() {
+            Key::Named(Named::Tab) if modifiers.shift() => Some(Message::FocusPrevious),
+            Key::Named(Named::Tab) => Some(Message::FocusNext),
+            Key::Character("r" | "R") if modifiers.command() && modifiers.shift() => {
+                Some(Message::RepeatLast)
+            }
+            Key::Character("r") if modifiers.command() => Some(Message::Run),
+            Key::Character("k") if modifiers.command() => Some(Message::OpenSwitcher),
+            _ => None,
+        });
+        // Escape is captured by the focused switcher input, so events are
+        // listened to regardless of status.
+        let switcher = self.state.switcher.is_some().then(|| {
+            ::iced::event::listen_with(|event, _status, _window| match event {
+                ::iced::Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
+                    match key.as_ref() {
+                        Key::Named(Named::Escape) => Some(Message::CloseSwitcher),
+                        Key::Named(Named::ArrowUp) => Some(Message::MoveSwitcherSelection(-1)),
+                        Key::Named(Named::ArrowDown) => Some(Message::MoveSwitcherSelection(1)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+        });
+        let close = ::iced::window::close_requests().map(|_| Message::Exit);
+        let watch = (self.watch && self.state.config_path.is_some())
+            .then(|| ::iced::time::every(WATCH_INTERVAL).map(|_| Message::CheckConfigFile));
+        let elapsed = (self.state.kill.is_some() || !self.state.background.is_empty())
+            .then(|| ::iced::time::every(Duration::from_secs(1)).map(|_| Message::RefreshElapsed));
+        let toasts = (!self.state.toasts.is_empty())
+            .then(|| ::iced::time::every(TOAST_TICK).map(|_| Message::ExpireToasts));
+        Subscription::batch(
+            [keys, close]
+                .into_iter()
+                .chain(watch)
+                .chain(elapsed)
+                .chain(toasts)
+                .chain(switcher),
+        )
+    }
+
+    /// Render button of action.
+    fn view_action(&self, action: Action) -> Element<'_, Message> {
+        let button = match action {
+            Action::Save => button(action.label()).on_press_with(|| Message::SaveConfigDialog),
+            Action::Load => button(action.label()).on_press_with(|| Message::LoadConfigDialog),
+            Action::Merge => button(action.label()).on_press_with(|| Message::MergeConfigDialog),
+            Action::Copy => button(action.label()).on_press_with(|| Message::CopyConfig),
+            Action::Compare => {
+                button(action.label()).on_press_with(|| Message::CompareConfigDialog)
+            }
+            Action::Edit => button(action.label()).on_press_maybe(
+                self.state
+                    .config_path
+                    .is_some()
+                    .then_some(Message::EditConfig),
+            ),
+            Action::Reload => button(action.label()).on_press_with(|| Message::RequestReload),
+            Action::Cancel => button(action.label()).on_press_with(|| Message::Exit),
+            Action::Run if self.state.kill.is_some() => button("Kill")
+                .style(button::danger)
+                .on_press_with(|| Message::Kill),
+            Action::Run => button(action.label()).on_press_with(|| Message::Run),
+            Action::Restart => button(action.label()).on_press_with(|| Message::Restart),
+            Action::Background => button(action.label()).on_press_with(|| Message::RunBackground),
+        };
+        tip(button, action.help(), Position::Top)
+    }
+
+    /// Render overflow row, holding hidden actions and toggles for which
+    /// actions are shown in the bottom row.
+    fn view_overflow(&self) -> Element<'_, Message> {
+        let shown = self.ui_state.actions();
+        Row::new()
+            .spacing(3)
+            .align_y(Center)
+            .extend(
+                Action::ALL
+                    .into_iter()
+                    .filter(|action| !shown.contains(action))
+                    .map(|action| self.view_action(action)),
+            )
+            .push(widget::horizontal_space())
+            .push(tip(
+                text_input(
+                    "Max concurrent...",
+                    &self
+                        .ui_state
+                        .max_concurrent
+                        .map_or_else(String::new, |max| max.to_string()),
+                )
+                .on_input(Message::SetMaxConcurrent)
+                .width(120),
+                "Max background processes running at once, further runs are queued",
+                Position::Top,
+            ))
+            .push(tip(
+                widget::checkbox("Sources", self.state.show_sources)
+                    .on_toggle(Message::SetShowSources),
+                "Show whether config values came from the command line, a config file or edits",
+                Position::Top,
+            ))
+            .push(tip(
+                widget::checkbox("Stats", self.state.show_stats).on_toggle(Message::SetShowStats),
+                "Show durations of recent runs of this session",
+                Position::Top,
+            ))
+            .push(tip(
+                widget::checkbox("Queue", self.state.show_queue).on_toggle(Message::SetShowQueue),
+                "Show queue of configs ran one after another, kept between sessions",
+                Position::Top,
+            ))
+            .push(tip(
+                widget::checkbox("Library", self.state.show_library)
+                    .on_toggle(Message::SetShowLibrary),
+                "Show library of named configs stored in a single file",
+                Position::Top,
+            ))
+            .push(tip(
+                widget::checkbox("Browser", self.state.show_browser)
+                    .on_toggle(Message::SetShowBrowser),
+                "Show config files of a directory, click one to load it",
+                Position::Top,
+            ))
+            .push(tip(
+                button("Duplicate Window").on_press(Message::DuplicateWindow),
+                "Open another window with the current config, edits are not shared",
+                Position::Top,
+            ))
+            .push(tip(
+                button("Diagnostics").on_press(Message::ShowDiagnostics),
+                "Show version, platform and paths, for bug reports",
+                Position::Top,
+            ))
+            .extend(Action::ALL.into_iter().map(|action| {
+                widget::checkbox(action.label(), shown.contains(&action))
+                    .on_toggle(move |shown| Message::SetActionShown(action, shown))
+                    .into()
+            }))
+            .into()
+    }
+
+    /// Render list of background processes, each with a kill button,
+    /// followed by queued runs, each with a remove button.
+    fn view_background(&self) -> Element<'_, Message> {
+        Column::new()
+            .spacing(3)
+            .push(text(format!(
+                "{} running, {} queued",
+                self.state.background.len(),
+                self.state.background_queue.len()
+            )))
+            .extend(self.state.background.iter().map(|background| {
+                let pid = background
+                    .pid
+                    .map_or_else(|| "?".to_owned(), |pid| pid.to_string());
+                Row::new()
+                    .spacing(9)
+                    .align_y(Center)
+                    .push(text(pid).font(Font::MONOSPACE))
+                    .push(text(&background.command).font(Font::MONOSPACE).width(Fill))
+                    .push(text(format!("{}s", background.started.elapsed().as_secs())))
+                    .push(
+                        button("Kill")
+                            .style(button::danger)
+                            .on_press(Message::KillBackground(background.id)),
+                    )
+                    .into()
+            }))
+            .extend(self.state.background_queue.iter().map(|queued| {
+                Row::new()
+                    .spacing(9)
+                    .align_y(Center)
+                    .push(text("queued"))
+                    .push(
+                        text(queued.config.command_line())
+                            .font(Font::MONOSPACE)
+                            .width(Fill),
+                    )
+                    .push(button("Remove").on_press(Message::KillBackground(queued.id)))
+                    .into()
+            }))
+            .into()
+    }
+
+    /// Render quick switcher, listing recent configs matching its query.
+    fn view_switcher(&self, switcher: &Switcher) -> Element<'_, Message> {
+        let matches = switcher.matches(&self.ui_state.recent_configs);
+        let list = Column::new()
+            .spacing(3)
+            .extend(matches.into_iter().enumerate().map(|(index, path)| {
+                button(text(path.to_string_lossy().into_owned()).font(Font::MONOSPACE))
+                    .width(Fill)
+                    .style(if index == switcher.selected {
+                        button::primary
+                    } else {
+                        button::text
+                    })
+                    .on_press_with(|| Message::LoadConfig(path.to_path_buf()))
+                    .into()
+            }));
+        widget::container(
+            widget::container(
+                Column::new()
+                    .spacing(6)
+                    .push(
+                        text_input("Find recent config...", &switcher.query)
+                            .id(switcher_input_id())
+                            .on_input(Message::SetSwitcherQuery)
+                            .on_submit(Message::SubmitSwitcher),
+                    )
+                    .push(list),
+            )
+            .padding(6)
+            .max_width(450)
+            .style(widget::container::rounded_box),
+        )
+        .width(Fill)
+        .height(Fill)
+        .padding(40)
+        .align_x(Horizontal::Center)
+        .into()
+    }
+
+    /// Render a button per argument with a placeholder, picking a path for
+    /// it, [None] if there are no placeholders.
+    fn view_placeholders(&self) -> Option<Element<'_, Message>> {
+        let args = self.state.plain_args().ok()?;
+        let buttons = args
+            .iter()
+            .enumerate()
+            .filter_map(|(index, arg)| {
+                let pick = Pick::find(arg)?;
+                let help = match pick {
+                    Pick::File => "Pick a file to replace {{file}} with",
+                    Pick::Dir => "Pick a directory to replace {{dir}} with",
+                };
+                Some(tip(
+                    button(text(arg.clone()).font(Font::MONOSPACE))
+                        .on_press(Message::PickPlaceholder(index, pick)),
+                    help,
+                    Position::Top,
+                ))
+            })
+            .collect::<Vec<Element<Message>>>();
+        (!buttons.is_empty()).then(|| Row::new().spacing(3).extend(buttons).wrap().into())
+    }
+
+    /// Render config library, selecting a config loads it.
+    fn view_library(&self, library: &Library) -> Element<'_, Message> {
+        let selected = library
+            .configs
+            .contains_key(&self.state.library_name)
+            .then(|| self.state.library_name.clone());
+        Row::new()
+            .spacing(3)
+            .align_y(Center)
+            .push(
+                pick_list(library.names(), selected, Message::LoadLibraryEntry)
+                    .placeholder("Load from library..."),
+            )
+            .push(
+                text_input("Name...", &self.state.library_name)
+                    .on_input(Message::SetLibraryName)
+                    .on_submit(Message::SaveLibraryEntry),
+            )
+            .push(tip(
+                button("Save to Library").on_press(Message::SaveLibraryEntry),
+                "Save current config to the library, replacing any config with the same name",
+                Position::Top,
+            ))
+            .into()
+    }
+
+    /// Render config browser, listing config files of a directory with
+    /// their executables.
+    fn view_browser(&self) -> Element<'_, Message> {
+        let dir = self.ui_state.browser_dir.as_deref().map_or_else(
+            || "No directory".to_owned(),
+            |dir| dir.display().to_string(),
+        );
+        let header = Row::new()
+            .spacing(3)
+            .align_y(Center)
+            .push(text(dir).width(Fill))
+            .push(button("Choose Directory...").on_press(Message::BrowserDirDialog))
+            .push(
+                button("Refresh").on_press_maybe(
+                    self.ui_state
+                        .browser_dir
+                        .is_some()
+                        .then_some(Message::RefreshBrowser),
+                ),
+            );
+        let entries = self.state.browser.as_deref().unwrap_or_default();
+        let list = Column::with_children(entries.iter().map(|entry| {
+            let exe = match &entry.exe {
+                Some(exe) if exe.is_empty() => text("no executable"),
+                Some(exe) => text(exe).font(Font::MONOSPACE),
+                None => text("could not be read").style(text::danger),
+            };
+            button(
+                Row::new()
+                    .spacing(6)
+                    .push(text(entry.name()).width(Fill))
+                    .push(exe.size(12)),
+            )
+            .style(button::text)
+            .width(Fill)
+            .on_press_with(|| Message::LoadConfig(entry.path.clone()))
+            .into()
+        }));
+        Column::new()
+            .spacing(3)
+            .push(header)
+            .push_maybe(
+                (self.state.browser.is_some() && entries.is_empty())
+                    .then(|| text("No configs found")),
+            )
+            .push(widget::container(widget::scrollable(list)).max_height(150))
+            .into()
+    }
+
+    /// Render where values of set config fields came from, edited fields
+    /// are highlighted.
+    fn view_sources(&self) -> Element<'_, Message> {
+        let edited = self
+            .state
+            .to_config()
+            .ok()
+            .map(|config| self.state.for_save(config));
+        let sources = self
+            .state
+            .provenance
+            .sources(&self.state.loaded, edited.as_ref());
+        if sources.is_empty() {
+            return text("All values are defaults").into();
+        }
+        Column::with_children(sources.into_iter().map(|(key, source)| {
+            let line = text(format!("{key}: {source}")).size(12);
+            match source {
+                Source::Cli => line.style(text::primary),
+                Source::File => line,
+                Source::Edited => line.color(status::warning_color()),
+            }
+            .into()
+        }))
+        .into()
+    }
+
+    /// Render captured output, with lines colored by highlight rules if
+    /// enabled.
+    fn view_output_text(&self) -> Element<'_, Message> {
+        let output = &self.state.output;
+        if !self.ui_state.highlight_output {
+            return text(output.text()).font(Font::MONOSPACE).width(Fill).into();
+        }
+        let palette = self.theme().palette();
+        let spans = output
+            .lines()
+            .map(|(line, color)| {
+                let span = widget::span(line);
+                match color {
+                    Some(color) => span.color(color.resolve(&palette)),
+                    None => span,
+                }
+            })
+            .collect::<Vec<_>>();
+        widget::rich_text(spans)
+            .font(Font::MONOSPACE)
+            .width(Fill)
+            .into()
+    }
+
+    /// Render statistics and a bar chart of durations of recent runs.
+    fn view_stats(&self) -> Element<'_, Message> {
+        let runs = &self.state.report.runs;
+        let durations = runs[runs.len().saturating_sub(stats::MAX_RUNS)..]
+            .iter()
+            .map(|run| run.duration_secs)
+            .collect::<Vec<_>>();
+        let Some(summary) = Stats::new(&durations) else {
+            return text("No finished runs").into();
+        };
+        Column::new()
+            .spacing(3)
+            .push(text(summary.to_string()))
+            .push(
+                widget::canvas(DurationChart { durations })
+                    .width(Fill)
+                    .height(60),
+            )
+            .into()
+    }
+
+    /// Render run queue, each config with buttons to reorder and remove it.
+    fn view_queue(&self) -> Element<'_, Message> {
+        let configs = &self.state.run_queue.configs;
+        let header = Row::new()
+            .spacing(3)
+            .align_y(Center)
+            .push(
+                text(if self.state.queue_running {
+                    format!("Queue, processing {} configs", configs.len())
+                } else {
+                    format!("Queue, {} configs", configs.len())
+                })
+                .width(Fill),
+            )
+            .push(tip(
+                button("Add Current").on_press(Message::AddToQueue),
+                "Add current config to the end of the queue",
+                Position::Top,
+            ))
+            .push(tip(
+                button("Process Queue").on_press_maybe(
+                    (!self.state.queue_running && self.state.kill.is_none() && !configs.is_empty())
+                        .then_some(Message::ProcessQueue),
+                ),
+                "Run queued configs in order, each is removed once it succeeds and a \
+                failed run stops the queue",
+                Position::Top,
+            ))
+            .push(button("Discard").style(button::danger).on_press_maybe(
+                (!self.state.queue_running && !configs.is_empty()).then_some(Message::DiscardQueue),
+            ));
+        Column::new()
+            .spacing(3)
+            .push(header)
+            .extend(configs.iter().enumerate().map(|(index, config)| {
+                let locked = self.is_queue_locked(index);
+                let movable = |offset: isize| {
+                    let target = index.saturating_add_signed(offset);
+                    (!locked
+                        && target != index
+                        && target < configs.len()
+                        && !self.is_queue_locked(target))
+                    .then_some(Message::MoveQueued(index, offset))
+                };
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(text(format!("{}.", index + 1)).font(Font::MONOSPACE))
+                    .push(
+                        text(config.command_line())
+                            .font(Font::MONOSPACE)
+                            .width(Fill),
+                    )
+                    .push(button("Up").on_press_maybe(movable(-1)))
+                    .push(button("Down").on_press_maybe(movable(1)))
+                    .push(
+                        button("Remove")
+                            .on_press_maybe((!locked).then_some(Message::RemoveFromQueue(index))),
+                    )
+                    .into()
+            }))
+            .into()
+    }
+
+    /// Render comparison of current config with another, additions and
+    /// removals being colored.
+    fn view_comparison(&self, comparison: &Comparison) -> Element<'_, Message> {
+        let differences = comparison.differences.iter().map(|difference| {
+            let (change, style): (_, fn(&::iced::Theme) -> text::Style) = match &difference.change {
+                Change::Added(value) => (format!("+ {value}"), text::success),
+                Change::Removed(value) => (format!("- {value}"), text::danger),
+                Change::Changed(left, right) => (format!("{left} -> {right}"), text::primary),
+            };
+            Row::new()
+                .spacing(9)
+                .push(
+                    text(difference.field.clone())
+                        .font(Font::MONOSPACE)
+                        .width(150),
+                )
+                .push(text(change).font(Font::MONOSPACE).style(style).width(Fill))
+                .into()
+        });
+        let list: Element<Message> = if comparison.differences.is_empty() {
+            text("No differences").into()
+        } else {
+            widget::scrollable(Column::new().spacing(3).extend(differences)).into()
+        };
+        widget::container(
+            widget::container(
+                Column::new()
+                    .spacing(6)
+                    .push(
+                        text(format!(
+                            "Compared with {}",
+                            comparison.path.to_string_lossy()
+                        ))
+                        .size(18),
+                    )
+                    .push(list)
+                    .push(
+                        Row::new()
+                            .push(widget::horizontal_space())
+                            .push(button("Close").on_press(Message::CloseComparison)),
+                    ),
+            )
+            .padding(6)
+            .max_width(600)
+            .style(widget::container::rounded_box),
+        )
+        .width(Fill)
+        .height(Fill)
+        .padding(40)
+        .align_x(Horizontal::Center)
+        .into()
+    }
+
+    /// Render diagnostics panel.
+    fn view_diagnostics(&self, diagnostics: &Diagnostics) -> Element<'_, Message> {
+        widget::container(
+            widget::container(
+                Column::new()
+                    .spacing(6)
+                    .push(text("Diagnostics").size(18))
+                    .push(widget::scrollable(
+                        text(diagnostics.to_string()).font(Font::MONOSPACE),
+                    ))
+                    .push(
+                        Row::new()
+                            .spacing(3)
+                            .push(widget::horizontal_space())
+                            .push(button("Copy").on_press(Message::CopyDiagnostics))
+                            .push(button("Close").on_press(Message::CloseDiagnostics)),
+                    ),
+            )
+            .padding(6)
+            .max_width(600)
+            .style(widget::container::rounded_box),
+        )
+        .width(Fill)
+        .height(Fill)
+        .padding(40)
+        .align_x(Horizontal::Center)
+        .into()
+    }
+
+    /// Render toasts in the bottom right corner, fading out as they expire.
+    fn view_toasts(&self) -> Element<'_, Message> {
+        let now = Instant::now();
+        let toasts = self.state.toasts.iter().map(|toast| {
+            let remaining = toast.expires_at.saturating_duration_since(now);
+            let alpha = (remaining.as_secs_f32() / TOAST_FADE.as_secs_f32()).min(1.0);
+            let level = toast.level;
+            widget::container(text(&toast.text))
+                .padding(6)
+                .max_width(300)
+                .style(move |theme: &::iced::Theme| {
+                    let mut style = widget::container::rounded_box(theme);
+                    let color = match level {
+                        Level::Success => theme.palette().success,
+                        _ => theme.palette().text,
+                    };
+                    style.background = style
+                        .background
+                        .map(|background| background.scale_alpha(alpha));
+                    style.text_color = Some(color.scale_alpha(alpha));
+                    style
+                })
+                .into()
+        });
+        widget::container(
+            Column::new()
+                .spacing(3)
+                .align_x(Horizontal::Right)
+                .extend(toasts),
+        )
+        .width(Fill)
+        .height(Fill)
+        .padding([40, 10])
+        .align_x(Horizontal::Right)
+        .align_y(Vertical::Bottom)
+        .into()
+    }
+
+    /// Render status line, colored by its severity.
+    fn view_status(&self) -> widget::Text<'_> {
+        let status = text(&self.state.status).width(Fill);
+        match self.state.status_level {
+            Level::Info => status,
+            Level::Success => status.style(text::success),
+            Level::Warning => status.color(status::warning_color()),
+            Level::Error => status.style(text::danger),
+        }
+    }
+
+    /// Render arguments as a table.
+    fn view_args_table(&self) -> Element<'_, Message> {
+        let len = self.state.args_vec.len();
+        let rows = self.state.args_vec.iter().enumerate().map(|(index, arg)| {
+            Row::new()
+                .spacing(3)
+                .align_y(Center)
+                .push(
+                    text_input("Argument...", arg)
+                        .font(Font::MONOSPACE)
+                        .on_input(move |value| Message::EditArg(index, value)),
+                )
+                .push(tip(
+                    button("Up").on_press_maybe((index > 0).then_some(Message::MoveArgUp(index))),
+                    "Move argument up",
+                    Position::Top,
+                ))
+                .push(tip(
+                    button("Down")
+                        .on_press_maybe((index + 1 < len).then_some(Message::MoveArgDown(index))),
+                    "Move argument down",
+                    Position::Top,
+                ))
+                .push(tip(
+                    button("Remove").on_press(Message::RemoveArg(index)),
+                    "Remove argument",
+                    Position::Top,
+                ))
+                .into()
+        });
+
+        widget::scrollable(Column::with_children(rows).spacing(3).push(tip(
+            button("Add").on_press(Message::AddArg),
+            "Add an empty argument",
+            Position::Top,
+        )))
+        .width(Fill)
+        .height(Fill)
+        .into()
+    }
+
+    /// Render application.
+    pub fn view(&self) -> Element<'_, Message> {
+        let content = Column::new()
             .padding(5)
             .spacing(3)
             .width(Fill)
             .height(Fill)
             .align_x(Center)
+            .push_maybe(self.state.error_banner.as_deref().map(|banner| {
+                button(text(banner).width(Fill))
+                    .style(button::danger)
+                    .width(Fill)
+                    .on_press(Message::DismissBanner)
+            }))
+            .push(
+                Row::new()
+                    .align_y(Center)
+                    .spacing(3)
+                    .push(tip(
+                        text_input("Executable...", &self.state.exe)
+                            .id(exe_input_id())
+                            .on_input(Message::SetExe)
+                            .on_submit(Message::ExeSubmitted),
+                        "Executable to run, environment variables are expanded. \
+                         Enter checks that it can be found, or runs it if Run on Enter is set",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        button("Open").on_press_with(|| Message::ExeDialog),
+                        "Pick executable using a file dialog",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        pick_list(
+                            self.ui_state.arg_history(&self.state.exe),
+                            None::<String>,
+                            Message::SetArgs,
+                        )
+                        .placeholder("History")
+                        .width(90),
+                        "Arguments of earlier successful runs of executable",
+                        Position::Bottom,
+                    )),
+            )
+            .push(
+                Row::new()
+                    .align_y(Center)
+                    .spacing(3)
+                    .push(tip(
+                        text_input("Working directory...", &self.state.cwd)
+                            .on_input(Message::SetCwd),
+                        "Directory executable is ran in, empty for current directory",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        button("Browse").on_press_with(|| Message::CwdDialog),
+                        "Pick working directory using a file dialog",
+                        Position::Bottom,
+                    )),
+            )
             .push(
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(tip(
+                        text_input("Wrapper...", &self.state.wrapper)
+                            .font(Font::MONOSPACE)
+                            .on_input(Message::SetWrapper),
+                        "Command executable is launched through, such as gamemoderun",
+                        Position::Bottom,
+                    ))
+                    .push(
+                        widget::container(tip(
+                            text_input("argv[0]...", &self.state.arg0)
+                                .font(Font::MONOSPACE)
+                                .on_input(Message::SetArg0),
+                            "Name executable is invoked as, for multi-call binaries",
+                            Position::Bottom,
+                        ))
+                        .width(150),
+                    )
+                    .push(
+                        widget::container(tip(
+                            text_input("Locale...", &self.state.locale)
+                                .font(Font::MONOSPACE)
+                                .on_input(Message::SetLocale),
+                            "Locale set as LC_ALL and LANG, such as C for deterministic output",
+                            Position::Bottom,
+                        ))
+                        .width(100),
+                    )
+                    .push(tip(
+                        widget::checkbox("Debugger", self.state.use_debugger)
+                            .on_toggle(Message::SetUseDebugger),
+                        "Run under a debugger using the terminal the ui was started from",
+                        Position::Bottom,
+                    ))
+                    .push_maybe(self.state.use_debugger.then(|| {
+                        widget::container(
+                            text_input(DEFAULT_DEBUGGER, &self.state.debugger)
+                                .font(Font::MONOSPACE)
+                                .on_input(Message::SetDebugger),
+                        )
+                        .width(120)
+                    })),
+            )
+            .push_maybe((!self.config.profile.is_empty()).then(|| {
+                let choices = ::std::iter::once(ProfileChoice(None))
+                    .chain(
+                        self.config
+                            .profile
+                            .keys()
+                            .map(|name| ProfileChoice(Some(name.clone()))),
+                    )
+                    .collect::<Vec<_>>();
                 Row::new()
                     .align_y(Center)
                     .spacing(3)
-                    .push(text_input("Executable...", &self.state.exe).on_input(Message::SetExe))
-                    .push(button("Open").on_press_with(|| Message::ExeDialog)),
+                    .push(text("Profile"))
+                    .push(tip(
+                        pick_list(
+                            choices,
+                            Some(ProfileChoice(self.state.profile.clone())),
+                            Message::SelectProfile,
+                        )
+                        .width(Fill),
+                        "Profile of config to use, selecting one discards edits",
+                        Position::Bottom,
+                    ))
+            }))
+            .push(
+                Row::new()
+                    .spacing(9)
+                    .align_y(Center)
+                    .push(tip(
+                        widget::checkbox("Edit arguments as table", self.state.args_table)
+                            .on_toggle(Message::SetArgsTable),
+                        "Edit one argument per row instead of as shell quoted text",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Trim", self.state.trim_args)
+                            .on_toggle(Message::SetTrimArgs),
+                        "Trim whitespace of arguments and drop empty ones when running or saving",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Inline env", self.state.inline_env)
+                            .on_toggle(Message::SetInlineEnv),
+                        "Treat leading NAME=VALUE arguments as environment variables, up to a --, when no executable is set",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Token editor", self.ui_state.token_editor)
+                            .on_toggle_maybe(
+                                (!self.state.args_table).then_some(Message::SetTokenEditor),
+                            ),
+                        "Tab and Shift+Tab select arguments, Enter splits or joins them",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Scratch", self.state.show_scratch)
+                            .on_toggle(Message::SetShowScratch),
+                        "Show a text area for notes, never saved or ran",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Environment", self.state.show_env)
+                            .on_toggle(Message::SetShowEnv),
+                        "Show environment variables set for the executable",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        pick_list(Launch::ALL, Some(self.state.launch), Message::SetLaunch),
+                        "Launch executable directly, or as a flatpak app id or snap name",
+                        Position::Bottom,
+                    ))
+                    .push_maybe((!self.state.launch_apps.is_empty()).then(|| {
+                        pick_list(
+                            self.state.launch_apps.as_slice(),
+                            self.state
+                                .launch_apps
+                                .contains(&self.state.exe)
+                                .then(|| self.state.exe.clone()),
+                            Message::SetExe,
+                        )
+                        .placeholder("Installed...")
+                    }))
+                    .push(tip(
+                        widget::checkbox("Run through shell", self.state.shell)
+                            .on_toggle(Message::SetShell),
+                        "Run executable and arguments as a command line using the system shell",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Auto save", self.auto_save)
+                            .on_toggle(Message::SetAutoSave),
+                        "Save config after each successful run",
+                        Position::Bottom,
+                    ))
+                    .push(widget::horizontal_space())
+                    .push(tip(
+                        button("Add Files").on_press_with(|| Message::AddArgFilesDialog),
+                        "Pick files to append as arguments",
+                        Position::Bottom,
+                    )),
+            )
+            .push(
+                Row::new()
+                    .spacing(9)
+                    .push(tip(
+                        widget::checkbox("Watch", self.watch).on_toggle(Message::SetWatch),
+                        "Reload and run config when its file changes",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Restart", self.watch_restart)
+                            .on_toggle_maybe(self.watch.then_some(Message::SetWatchRestart)),
+                        "Kill and restart a running process when config changes",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Notify", self.notify).on_toggle(Message::SetNotify),
+                        "Show a desktop notification when a run finishes",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Only failures", self.notify_failure_only)
+                            .on_toggle_maybe(self.notify.then_some(Message::SetNotifyFailureOnly)),
+                        "Only notify when a run fails",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Kill on exit", self.ui_state.kill_on_exit)
+                            .on_toggle(Message::SetKillOnExit),
+                        "Kill running process when closing the application",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Run on Enter", self.ui_state.run_on_submit)
+                            .on_toggle(Message::SetRunOnSubmit),
+                        "Run when Enter is pressed in the executable input, \
+                         instead of checking that it can be found",
+                        Position::Bottom,
+                    ))
+                    .push(widget::horizontal_space())
+                    .push(tip(
+                        pick_list(Theme::ALL, Some(self.selected_theme()), Message::SetTheme),
+                        "Theme of application, remembered between sessions",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        pick_list(LogLevel::ALL, LogLevel::current(), Message::SetLogLevel)
+                            .placeholder("Log level"),
+                        "Level of application logging",
+                        Position::Bottom,
+                    )),
             )
             .push(
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(text("Arguments").width(Fill))
+                    .push(tip(
+                        pick_list(
+                            PasteMode::ALL,
+                            Some(self.ui_state.paste_mode),
+                            Message::SetPasteMode,
+                        ),
+                        "How pasted text is split into arguments",
+                        Position::Bottom,
+                    ))
+                    .push(tip(
+                        button("Paste").on_press(Message::PasteArgs),
+                        "Append arguments from clipboard",
+                        Position::Bottom,
+                    )),
+            )
+            .push(if self.state.args_table {
+                self.view_args_table()
+            } else if self.ui_state.token_editor {
+                text_editor(&self.state.args)
+                    .on_action(Message::EditArgs)
+                    .key_binding(token_key_binding)
+                    .highlight_with::<TokenHighlighter>((), Highlight::to_format)
+                    .font(Font::MONOSPACE)
+                    .height(Fill)
+                    .into()
+            } else {
                 text_editor(&self.state.args)
                     .on_action(Message::EditArgs)
                     .font(Font::MONOSPACE)
-                    .height(Fill),
+                    .height(Fill)
+                    .into()
+            })
+            .push_maybe(self.view_placeholders())
+            .push_maybe(self.state.show_scratch.then(|| {
+                text_editor(&self.state.scratch)
+                    .on_action(Message::EditScratch)
+                    .placeholder("Scratch, not saved or passed to the process")
+                    .font(Font::MONOSPACE)
+                    .height(80)
+            }))
+            .push_maybe(self.state.show_env.then(|| {
+                text_editor(&self.state.env)
+                    .on_action(Message::EditEnv)
+                    .placeholder("NAME=VALUE, one per line, values may reference ${NAME}")
+                    .font(Font::MONOSPACE)
+                    .height(80)
+            }))
+            .push_maybe(self.state.show_env.then(|| {
+                tip(
+                    text_input("Unset...", &self.state.env_unset)
+                        .font(Font::MONOSPACE)
+                        .on_input(Message::SetEnvUnset),
+                    "Names of inherited variables to remove, separated by spaces, such as LD_PRELOAD",
+                    Position::Bottom,
+                )
+            }))
+            .push_maybe(
+                (self.state.show_env && !self.config.env_from_file.is_empty()).then(|| {
+                    Column::with_children(self.config.env_from_file.iter().map(|entry| {
+                        tip(
+                            text(format!("{}=(from file)", entry.name)).font(Font::MONOSPACE),
+                            entry
+                                .from_file
+                                .to_str()
+                                .unwrap_or("path is not valid utf-8"),
+                            Position::Bottom,
+                        )
+                    }))
+                    .width(Fill)
+                }),
+            )
+            .push_maybe((!self.state.tagged_args.is_empty()).then(|| {
+                Column::with_children(self.state.tagged_args.iter().enumerate().map(
+                    |(index, TaggedArg { arg, .. })| {
+                        let label = if arg.is_current() {
+                            arg.value.clone()
+                        } else {
+                            format!("{} ({:?} only)", arg.value, arg.os)
+                        };
+                        let toggle = widget::checkbox(label, arg.enabled)
+                            .on_toggle(move |enabled| Message::SetArgEnabled(index, enabled))
+                            .font(Font::MONOSPACE);
+                        match &arg.desc {
+                            Some(desc) => tip(toggle, desc, Position::Bottom),
+                            None => toggle.into(),
+                        }
+                    },
+                ))
+                .spacing(3)
+                .width(Fill)
+            }))
+            .push_maybe(
+                self.state
+                    .config_path
+                    .as_deref()
+                    .and_then(|path| self.ui_state.run_stats(path))
+                    .map(|stats| {
+                        text(format!(
+                            "Ran {count} times, last at {last}",
+                            count = stats.run_count,
+                            last = stats.last_run.as_deref().unwrap_or("unknown"),
+                        ))
+                        .size(12)
+                        .width(Fill)
+                    }),
+            )
+            .push(
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(text("Output"))
+                    .push(
+                        text(
+                            self.state
+                                .kill
+                                .as_ref()
+                                .and(self.state.run_started)
+                                .and_then(|started| started.elapsed().ok())
+                                .map(|elapsed| format!("running for {}s", elapsed.as_secs()))
+                                .unwrap_or_default(),
+                        )
+                        .size(12)
+                        .width(Fill),
+                    )
+                    .push(tip(
+                        widget::checkbox("Interactive", self.ui_state.interactive_input)
+                            .on_toggle(Message::SetInteractiveInput),
+                        "Allow typing input to the process, otherwise it reads no input",
+                        Position::Top,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Echo command", self.ui_state.echo_command)
+                            .on_toggle(Message::SetEchoCommand),
+                        "Write command line and time to output before each run",
+                        Position::Top,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Save raw", self.ui_state.save_raw_output)
+                            .on_toggle(Message::SetSaveRawOutput),
+                        "Save output as the bytes written by the process",
+                        Position::Top,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Strip ANSI", self.ui_state.strip_ansi)
+                            .on_toggle(Message::SetStripAnsi),
+                        "Remove terminal escape sequences when copying or saving output",
+                        Position::Top,
+                    ))
+                    .push(tip(
+                        widget::checkbox("Highlight", self.ui_state.highlight_output)
+                            .on_toggle(Message::SetHighlightOutput),
+                        "Color lines of output matching highlight rules of config, \
+                        by default lines with errors and warnings",
+                        Position::Top,
+                    ))
+                    .push(tip(
+                        button("Copy Output").on_press_with(|| Message::CopyOutput),
+                        "Copy output to clipboard",
+                        Position::Top,
+                    ))
+                    .push(tip(
+                        button("Save Output").on_press_with(|| Message::SaveOutputDialog),
+                        "Save output to a file",
+                        Position::Top,
+                    ))
+                    .push(tip(
+                        button("Export Report").on_press_maybe(
+                            (!self.state.report.is_empty()).then_some(Message::ExportReportDialog),
+                        ),
+                        "Export command, exit code, duration and output tail of finished runs, \
+                        as JSON for .json files and Markdown otherwise",
+                        Position::Top,
+                    )),
+            )
+            .push(
+                widget::Stack::with_children([widget::scrollable(
+                    Column::new()
+                        .push_maybe(
+                            self.state
+                                .output
+                                .is_truncated()
+                                .then(|| text(output::TRUNCATED_MARKER).font(Font::MONOSPACE)),
+                        )
+                        .push(self.view_output_text()),
+                )
+                .id(output_scrollable_id())
+                .on_scroll(Message::OutputScrolled)
+                .width(Fill)
+                .height(Fill)
+                .into()])
+                .extend(self.state.output_scrolled_up.then(|| {
+                    widget::container(tip(
+                        button("Jump to Bottom").on_press(Message::OutputToBottom),
+                        "Scroll to the end of output and follow new lines",
+                        Position::Top,
+                    ))
+                    .width(Fill)
+                    .height(Fill)
+                    .padding(10)
+                    .align_x(Horizontal::Right)
+                    .align_y(Vertical::Bottom)
+                    .into()
+                }))
+                .width(Fill)
+                .height(Fill),
+            )
+            .push_maybe(self.state.input.as_ref().map(|_| {
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(
+                        text_input("Input...", &self.state.input_line)
+                            .font(Font::MONOSPACE)
+                            .on_input(Message::SetInputLine)
+                            .on_submit(Message::SendInput(self.state.input_line.clone())),
+                    )
+                    .push(tip(
+                        button("Close Input").on_press(Message::CloseInput),
+                        "Close stdin of process, signalling end of input",
+                        Position::Top,
+                    ))
+            }))
+            .push_maybe(
+                (!self.state.background.is_empty() || !self.state.background_queue.is_empty())
+                    .then(|| self.view_background()),
+            )
+            .push_maybe(self.state.confirm_reload.then(|| {
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(text("Discard unsaved changes?").width(Fill))
+                    .push(
+                        button("Discard")
+                            .style(button::danger)
+                            .on_press_with(|| Message::Reload),
+                    )
+                    .push(button("Keep").on_press_with(|| Message::CancelReload))
+            }))
+            .push_maybe(self.state.confirm_run.then(|| {
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(text(format!("Run {}?", self.state.exe)).width(Fill))
+                    .push(
+                        button("Run")
+                            .style(button::danger)
+                            .on_press_with(|| Message::ConfirmRun),
+                    )
+                    .push(button("Cancel").on_press_with(|| Message::CancelRun))
+            }))
+            .push_maybe(self.state.resume_queue.then(|| {
+                Row::new()
+                    .spacing(3)
+                    .align_y(Center)
+                    .push(
+                        text(format!(
+                            "Resume queue of {} configs?",
+                            self.state.run_queue.configs.len()
+                        ))
+                        .width(Fill),
+                    )
+                    .push(button("Resume").on_press_with(|| Message::ProcessQueue))
+                    .push(button("Later").on_press_with(|| Message::SetShowQueue(true)))
+                    .push(
+                        button("Discard")
+                            .style(button::danger)
+                            .on_press_with(|| Message::DiscardQueue),
+                    )
+            }))
+            .push_maybe(
+                self.state
+                    .library
+                    .as_ref()
+                    .filter(|_| self.state.show_library)
+                    .map(|library| self.view_library(library)),
             )
+            .push_maybe(self.state.show_browser.then(|| self.view_browser()))
+            .push_maybe(self.state.show_queue.then(|| self.view_queue()))
+            .push_maybe(self.state.show_stats.then(|| self.view_stats()))
+            .push_maybe(self.state.show_sources.then(|| self.view_sources()))
+            .push_maybe(self.state.show_overflow.then(|| self.view_overflow()))
             .push(
                 Row::new()
                     .spacing(3)
                     .align_y(Center)
-                    .push(text(&self.state.status).width(Fill))
-                    .push(button("Save").on_press_with(|| Message::SaveConfigDialog))
-                    .push(button("Load").on_press_with(|| Message::LoadConfigDialog))
-                    .push(button("Reload").on_press_with(|| Message::Reload))
-                    .push(button("Cancel").on_press_with(|| Message::Exit))
-                    .push(button("Run").on_press_with(|| Message::Run)),
+                    .push(self.view_status())
+                    .push_maybe(self.state.pending.as_ref().map(|pending| {
+                        Row::new()
+                            .spacing(3)
+                            .align_y(Center)
+                            .push(text(format!("{}\u{2026}", pending.description)))
+                            .push(button("Cancel").on_press(Message::CancelPending))
+                    }))
+                    .extend(
+                        self.ui_state
+                            .actions()
+                            .iter()
+                            .map(|action| self.view_action(*action)),
+                    )
+                    .push(tip(
+                        button("\u{22ef}").on_press(Message::ToggleOverflow),
+                        "More actions, and which actions are shown",
+                        Position::Top,
+                    )),
+            );
+
+        widget::Stack::with_children([content.into(), self.view_toasts()])
+            .extend(
+                self.state
+                    .switcher
+                    .as_ref()
+                    .map(|switcher| self.view_switcher(switcher)),
+            )
+            .extend(
+                self.state
+                    .comparison
+                    .as_ref()
+                    .map(|comparison| self.view_comparison(comparison)),
+            )
+            .extend(
+                self.state
+                    .diagnostics
+                    .as_ref()
+                    .map(|diagnostics| self.view_diagnostics(diagnostics)),
             )
             .into()
     }
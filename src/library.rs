@@ -0,0 +1,103 @@
+//! [Library] impl.
+
+use ::std::{collections::BTreeMap, path::PathBuf};
+
+use ::serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{Config, LoadError, SaveError},
+    paths,
+};
+
+/// Named configs stored together in a single file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Library {
+    /// Configs keyed by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub configs: BTreeMap<String, Config>,
+}
+
+impl Library {
+    /// Path library is stored at, if any.
+    pub fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join("library.toml"))
+    }
+
+    /// Load library, a missing file results in an empty library.
+    ///
+    /// # Errors
+    /// If library exists but cannot be read [LoadError::Read] is returned.
+    /// If library cannot be deserialized [LoadError::Deserialize] is returned.
+    pub async fn load() -> Result<Self, LoadError> {
+        paths::load(Self::path()).await
+    }
+
+    /// Save library.
+    ///
+    /// # Errors
+    /// If library cannot be serialized [SaveError::Serialize] is returned.
+    /// If library cannot be written [SaveError::Write] is returned.
+    pub async fn save(self) -> Result<(), SaveError> {
+        paths::save(&self, Self::path()).await
+    }
+
+    /// Names of configs, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.configs.keys().cloned().collect()
+    }
+
+    /// Take config named name with cli, the values given on the command
+    /// line, merged into it such that they take precedence, see
+    /// [Config::merge]. [None] if there is no config named name.
+    pub fn take_with(&mut self, name: &str, cli: Config) -> Option<Config> {
+        let mut config = self.configs.remove(name)?;
+        config.merge(cli);
+        Some(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of running library configs.
+
+    use super::*;
+
+    /// Values given on the command line replace those of the named config,
+    /// the rest of which is kept.
+    #[test]
+    fn take_with_cli() {
+        let mut library: Library = ::toml::from_str(
+            r#"
+            [configs.build]
+            exe = "make"
+            arg = ["all"]
+            env = { A = "library" }
+            "#,
+        )
+        .unwrap();
+        let cli: Config = ::toml::from_str(
+            r#"
+            arg = ["test"]
+            env = { A = "cli" }
+            shell = true
+            "#,
+        )
+        .unwrap();
+
+        assert!(library.take_with("missing", cli.clone()).is_none());
+        let config = library.take_with("build", cli).unwrap();
+        assert_eq!(config.exe, "make");
+        assert_eq!(
+            config
+                .arg
+                .iter()
+                .map(|arg| arg.value.as_str())
+                .collect::<Vec<_>>(),
+            ["test"]
+        );
+        assert_eq!(config.env, [("A".to_owned(), "cli".to_owned())]);
+        assert!(config.shell);
+        assert!(library.configs.is_empty());
+    }
+}
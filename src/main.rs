@@ -10,5 +10,6 @@ fn main() -> ::color_eyre::Result<()> {
         .filter_module("command_runner", LevelFilter::Debug)
         .init();
 
-    Cli::parse().run()
+    let code = Cli::parse().run()?;
+    ::std::process::exit(code)
 }
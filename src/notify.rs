@@ -0,0 +1,20 @@
+//! Desktop notifications.
+
+/// Show a desktop notification. Notifications are not supported everywhere,
+/// as such failures are only logged.
+pub async fn notify(summary: String, body: String) {
+    let result = ::tokio::task::spawn_blocking(move || {
+        ::notify_rust::Notification::new()
+            .appname("command-runner")
+            .summary(&summary)
+            .body(&body)
+            .show()
+            .map(drop)
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => ::log::info!("could not show notification\n{err}"),
+        Err(err) => ::log::warn!("notification task failed\n{err}"),
+    }
+}
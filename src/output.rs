@@ -0,0 +1,371 @@
+//! Captured output helpers.
+
+use ::std::{borrow::Cow, collections::VecDeque, path::PathBuf};
+
+use ::rfd::AsyncFileDialog;
+
+use crate::{
+    Message,
+    highlight::{Highlighter, LineColor},
+    status::Level,
+};
+
+/// Default max amount of bytes of captured output kept in memory.
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Marker shown before output when older lines have been dropped.
+pub const TRUNCATED_MARKER: &str = "\u{2026} output truncated \u{2026}";
+
+/// A line of captured output, as streamed by
+/// [Config::run_with_callback][crate::config::Config::run_with_callback].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    /// Line as raw bytes, including its line terminator if it has one.
+    raw: Vec<u8>,
+}
+
+impl OutputLine {
+    /// Create a line from raw bytes, which should include the line
+    /// terminator if the line has one.
+    pub const fn new(raw: Vec<u8>) -> Self {
+        Self { raw }
+    }
+
+    /// Line as raw bytes, including its line terminator if it has one.
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// Take raw bytes of line.
+    pub fn into_raw(self) -> Vec<u8> {
+        self.raw
+    }
+
+    /// Line decoded lossily as utf-8, without its line terminator.
+    pub fn text(&self) -> Cow<'_, str> {
+        let text = self.raw.strip_suffix(b"\n").unwrap_or(&self.raw);
+        let text = text.strip_suffix(b"\r").unwrap_or(text);
+        String::from_utf8_lossy(text)
+    }
+}
+
+impl From<String> for OutputLine {
+    fn from(value: String) -> Self {
+        Self::new(value.into_bytes())
+    }
+}
+
+/// A line kept by [OutputBuffer].
+#[derive(Debug, Clone, Copy)]
+struct BufferedLine {
+    /// Length of line in text, including its newline.
+    text: usize,
+    /// Length of line in raw bytes.
+    raw: usize,
+    /// Color line is highlighted with, if any.
+    color: Option<LineColor>,
+}
+
+/// Captured output bounded in size, when full the oldest lines are dropped.
+///
+/// Lines are highlighted once as they are pushed, such that rendering does
+/// not need to match every line again.
+#[derive(Debug)]
+pub struct OutputBuffer {
+    /// Output decoded lossily as utf-8.
+    text: String,
+    /// Output as raw bytes.
+    raw: Vec<u8>,
+    /// Kept lines, oldest first.
+    lines: VecDeque<BufferedLine>,
+    /// Max amount of raw bytes kept.
+    max_bytes: usize,
+    /// Set if lines have been dropped.
+    truncated: bool,
+    /// Amount of text dropped with older lines.
+    dropped: usize,
+}
+
+impl Default for OutputBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_OUTPUT_BYTES)
+    }
+}
+
+impl OutputBuffer {
+    /// Create an empty buffer keeping at most max_bytes of raw output.
+    pub const fn new(max_bytes: usize) -> Self {
+        Self {
+            text: String::new(),
+            raw: Vec::new(),
+            lines: VecDeque::new(),
+            max_bytes,
+            truncated: false,
+            dropped: 0,
+        }
+    }
+
+    /// Output decoded lossily as utf-8.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Position after all text pushed so far, which stays valid when older
+    /// lines are dropped.
+    pub const fn end(&self) -> usize {
+        self.dropped + self.text.len()
+    }
+
+    /// Text pushed after position, see [OutputBuffer::end]. If lines pushed
+    /// after position have been dropped, the text that is kept.
+    pub fn text_since(&self, position: usize) -> &str {
+        let start = position.saturating_sub(self.dropped).min(self.text.len());
+        &self.text[start..]
+    }
+
+    /// Output as raw bytes.
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// Lines of output decoded lossily as utf-8, including their newline,
+    /// with the color they are highlighted with.
+    pub fn lines(&self) -> impl Iterator<Item = (&str, Option<LineColor>)> {
+        self.lines.iter().scan(0, |start, line| {
+            let text = &self.text[*start..*start + line.text];
+            *start += line.text;
+            Some((text, line.color))
+        })
+    }
+
+    /// Check if lines have been dropped.
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Change max amount of raw bytes kept, dropping lines if needed.
+    pub fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = max_bytes;
+        self.shrink();
+    }
+
+    /// Append a line highlighted by highlighter, line should include its
+    /// line terminator if it has one.
+    pub fn push_line(&mut self, line: &[u8], highlighter: &Highlighter) {
+        let text = line.strip_suffix(b"\n").unwrap_or(line);
+        let text = text.strip_suffix(b"\r").unwrap_or(text);
+        let text = String::from_utf8_lossy(text);
+        let color = highlighter.color(&text);
+        self.text.push_str(&text);
+        self.text.push('\n');
+
+        self.raw.extend_from_slice(line);
+        self.lines.push_back(BufferedLine {
+            text: text.len() + 1,
+            raw: line.len(),
+            color,
+        });
+
+        if self.raw.len() > self.max_bytes {
+            self.shrink();
+        }
+    }
+
+    /// Highlight kept lines again, such as when highlight rules change.
+    pub fn highlight(&mut self, highlighter: &Highlighter) {
+        let mut start = 0;
+        for line in &mut self.lines {
+            let end = start + line.text;
+            line.color = highlighter.color(&self.text[start..end - 1]);
+            start = end;
+        }
+    }
+
+    /// Drop oldest lines until at most three quarters of max bytes are used,
+    /// such that dropping happens in chunks rather than for every line.
+    fn shrink(&mut self) {
+        if self.raw.len() <= self.max_bytes {
+            return;
+        }
+        let target = self.max_bytes - self.max_bytes / 4;
+        let (mut text_len, mut raw_len) = (0, 0);
+        while self.raw.len() - raw_len > target {
+            let Some(line) = self.lines.pop_front() else {
+                break;
+            };
+            text_len += line.text;
+            raw_len += line.raw;
+        }
+        self.text.drain(..text_len);
+        self.raw.drain(..raw_len);
+        self.dropped += text_len;
+        self.truncated = true;
+    }
+}
+
+/// Error raised when captured output cannot be saved.
+#[derive(Debug, ::thiserror::Error)]
+pub enum SaveOutputError {
+    /// Writing of output failed.
+    #[error("could not write output to {path:?}\n{source}")]
+    Write {
+        /// Path that could not be written.
+        path: PathBuf,
+        /// Error source.
+        #[source]
+        source: ::std::io::Error,
+    },
+
+    /// No file was selected.
+    #[error("no file selected using dialog")]
+    NoneSelected,
+}
+
+impl From<SaveOutputError> for Message {
+    fn from(value: SaveOutputError) -> Self {
+        match value {
+            SaveOutputError::Write { path, source: _ } => {
+                Message::SetStatusLevel(format!("could not write {path:?}"), Level::Error)
+            }
+            SaveOutputError::NoneSelected => {
+                Message::SetStatusLevel("no path entered".into(), Level::Warning)
+            }
+        }
+    }
+}
+
+/// Save captured output.
+///
+/// # Errors
+/// If output cannot be written [SaveOutputError::Write] is returned.
+pub async fn save(content: Vec<u8>, path: PathBuf) -> Result<PathBuf, SaveOutputError> {
+    match ::tokio::fs::write(&path, &content).await {
+        Ok(_) => Ok(path),
+        Err(source) => Err(SaveOutputError::Write { path, source }),
+    }
+}
+
+/// Save output dialog.
+///
+/// # Errors
+/// If nothing was selected [SaveOutputError::NoneSelected] is returned.
+pub async fn save_dialog() -> Result<PathBuf, SaveOutputError> {
+    match AsyncFileDialog::new()
+        .set_title("Save Output")
+        .add_filter("Log", &["log", "txt"])
+        .save_file()
+        .await
+    {
+        Some(handle) => Ok(handle.path().to_path_buf()),
+        None => Err(SaveOutputError::NoneSelected),
+    }
+}
+
+/// Strip ANSI escape sequences from text.
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Control sequence, ends with a byte in the range @ to ~.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // Operating system command, ends with BEL or ST.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' {
+                        break;
+                    }
+                    if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Escape sequence with intermediate bytes, ends with a final byte.
+            Some(c) if (' '..='/').contains(&c) => {
+                for c in chars.by_ref() {
+                    if !(' '..='/').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of captured output.
+
+    use super::*;
+    use crate::highlight;
+
+    /// Lines are highlighted as they are pushed and keep their color when
+    /// older lines are dropped.
+    #[test]
+    fn highlighted_lines() {
+        let (highlighter, invalid) = Highlighter::new(&highlight::default_rules());
+        assert!(invalid.is_empty());
+        let mut output = OutputBuffer::new(40);
+        output.push_line(b"error: first\n", &highlighter);
+        output.push_line(b"plain\r\n", &highlighter);
+        output.push_line(b"warning: last", &highlighter);
+
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                ("error: first\n", Some(LineColor::Level(Level::Error))),
+                ("plain\n", None),
+                ("warning: last\n", Some(LineColor::Level(Level::Warning))),
+            ]
+        );
+
+        output.push_line(b"more output\n", &highlighter);
+        assert!(output.is_truncated());
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                ("warning: last\n", Some(LineColor::Level(Level::Warning))),
+                ("more output\n", None),
+            ]
+        );
+
+        output.highlight(&Highlighter::default());
+        assert!(output.lines().all(|(_, color)| color.is_none()));
+    }
+
+    /// Text since a position includes only lines pushed after it, also once
+    /// older lines have been dropped.
+    #[test]
+    fn text_since_run_start() {
+        let highlighter = Highlighter::default();
+        let mut output = OutputBuffer::new(40);
+        output.push_line(b"first run\n", &highlighter);
+        let start = output.end();
+        assert_eq!(output.text_since(start), "");
+        output.push_line(b"second run\n", &highlighter);
+        assert_eq!(output.text_since(start), "second run\n");
+        assert_eq!(output.text_since(0), "first run\nsecond run\n");
+
+        output.push_line(b"second run, more output\n", &highlighter);
+        assert!(output.is_truncated());
+        assert_eq!(output.text_since(start), "second run, more output\n");
+        let start = output.end();
+        output.push_line(b"third\n", &highlighter);
+        assert_eq!(output.text_since(start), "third\n");
+    }
+}
@@ -0,0 +1,116 @@
+//! [ColorPalette] impl.
+
+use ::iced::{Color, theme::Palette};
+use ::serde::{Deserialize, Serialize};
+
+/// Named colors of ui, given as hex such as `#3c8dbc`. Slots that are not
+/// set keep the color of the selected theme.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorPalette {
+    /// Background color.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// Text color.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Primary color, used for buttons and selections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
+    /// Color of successes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+    /// Color of failures.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub danger: Option<String>,
+}
+
+impl ColorPalette {
+    /// Check if no slot is set.
+    pub const fn is_empty(&self) -> bool {
+        self.background.is_none()
+            && self.text.is_none()
+            && self.primary.is_none()
+            && self.success.is_none()
+            && self.danger.is_none()
+    }
+
+    /// Replace slots of self with the ones set in other.
+    pub fn merge(&mut self, other: Self) {
+        let Self {
+            background,
+            text,
+            primary,
+            success,
+            danger,
+        } = other;
+        for (slot, value) in [
+            (&mut self.background, background),
+            (&mut self.text, text),
+            (&mut self.primary, primary),
+            (&mut self.success, success),
+            (&mut self.danger, danger),
+        ] {
+            if value.is_some() {
+                *slot = value;
+            }
+        }
+    }
+
+    /// Set slots as name and value.
+    fn slots(&self) -> [(&'static str, Option<&str>); 5] {
+        [
+            ("background", self.background.as_deref()),
+            ("text", self.text.as_deref()),
+            ("primary", self.primary.as_deref()),
+            ("success", self.success.as_deref()),
+            ("danger", self.danger.as_deref()),
+        ]
+    }
+
+    /// Messages for slots that are not valid hex colors.
+    pub fn invalid(&self) -> Vec<String> {
+        self.slots()
+            .into_iter()
+            .filter_map(|(name, value)| {
+                parse_hex(value?)
+                    .err()
+                    .map(|err| format!("palette {name}: {err}"))
+            })
+            .collect()
+    }
+
+    /// Apply slots to base, invalid slots keep the color of base, see
+    /// [ColorPalette::invalid].
+    pub fn apply(&self, base: Palette) -> Palette {
+        let color = |value: &Option<String>, fallback: Color| {
+            value
+                .as_deref()
+                .and_then(|value| parse_hex(value).ok())
+                .map_or(fallback, |[r, g, b]| Color::from_rgb8(r, g, b))
+        };
+        Palette {
+            background: color(&self.background, base.background),
+            text: color(&self.text, base.text),
+            primary: color(&self.primary, base.primary),
+            success: color(&self.success, base.success),
+            danger: color(&self.danger, base.danger),
+        }
+    }
+}
+
+/// Parse a color given as hex, with or without a leading `#`.
+///
+/// # Errors
+/// If value is not six hex digits.
+pub fn parse_hex(value: &str) -> Result<[u8; 3], String> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(format!("expected a color as '#rrggbb', got '{value}'"));
+    }
+    let channel = |index: usize| {
+        u8::from_str_radix(&hex[index..index + 2], 16)
+            .map_err(|err| format!("invalid color '{value}'\n{err}"))
+    };
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
@@ -0,0 +1,45 @@
+//! [PasteMode] impl.
+
+use ::std::fmt::Display;
+
+use ::serde::{Deserialize, Serialize};
+
+/// How pasted text is split into arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasteMode {
+    /// Text is shell quoted.
+    #[default]
+    Quoted,
+    /// Each non-empty line is an argument.
+    Lines,
+}
+
+impl PasteMode {
+    /// All paste modes.
+    pub const ALL: [PasteMode; 2] = [PasteMode::Quoted, PasteMode::Lines];
+
+    /// Split text into arguments.
+    ///
+    /// # Errors
+    /// If text is to be split as shell quoted and cannot be.
+    pub fn split(self, text: &str) -> Result<Vec<String>, ::shell_words::ParseError> {
+        match self {
+            PasteMode::Quoted => ::shell_words::split(text),
+            PasteMode::Lines => Ok(text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(String::from)
+                .collect()),
+        }
+    }
+}
+
+impl Display for PasteMode {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match self {
+            PasteMode::Quoted => "Shell quoted",
+            PasteMode::Lines => "One per line",
+        })
+    }
+}
@@ -0,0 +1,104 @@
+//! Location of persisted application data.
+
+use ::std::{io::ErrorKind, path::PathBuf, sync::OnceLock};
+
+use ::serde::{Serialize, de::DeserializeOwned};
+use ::tokio::sync::Mutex;
+
+use crate::config::{LoadError, SaveError};
+
+/// Environment variable overriding the data directory.
+pub const DATA_DIR_ENV: &str = "COMMAND_RUNNER_DATA_DIR";
+
+/// Data directory given on the command line, set once on startup.
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Held while saving, such that saves run one at a time in the order they
+/// were started and an earlier save cannot replace a later one.
+static SAVE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Override data directory for the rest of the session, only the first call
+/// has an effect.
+pub fn set_data_dir(path: PathBuf) {
+    if DATA_DIR.set(path).is_err() {
+        ::log::warn!("data directory already set, ignoring new value");
+    }
+}
+
+/// Directory ui state, library and queue are stored in, if any.
+///
+/// In order of precedence this is the directory given by `--data-dir`, the
+/// directory given by the [DATA_DIR_ENV] environment variable and
+/// `command-runner` in the data directory of the platform.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(path) = DATA_DIR.get() {
+        return Some(path.clone());
+    }
+    if let Some(path) = ::std::env::var_os(DATA_DIR_ENV).filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    ::dirs::data_dir().map(|dir| dir.join("command-runner"))
+}
+
+/// Load value stored as toml at path, a missing file or no path results in
+/// the default value.
+///
+/// # Errors
+/// If file exists but cannot be read [LoadError::Read] is returned.
+/// If file cannot be deserialized [LoadError::Deserialize] is returned.
+pub async fn load<T>(path: Option<PathBuf>) -> Result<T, LoadError>
+where
+    T: DeserializeOwned + Default,
+{
+    let Some(path) = path else {
+        return Ok(T::default());
+    };
+    match ::tokio::fs::read_to_string(&path).await {
+        Ok(content) => match ::toml::from_str(&content) {
+            Ok(value) => Ok(value),
+            Err(source) => Err(LoadError::Deserialize { path, source }),
+        },
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(T::default()),
+        Err(source) => Err(LoadError::Read { path, source }),
+    }
+}
+
+/// Save value as toml to path, nothing is saved if there is no path. The
+/// value is written to a temporary file next to path which then replaces
+/// it, such that an interrupted save leaves the previous file intact.
+///
+/// # Errors
+/// If value cannot be serialized [SaveError::Serialize] is returned.
+/// If value cannot be written [SaveError::Write] is returned.
+pub async fn save<T>(value: &T, path: Option<PathBuf>) -> Result<(), SaveError>
+where
+    T: Serialize + Sync,
+{
+    let Some(path) = path else {
+        return Ok(());
+    };
+    let content = match ::toml::to_string_pretty(value) {
+        Ok(content) => content,
+        Err(source) => return Err(SaveError::Serialize { path, source }),
+    };
+    let _guard = SAVE_LOCK.lock().await;
+    if let Some(parent) = path.parent()
+        && let Err(source) = ::tokio::fs::create_dir_all(parent).await
+    {
+        return Err(SaveError::Write { path, source });
+    }
+    // Other instances may save the same file.
+    let mut tmp = path.clone().into_os_string();
+    tmp.push(format!(".{}.tmp", ::std::process::id()));
+    let tmp = PathBuf::from(tmp);
+    if let Err(source) = ::tokio::fs::write(&tmp, &content).await {
+        return Err(SaveError::Write { path, source });
+    }
+    match ::tokio::fs::rename(&tmp, &path).await {
+        Ok(()) => Ok(()),
+        Err(source) => {
+            _ = ::tokio::fs::remove_file(&tmp).await;
+            Err(SaveError::Write { path, source })
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! [Pick] impl.
+
+use ::std::{fmt::Display, path::PathBuf};
+
+use ::rfd::AsyncFileDialog;
+
+/// Placeholder in an argument that is filled by picking a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+    /// `{{file}}`, filled by picking a file.
+    File,
+    /// `{{dir}}`, filled by picking a directory.
+    Dir,
+}
+
+impl Pick {
+    /// All picks.
+    pub const ALL: [Pick; 2] = [Pick::File, Pick::Dir];
+
+    /// Placeholder text of pick.
+    pub const fn placeholder(self) -> &'static str {
+        match self {
+            Pick::File => "{{file}}",
+            Pick::Dir => "{{dir}}",
+        }
+    }
+
+    /// First placeholder of arg, if any.
+    pub fn find(arg: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .filter_map(|pick| Some((arg.find(pick.placeholder())?, pick)))
+            .min_by_key(|(index, _)| *index)
+            .map(|(_, pick)| pick)
+    }
+
+    /// Replace first placeholder of pick in arg by path.
+    pub fn fill(self, arg: &str, path: &str) -> String {
+        arg.replacen(self.placeholder(), path, 1)
+    }
+
+    /// Show a dialog picking a path for placeholder, [None] if cancelled.
+    pub async fn dialog(self) -> Option<PathBuf> {
+        let dialog = AsyncFileDialog::new().set_title(format!("Pick {self}"));
+        let handle = match self {
+            Pick::File => dialog.pick_file().await,
+            Pick::Dir => dialog.pick_folder().await,
+        }?;
+        Some(handle.path().to_path_buf())
+    }
+}
+
+impl Display for Pick {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match self {
+            Pick::File => "File",
+            Pick::Dir => "Directory",
+        })
+    }
+}
@@ -0,0 +1,48 @@
+//! [PostAction] impl.
+
+use ::std::path::PathBuf;
+
+use ::serde::{Deserialize, Serialize};
+
+use crate::{config::Config, notify};
+
+/// Action taken once a run has finished, see [Config::on_success] and
+/// [Config::on_failure].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostAction {
+    /// Open path using the default application, such as a file or the
+    /// folder output was written to.
+    OpenPath(PathBuf),
+    /// Show a desktop notification with text.
+    ShowNotification(String),
+    /// Run config, in the background when ran from the ui.
+    RunConfig(Box<Config>),
+}
+
+impl PostAction {
+    /// Perform action, waiting for configs to finish running.
+    ///
+    /// # Errors
+    /// If a path cannot be opened or a config cannot be ran.
+    pub async fn run(self) -> Result<(), String> {
+        match self {
+            PostAction::OpenPath(path) => ::open::that_detached(&path)
+                .map_err(|err| format!("could not open {path:?}\n{err}")),
+            PostAction::ShowNotification(text) => {
+                notify::notify("command-runner".to_owned(), text).await;
+                Ok(())
+            }
+            PostAction::RunConfig(config) => {
+                let command_line = config.command_line();
+                match (*config).run_async().await {
+                    Ok(status) => {
+                        ::log::info!("{command_line} finished with {status}");
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("could not run {command_line}\n{err}")),
+                }
+            }
+        }
+    }
+}
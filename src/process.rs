@@ -0,0 +1,1058 @@
+//! Running and stopping of processes.
+
+use ::std::{
+    fs::File as StdFile,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    sync::Arc,
+    time::Duration,
+};
+
+use ::clap::ValueEnum;
+use ::iced::futures::{SinkExt, channel::mpsc::Sender};
+use ::serde::{Deserialize, Serialize};
+use ::tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader},
+    process::{Child, ChildStderr, ChildStdin, ChildStdout},
+    sync::{
+        Mutex,
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        watch,
+    },
+};
+
+use crate::{Message, output::OutputLine, usage::Usage};
+
+/// Input for stdin of a running process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// Write line followed by a newline.
+    Line(String),
+    /// Close stdin.
+    Close,
+}
+
+/// Sends input to stdin of a running process.
+pub type InputSender = UnboundedSender<Input>;
+
+/// Default seconds to wait for a process to exit after sending a kill signal.
+pub const DEFAULT_KILL_GRACE_SECS: u64 = 5;
+
+/// Signal sent to ask a process to exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum KillSignal {
+    /// SIGTERM.
+    Term,
+    /// SIGINT.
+    Int,
+    /// SIGHUP.
+    Hup,
+    /// SIGQUIT.
+    Quit,
+}
+
+impl KillSignal {
+    /// Send signal to process with pid, or to the process group it leads if
+    /// group is set.
+    ///
+    /// # Errors
+    /// If the signal cannot be sent.
+    #[cfg(unix)]
+    pub async fn send(self, pid: u32, group: bool) -> ::std::io::Result<()> {
+        use ::nix::{
+            sys::signal::{Signal, kill, killpg},
+            unistd::Pid,
+        };
+
+        let signal = match self {
+            KillSignal::Term => Signal::SIGTERM,
+            KillSignal::Int => Signal::SIGINT,
+            KillSignal::Hup => Signal::SIGHUP,
+            KillSignal::Quit => Signal::SIGQUIT,
+        };
+        let pid = Pid::from_raw(i32::try_from(pid).map_err(::std::io::Error::other)?);
+        if group {
+            Ok(killpg(pid, signal)?)
+        } else {
+            Ok(kill(pid, signal)?)
+        }
+    }
+
+    /// Ask process with pid to close, along with its descendants if group is
+    /// set. Windows has no signals so `taskkill` is used without forcing,
+    /// regardless of signal.
+    ///
+    /// # Errors
+    /// If `taskkill` cannot be ran or fails.
+    #[cfg(windows)]
+    pub async fn send(self, pid: u32, group: bool) -> ::std::io::Result<()> {
+        taskkill(pid, group).await
+    }
+}
+
+/// Run `taskkill` for process with pid, including its descendants if tree
+/// is set.
+///
+/// # Errors
+/// If `taskkill` cannot be ran or fails.
+#[cfg(windows)]
+async fn taskkill(pid: u32, tree: bool) -> ::std::io::Result<()> {
+    let mut command = ::tokio::process::Command::new("taskkill");
+    command.args(["/PID", &pid.to_string()]);
+    if tree {
+        command.arg("/T");
+    }
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(::std::io::Error::other(format!(
+            "taskkill finished with {status}"
+        )))
+    }
+}
+
+/// Place command in a process group of its own, such that its descendants
+/// may be stopped together with it, see [Group].
+fn isolate(command: &mut ::tokio::process::Command) {
+    #[cfg(unix)]
+    command.process_group(0);
+
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+/// Group of a spawned child and its descendants, which may be killed as one.
+///
+/// On unix this is the process group led by the child, which requires the
+/// command to have been passed to [isolate]. On windows the child is
+/// assigned to a job object set to kill its processes once closed, which
+/// happens when the group is dropped. Processes started by the child before
+/// it is assigned are not part of the job.
+#[derive(Debug)]
+pub struct Group {
+    /// Process group id, the pid of its leader.
+    #[cfg(unix)]
+    pgid: ::nix::unistd::Pid,
+    /// Job object the child is assigned to.
+    #[cfg(windows)]
+    job: Job,
+}
+
+impl Group {
+    /// Get group of child, [None] if the child has exited or the group
+    /// cannot be created.
+    pub fn new(child: &Child) -> Option<Self> {
+        #[cfg(unix)]
+        {
+            Self::led_by(child.id()?)
+        }
+
+        #[cfg(windows)]
+        {
+            let handle = child.raw_handle()?;
+            match Job::new(handle) {
+                Ok(job) => Some(Self { job }),
+                Err(err) => {
+                    ::log::warn!("could not assign process to job object\n{err}");
+                    None
+                }
+            }
+        }
+    }
+
+    /// Get group led by process with pid, which is the group of a child
+    /// spawned from a command passed to [isolate].
+    #[cfg(unix)]
+    fn led_by(pid: u32) -> Option<Self> {
+        let pid = i32::try_from(pid).ok()?;
+        Some(Self {
+            pgid: ::nix::unistd::Pid::from_raw(pid),
+        })
+    }
+
+    /// Forcefully kill all processes of group. A group without processes
+    /// left is not an error.
+    ///
+    /// # Errors
+    /// If the group cannot be killed.
+    pub fn kill(&self) -> ::std::io::Result<()> {
+        #[cfg(unix)]
+        {
+            use ::nix::{
+                errno::Errno,
+                sys::signal::{Signal, killpg},
+            };
+
+            match killpg(self.pgid, Signal::SIGKILL) {
+                Ok(()) | Err(Errno::ESRCH) => Ok(()),
+                Err(err) => Err(err.into()),
+            }
+        }
+
+        #[cfg(windows)]
+        {
+            self.job.terminate()
+        }
+    }
+}
+
+/// Job object which kills its processes once closed.
+#[cfg(windows)]
+#[derive(Debug)]
+struct Job {
+    /// Handle of job object, closed on drop.
+    handle: ::windows_sys::Win32::Foundation::HANDLE,
+}
+
+// SAFETY: Job object handles may be used and closed from any thread.
+#[cfg(windows)]
+unsafe impl Send for Job {}
+
+// SAFETY: All operations used on the handle are thread safe.
+#[cfg(windows)]
+unsafe impl Sync for Job {}
+
+#[cfg(windows)]
+impl Job {
+    /// Create a job object set to kill its processes when closed and assign
+    /// process with handle to it.
+    ///
+    /// # Errors
+    /// If the job object cannot be created, configured or assigned to.
+    fn new(process: ::std::os::windows::io::RawHandle) -> ::std::io::Result<Self> {
+        use ::windows_sys::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+            JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JobObjectExtendedLimitInformation,
+            SetInformationJobObject,
+        };
+
+        // SAFETY: Null attributes and name create an anonymous job object.
+        let handle = unsafe { CreateJobObjectW(::std::ptr::null(), ::std::ptr::null()) };
+        if handle.is_null() {
+            return Err(::std::io::Error::last_os_error());
+        }
+        // Closes handle on early returns.
+        let job = Self { handle };
+
+        // SAFETY: Limit information is plain data, for which all zeroes is
+        // a valid value.
+        let mut info = unsafe { ::std::mem::zeroed::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() };
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        let size = u32::try_from(size_of_val(&info)).map_err(::std::io::Error::other)?;
+        // SAFETY: info is valid for reads of size for the whole call.
+        if unsafe {
+            SetInformationJobObject(
+                job.handle,
+                JobObjectExtendedLimitInformation,
+                (&raw const info).cast(),
+                size,
+            )
+        } == 0
+        {
+            return Err(::std::io::Error::last_os_error());
+        }
+
+        // SAFETY: process is a valid handle of a child which is not reaped.
+        if unsafe { AssignProcessToJobObject(job.handle, process.cast()) } == 0 {
+            return Err(::std::io::Error::last_os_error());
+        }
+
+        Ok(job)
+    }
+
+    /// Forcefully kill all processes of job.
+    ///
+    /// # Errors
+    /// If the processes cannot be terminated.
+    fn terminate(&self) -> ::std::io::Result<()> {
+        use ::windows_sys::Win32::System::JobObjects::TerminateJobObject;
+
+        // SAFETY: handle is a valid job object handle until dropped.
+        if unsafe { TerminateJobObject(self.handle, 1) } == 0 {
+            Err(::std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Job {
+    fn drop(&mut self) {
+        use ::windows_sys::Win32::Foundation::CloseHandle;
+
+        // SAFETY: handle is a valid job object handle not closed elsewhere.
+        unsafe { CloseHandle(self.handle) };
+    }
+}
+
+/// Process that may be stopped, see [Shutdown::stop].
+pub trait Stoppable {
+    /// Process id, [None] once the process has been reaped.
+    fn id(&self) -> Option<u32>;
+
+    /// Wait for process to exit.
+    fn wait(&mut self) -> impl Future<Output = ::std::io::Result<ExitStatus>> + Send;
+
+    /// Forcefully kill process and wait for it to exit.
+    fn kill(&mut self) -> impl Future<Output = ::std::io::Result<()>> + Send;
+}
+
+impl Stoppable for Child {
+    fn id(&self) -> Option<u32> {
+        Child::id(self)
+    }
+
+    fn wait(&mut self) -> impl Future<Output = ::std::io::Result<ExitStatus>> + Send {
+        Child::wait(self)
+    }
+
+    fn kill(&mut self) -> impl Future<Output = ::std::io::Result<()>> + Send {
+        Child::kill(self)
+    }
+}
+
+/// How a process is stopped when killed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shutdown {
+    /// Signal sent before forcefully killing, if any.
+    pub signal: Option<KillSignal>,
+    /// Time given to process to exit after signal is sent.
+    pub grace: Duration,
+}
+
+impl Shutdown {
+    /// Stop child, first by sending signal and waiting for the grace period,
+    /// then forcefully. If group is given the signal is sent to the whole
+    /// group and it is killed before returning, also when the child exits
+    /// within the grace period, such that no descendants are left running.
+    ///
+    /// # Errors
+    /// If the child cannot be killed or waited on.
+    pub async fn stop(
+        self,
+        child: &mut impl Stoppable,
+        group: Option<&Group>,
+    ) -> ::std::io::Result<ExitStatus> {
+        let kill_group = || {
+            if let Some(group) = group
+                && let Err(err) = group.kill()
+            {
+                ::log::warn!("could not kill process group\n{err}");
+            }
+        };
+        if let Some(signal) = self.signal
+            && let Some(pid) = child.id()
+        {
+            match signal.send(pid, group.is_some()).await {
+                Ok(()) => match ::tokio::time::timeout(self.grace, child.wait()).await {
+                    Ok(status) => {
+                        kill_group();
+                        return status;
+                    }
+                    Err(_) => ::log::info!("process {pid} did not exit in time, killing it"),
+                },
+                Err(err) => ::log::warn!("could not send {signal:?} to {pid}\n{err}"),
+            }
+        }
+        kill_group();
+        child.kill().await?;
+        child.wait().await
+    }
+}
+
+/// Interval at which a child is checked for having exited when its resource
+/// usage is read, see [OwnedChild].
+#[cfg(unix)]
+const REAP_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Child taken out of tokio, such that only the application reaps it, using
+/// `wait4` to read the resource usage of the child alone. Tokio reaps a
+/// [Child] on its own, also in the background once it is dropped, which
+/// may hit an unrelated process reusing the pid if the application has
+/// already reaped it.
+///
+/// The pid of the child is only used until it has been reaped. The
+/// [std::process::Child] is never waited on through std, only dropped.
+#[cfg(unix)]
+#[derive(Debug)]
+struct OwnedChild {
+    /// Spawned child.
+    child: ::std::process::Child,
+    /// Exit status and usage, set once reaped.
+    exit: Option<(ExitStatus, Usage)>,
+}
+
+#[cfg(unix)]
+impl OwnedChild {
+    /// Wait for child to exit and reap it, reading its usage. The child is
+    /// polled for as it may only be reaped once it has exited, which keeps
+    /// waiting cancellable, such as when the child is stopped instead.
+    ///
+    /// # Errors
+    /// If the child cannot be waited on.
+    async fn wait_usage(&mut self) -> ::std::io::Result<(ExitStatus, Usage)> {
+        let pid = ::libc::pid_t::try_from(self.child.id()).map_err(::std::io::Error::other)?;
+        loop {
+            if let Some(exit) = self.exit {
+                return Ok(exit);
+            }
+            self.exit = reap(pid, true)?;
+            if self.exit.is_none() {
+                ::tokio::time::sleep(REAP_INTERVAL).await;
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Stoppable for OwnedChild {
+    fn id(&self) -> Option<u32> {
+        self.exit.is_none().then(|| self.child.id())
+    }
+
+    async fn wait(&mut self) -> ::std::io::Result<ExitStatus> {
+        self.wait_usage().await.map(|(status, _)| status)
+    }
+
+    async fn kill(&mut self) -> ::std::io::Result<()> {
+        use ::nix::{
+            errno::Errno,
+            sys::signal::{Signal, kill},
+            unistd::Pid,
+        };
+
+        if let Some(pid) = self.id() {
+            let pid = i32::try_from(pid).map_err(::std::io::Error::other)?;
+            match kill(Pid::from_raw(pid), Signal::SIGKILL) {
+                Ok(()) | Err(Errno::ESRCH) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        self.wait_usage().await.map(|_| ())
+    }
+}
+
+/// Spawned child, owned by tokio unless its resource usage is read on unix,
+/// see [OwnedChild].
+#[derive(Debug)]
+enum Spawned {
+    /// Child owned and reaped by tokio.
+    Tokio(Child),
+    /// Child reaped by the application.
+    #[cfg(unix)]
+    Owned(OwnedChild),
+}
+
+/// Pipes of stdin, stdout and stderr of a spawned child.
+type Pipes = (Option<ChildStdin>, Option<ChildStdout>, Option<ChildStderr>);
+
+impl Spawned {
+    /// Spawn command, taken out of tokio if usage is set on unix. Pipes of
+    /// the child are returned along with it.
+    ///
+    /// # Errors
+    /// If the command cannot be spawned or its pipes used with tokio.
+    fn spawn(
+        mut command: ::tokio::process::Command,
+        usage: bool,
+    ) -> ::std::io::Result<(Self, Pipes)> {
+        #[cfg(unix)]
+        if usage {
+            let mut child = command.into_std().spawn()?;
+            let pipes = (
+                child.stdin.take().map(ChildStdin::from_std).transpose()?,
+                child.stdout.take().map(ChildStdout::from_std).transpose()?,
+                child.stderr.take().map(ChildStderr::from_std).transpose()?,
+            );
+            return Ok((Self::Owned(OwnedChild { child, exit: None }), pipes));
+        }
+        #[cfg(not(unix))]
+        let _ = usage;
+        let mut child = command.spawn()?;
+        let pipes = (child.stdin.take(), child.stdout.take(), child.stderr.take());
+        Ok((Self::Tokio(child), pipes))
+    }
+
+    /// Group of child, see [Group::new].
+    fn group(&self) -> Option<Group> {
+        match self {
+            Self::Tokio(child) => Group::new(child),
+            #[cfg(unix)]
+            Self::Owned(child) => Group::led_by(child.child.id()),
+        }
+    }
+
+    /// Wait for child to exit, reading its resource usage if usage is set
+    /// and supported on this platform. On windows processor times are read
+    /// from the job object of group if given, such that they include
+    /// descendants.
+    ///
+    /// # Errors
+    /// If the child cannot be waited on.
+    async fn wait_usage(
+        &mut self,
+        group: Option<&Group>,
+        usage: bool,
+    ) -> ::std::io::Result<(ExitStatus, Option<Usage>)> {
+        let child = match self {
+            Self::Tokio(child) => child,
+            #[cfg(unix)]
+            Self::Owned(child) => {
+                let (status, usage) = child.wait_usage().await?;
+                return Ok((status, Some(usage)));
+            }
+        };
+        #[cfg(windows)]
+        if usage && let Some(handle) = child.raw_handle() {
+            // The handle of child is closed once it has been waited for.
+            let process = Process::duplicate(handle)?;
+            let status = child.wait().await?;
+            let usage = Usage::from_process(process.handle, group.map(|group| group.job.handle))
+                .map_err(|err| ::log::warn!("could not read resource usage\n{err}"))
+                .ok();
+            return Ok((status, usage));
+        }
+        #[cfg(not(windows))]
+        let _ = (group, usage);
+        Ok((child.wait().await?, None))
+    }
+}
+
+impl Stoppable for Spawned {
+    fn id(&self) -> Option<u32> {
+        match self {
+            Self::Tokio(child) => child.id(),
+            #[cfg(unix)]
+            Self::Owned(child) => child.id(),
+        }
+    }
+
+    async fn wait(&mut self) -> ::std::io::Result<ExitStatus> {
+        match self {
+            Self::Tokio(child) => child.wait().await,
+            #[cfg(unix)]
+            Self::Owned(child) => Stoppable::wait(child).await,
+        }
+    }
+
+    async fn kill(&mut self) -> ::std::io::Result<()> {
+        match self {
+            Self::Tokio(child) => child.kill().await,
+            #[cfg(unix)]
+            Self::Owned(child) => Stoppable::kill(child).await,
+        }
+    }
+}
+
+/// Handle of a process owned by the application, which stays valid after
+/// the process exits such that its usage may be read.
+#[cfg(windows)]
+#[derive(Debug)]
+struct Process {
+    /// Process handle, closed on drop.
+    handle: ::windows_sys::Win32::Foundation::HANDLE,
+}
+
+// SAFETY: Process handles may be used and closed from any thread.
+#[cfg(windows)]
+unsafe impl Send for Process {}
+
+// SAFETY: All operations used on the handle are thread safe.
+#[cfg(windows)]
+unsafe impl Sync for Process {}
+
+#[cfg(windows)]
+impl Process {
+    /// Duplicate process handle, with the same access.
+    ///
+    /// # Errors
+    /// If the handle cannot be duplicated.
+    fn duplicate(process: ::std::os::windows::io::RawHandle) -> ::std::io::Result<Self> {
+        use ::windows_sys::Win32::{
+            Foundation::{DUPLICATE_SAME_ACCESS, DuplicateHandle},
+            System::Threading::GetCurrentProcess,
+        };
+
+        let mut handle = ::std::ptr::null_mut();
+        // SAFETY: process is a valid handle and handle is valid for writes
+        // for the whole call.
+        if unsafe {
+            DuplicateHandle(
+                GetCurrentProcess(),
+                process.cast(),
+                GetCurrentProcess(),
+                &raw mut handle,
+                0,
+                0,
+                DUPLICATE_SAME_ACCESS,
+            )
+        } == 0
+        {
+            return Err(::std::io::Error::last_os_error());
+        }
+        Ok(Self { handle })
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Process {
+    fn drop(&mut self) {
+        use ::windows_sys::Win32::Foundation::CloseHandle;
+
+        // SAFETY: handle is a valid process handle not closed elsewhere.
+        unsafe { CloseHandle(self.handle) };
+    }
+}
+
+/// Reap child with pid using `wait4`, [None] if nonblocking and the child
+/// has not exited.
+///
+/// # Errors
+/// If the child cannot be waited on.
+#[cfg(unix)]
+fn reap(pid: ::libc::pid_t, nonblocking: bool) -> ::std::io::Result<Option<(ExitStatus, Usage)>> {
+    use ::std::os::unix::process::ExitStatusExt;
+
+    let options = if nonblocking { ::libc::WNOHANG } else { 0 };
+    let mut status = 0;
+    // SAFETY: rusage is plain data, for which all zeroes is a valid value.
+    let mut usage = unsafe { ::std::mem::zeroed::<::libc::rusage>() };
+    loop {
+        // SAFETY: status and usage are valid for writes for the whole call.
+        match unsafe { ::libc::wait4(pid, &mut status, options, &mut usage) } {
+            0 => return Ok(None),
+            -1 => {
+                let err = ::std::io::Error::last_os_error();
+                if err.kind() != ::std::io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            _ => {
+                return Ok(Some((
+                    ExitStatus::from_raw(status),
+                    Usage::from_rusage(&usage),
+                )));
+            }
+        }
+    }
+}
+
+/// Wait for child to exit blocking the current thread, reading its resource
+/// usage if usage is set and supported on this platform.
+///
+/// # Errors
+/// If the child cannot be waited on.
+pub fn wait_blocking(
+    mut child: ::std::process::Child,
+    usage: bool,
+) -> ::std::io::Result<(ExitStatus, Option<Usage>)> {
+    #[cfg(unix)]
+    if usage {
+        let pid = ::libc::pid_t::try_from(child.id()).map_err(::std::io::Error::other)?;
+        if let Some((status, usage)) = reap(pid, false)? {
+            return Ok((status, Some(usage)));
+        }
+    }
+    #[cfg(windows)]
+    if usage {
+        use ::std::os::windows::io::AsRawHandle;
+
+        // The handle of child stays open until it is dropped.
+        let status = child.wait()?;
+        let usage = Usage::from_process(child.as_raw_handle().cast(), None)
+            .map_err(|err| ::log::warn!("could not read resource usage\n{err}"))
+            .ok();
+        return Ok((status, usage));
+    }
+    #[cfg(not(any(unix, windows)))]
+    let _ = usage;
+    Ok((child.wait()?, None))
+}
+
+/// Files output of a process is written to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputFiles {
+    /// File both stdout and stderr are written to.
+    pub log: Option<PathBuf>,
+    /// File stdout is written to.
+    pub stdout: Option<PathBuf>,
+    /// File stderr is written to.
+    pub stderr: Option<PathBuf>,
+}
+
+impl OutputFiles {
+    /// Redirect stdout and stderr of command to files, for streams without
+    /// a file of their own the log is used.
+    ///
+    /// # Errors
+    /// If a file cannot be created.
+    pub fn redirect(&self, command: &mut ::std::process::Command) -> ::std::io::Result<()> {
+        let log = self.log.as_deref().map(StdFile::create).transpose()?;
+        let open = |path: Option<&Path>| -> ::std::io::Result<Option<StdFile>> {
+            match path {
+                Some(path) => StdFile::create(path).map(Some),
+                None => log.as_ref().map(StdFile::try_clone).transpose(),
+            }
+        };
+        if let Some(file) = open(self.stdout.as_deref())? {
+            command.stdout(file);
+        }
+        if let Some(file) = open(self.stderr.as_deref())? {
+            command.stderr(file);
+        }
+        Ok(())
+    }
+}
+
+/// File shared between output streams.
+type SharedFile = Arc<Mutex<File>>;
+
+/// Create file at path truncating it, failures are reported to sender.
+async fn create_output(path: Option<&Path>, sender: &Sender<Message>) -> Option<SharedFile> {
+    let path = path?;
+    match File::create(path).await {
+        Ok(file) => Some(Arc::new(Mutex::new(file))),
+        Err(err) => {
+            ::log::warn!("could not create output file {path:?}\n{err}");
+            _ = sender
+                .clone()
+                .send(format!("could not create output file {path:?}").into())
+                .await;
+            None
+        }
+    }
+}
+
+/// Wait until kill generation differs from start, if the sender is dropped
+/// this never returns.
+async fn kill_requested(kill: &mut watch::Receiver<u64>, start: u64) {
+    if kill
+        .wait_for(|generation| *generation != start)
+        .await
+        .is_err()
+    {
+        ::std::future::pending::<()>().await;
+    }
+}
+
+/// Run command capturing stdout and stderr, forwarding lines to sender.
+///
+/// Lines are also written to files, which are created before the process
+/// is started. If interactive stdin is piped and [Message::InputOpened] is
+/// sent, otherwise the process reads no input. The process is stopped
+/// according to shutdown when the generation of kill is increased. If usage
+/// is set the resource usage of the process is returned, unless it was
+/// stopped.
+///
+/// # Errors
+/// If the process cannot be spawned or waited on.
+pub async fn capture_command(
+    command: ::std::process::Command,
+    shutdown: Shutdown,
+    files: OutputFiles,
+    interactive: bool,
+    usage: bool,
+    mut kill: watch::Receiver<u64>,
+    sender: &Sender<Message>,
+) -> Result<(ExitStatus, Option<Usage>), String> {
+    let mut command = ::tokio::process::Command::from(command);
+    command
+        .stdin(if interactive {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    isolate(&mut command);
+
+    let log = create_output(files.log.as_deref(), sender).await;
+    let stdout_file = create_output(files.stdout.as_deref(), sender).await;
+    let stderr_file = create_output(files.stderr.as_deref(), sender).await;
+    let stdout_sinks = log.iter().chain(&stdout_file).cloned().collect();
+    let stderr_sinks = log.into_iter().chain(stderr_file).collect();
+
+    let start = *kill.borrow_and_update();
+    let (mut child, (stdin, stdout, stderr)) = Spawned::spawn(command, usage).map_err(|err| {
+        ::log::error!("failed to run process\n{err}");
+        err.to_string()
+    })?;
+    let group = child.group();
+
+    let (input, receiver) = mpsc::unbounded_channel();
+    if interactive {
+        _ = sender.clone().send(Message::InputOpened(input)).await;
+    }
+
+    let wait = async {
+        ::tokio::select! {
+            exit = child.wait_usage(group.as_ref(), usage) => exit,
+            () = kill_requested(&mut kill, start) => {
+                shutdown.stop(&mut child, group.as_ref()).await.map(|status| (status, None))
+            }
+            // Input is only forwarded while process runs.
+            never = async {
+                forward_input(stdin, receiver).await;
+                ::std::future::pending().await
+            } => never,
+        }
+    };
+    let (status, (), ()) = ::iced::futures::future::join3(
+        wait,
+        forward_lines(stdout, sender.clone(), stdout_sinks),
+        forward_lines(stderr, sender.clone(), stderr_sinks),
+    )
+    .await;
+
+    status.map_err(|err| {
+        ::log::error!("failed to wait for process\n{err}");
+        err.to_string()
+    })
+}
+
+/// Run command in the background, sending [Message::BackgroundStarted] with
+/// id once it is spawned.
+///
+/// The process is stopped according to shutdown when the generation of kill
+/// is increased.
+///
+/// # Errors
+/// If the process cannot be spawned or waited on.
+pub async fn background_command(
+    command: ::std::process::Command,
+    shutdown: Shutdown,
+    id: u64,
+    mut kill: watch::Receiver<u64>,
+    sender: &Sender<Message>,
+) -> Result<ExitStatus, String> {
+    let mut command = ::tokio::process::Command::from(command);
+    command.stdin(Stdio::null());
+    isolate(&mut command);
+
+    let start = *kill.borrow_and_update();
+    let mut child = command.spawn().map_err(|err| {
+        ::log::error!("failed to run process\n{err}");
+        err.to_string()
+    })?;
+    let group = Group::new(&child);
+    if let Some(pid) = child.id() {
+        _ = sender
+            .clone()
+            .send(Message::BackgroundStarted(id, pid))
+            .await;
+    }
+
+    let status = ::tokio::select! {
+        status = child.wait() => status,
+        () = kill_requested(&mut kill, start) => shutdown.stop(&mut child, group.as_ref()).await,
+    };
+    status.map_err(|err| {
+        ::log::error!("failed to wait for process\n{err}");
+        err.to_string()
+    })
+}
+
+/// Run command with stdio inherited, for interactive programs such as
+/// debuggers that need a terminal.
+///
+/// The process is stopped according to shutdown when the generation of kill
+/// is increased. If usage is set the resource usage of the process is
+/// returned, unless it was stopped.
+///
+/// # Errors
+/// If the process cannot be spawned or waited on.
+pub async fn inherit_command(
+    command: ::std::process::Command,
+    shutdown: Shutdown,
+    usage: bool,
+    mut kill: watch::Receiver<u64>,
+) -> Result<(ExitStatus, Option<Usage>), String> {
+    let mut command = ::tokio::process::Command::from(command);
+    command
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+
+    let start = *kill.borrow_and_update();
+    let (mut child, _) = Spawned::spawn(command, usage).map_err(|err| {
+        ::log::error!("failed to run process\n{err}");
+        err.to_string()
+    })?;
+
+    // A process group would detach the child from the terminal, a job
+    // object does not.
+    #[cfg(windows)]
+    let group = child.group();
+    #[cfg(not(windows))]
+    let group = None;
+
+    let status = ::tokio::select! {
+        exit = child.wait_usage(group.as_ref(), usage) => exit,
+        () = kill_requested(&mut kill, start) => {
+            shutdown.stop(&mut child, group.as_ref()).await.map(|status| (status, None))
+        }
+    };
+    status.map_err(|err| {
+        ::log::error!("failed to wait for process\n{err}");
+        err.to_string()
+    })
+}
+
+/// Write input received by receiver to stdin, until stdin is closed by
+/// [Input::Close] or all senders are dropped.
+async fn forward_input(stdin: Option<ChildStdin>, mut receiver: UnboundedReceiver<Input>) {
+    let Some(mut stdin) = stdin else {
+        return;
+    };
+    while let Some(Input::Line(mut line)) = receiver.recv().await {
+        line.push('\n');
+        if let Err(err) = stdin.write_all(line.as_bytes()).await {
+            ::log::warn!("could not write to stdin of process\n{err}");
+            return;
+        }
+        if let Err(err) = stdin.flush().await {
+            ::log::warn!("could not flush stdin of process\n{err}");
+            return;
+        }
+    }
+}
+
+/// Forward lines read from reader to sender as [Message::Output] and write
+/// them to sinks, lines are forwarded as raw bytes including line
+/// terminators.
+async fn forward_lines<R>(reader: Option<R>, mut sender: Sender<Message>, sinks: Vec<SharedFile>)
+where
+    R: AsyncRead + Unpin,
+{
+    let Some(reader) = reader else {
+        return;
+    };
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                for sink in &sinks {
+                    if let Err(err) = sink.lock().await.write_all(&line).await {
+                        ::log::warn!("could not write process output to file\n{err}");
+                    }
+                }
+                if sender
+                    .send(Message::Output(OutputLine::new(::std::mem::take(
+                        &mut line,
+                    ))))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            Err(err) => {
+                ::log::warn!("could not read process output\n{err}");
+                break;
+            }
+        }
+    }
+    for sink in &sinks {
+        if let Err(err) = sink.lock().await.flush().await {
+            ::log::warn!("could not write process output to file\n{err}");
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    //! Tests of waiting for processes.
+
+    use super::*;
+
+    /// Command running a shell script.
+    fn sh(script: &str) -> ::std::process::Command {
+        let mut command = ::std::process::Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+
+    /// Usage of a child is read when waiting blocking, along with its status.
+    #[test]
+    fn wait_blocking_usage() {
+        let child = sh("exit 3").spawn().unwrap();
+        let (status, usage) = wait_blocking(child, true).unwrap();
+        assert_eq!(status.code(), Some(3));
+        assert!(usage.is_some());
+
+        let child = sh("exit 0").spawn().unwrap();
+        let (status, usage) = wait_blocking(child, false).unwrap();
+        assert!(status.success());
+        assert_eq!(usage, None);
+    }
+
+    /// Usage of a child is read when waiting, along with its status.
+    #[tokio::test]
+    async fn wait_usage() {
+        let (mut child, _) = Spawned::spawn(
+            ::tokio::process::Command::from(sh("sleep 0.1; exit 4")),
+            true,
+        )
+        .unwrap();
+        let (status, usage) = child.wait_usage(None, true).await.unwrap();
+        assert_eq!(status.code(), Some(4));
+        assert!(usage.is_some());
+    }
+
+    /// A child taken out of tokio is reaped when stopped, after which its
+    /// pid is no longer used.
+    #[tokio::test]
+    async fn stop_owned() {
+        let (mut child, _) =
+            Spawned::spawn(::tokio::process::Command::from(sh("sleep 5")), true).unwrap();
+        assert!(matches!(child, Spawned::Owned(_)));
+        let shutdown = Shutdown {
+            signal: None,
+            grace: Duration::from_secs(5),
+        };
+        let status = shutdown.stop(&mut child, None).await.unwrap();
+        assert!(!status.success());
+        assert_eq!(child.id(), None);
+        assert_eq!(child.wait().await.unwrap(), status);
+    }
+
+    /// The group of a stopped child is killed, also when the child exits
+    /// within the grace period.
+    #[tokio::test]
+    async fn stop_kills_group() {
+        let marker = ::std::env::temp_dir().join(format!(
+            "command-runner-stop-kills-group-{}",
+            ::std::process::id()
+        ));
+        let script = format!(
+            "(trap '' TERM; sleep 0.5; touch '{}') & wait",
+            marker.display()
+        );
+        let mut command = ::tokio::process::Command::from(sh(&script));
+        isolate(&mut command);
+        let mut child = command.spawn().unwrap();
+        let group = Group::new(&child).unwrap();
+        // Give the shell time to start the descendant and set up its trap.
+        ::tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let shutdown = Shutdown {
+            signal: Some(KillSignal::Term),
+            grace: Duration::from_secs(5),
+        };
+        let status = shutdown.stop(&mut child, Some(&group)).await.unwrap();
+        assert!(!status.success());
+
+        ::tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!marker.exists(), "descendant of stopped child kept running");
+    }
+}
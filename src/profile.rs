@@ -0,0 +1,185 @@
+//! [Profile] impl.
+
+use ::std::{fmt::Display, path::PathBuf};
+
+use ::serde::{Deserialize, Serialize};
+
+use crate::{arg::Arg, config::Config, env};
+
+/// Named variation of a config, set values replace those of the config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Executable path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exe: Option<String>,
+    /// Application arguments.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arg: Option<Vec<Arg>>,
+    /// Run through system shell.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<bool>,
+    /// Working directory of executable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Environment variables, replacing those of the config with the same
+    /// name and adding the rest.
+    #[serde(with = "crate::env", skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<(String, String)>,
+}
+
+impl Profile {
+    /// Apply profile to config, replacing values set by profile.
+    pub fn apply(&self, config: &mut Config) {
+        let Self {
+            exe,
+            arg,
+            shell,
+            cwd,
+            env,
+        } = self;
+
+        if let Some(exe) = exe {
+            config.exe.clone_from(exe);
+        }
+
+        if let Some(arg) = arg {
+            config.arg.clone_from(arg);
+        }
+
+        if let Some(shell) = shell {
+            config.shell = *shell;
+        }
+
+        if let Some(cwd) = cwd {
+            config.cwd = Some(cwd.clone());
+        }
+
+        env::apply_overrides(&mut config.env, env);
+    }
+
+    /// Move values of config differing from those of base to the profile,
+    /// resetting them to those of base, such that applying the profile to
+    /// config gives it back. A working directory or environment variables
+    /// removed from config cannot be expressed by a profile and are kept as
+    /// in base.
+    pub fn extract(&mut self, base: &Config, config: &mut Config) {
+        let Self {
+            exe,
+            arg,
+            shell,
+            cwd,
+            env,
+        } = self;
+
+        *exe = (config.exe != base.exe).then(|| config.exe.clone());
+        config.exe.clone_from(&base.exe);
+
+        *arg = (config.arg != base.arg).then(|| config.arg.clone());
+        config.arg.clone_from(&base.arg);
+
+        *shell = (config.shell != base.shell).then_some(config.shell);
+        config.shell = base.shell;
+
+        *cwd = config
+            .cwd
+            .clone()
+            .filter(|cwd| base.cwd.as_ref() != Some(cwd));
+        config.cwd.clone_from(&base.cwd);
+
+        *env = env::overrides(&base.env, &config.env);
+        config.env.clone_from(&base.env);
+    }
+}
+
+/// Choice of profile, [None] being the config itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileChoice(pub Option<String>);
+
+impl Display for ProfileChoice {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match &self.0 {
+            Some(name) => f.write_str(name),
+            None => f.write_str("Default"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of selecting profiles and saving to them.
+
+    use super::*;
+    use crate::state::State;
+
+    /// Config with a profile `p` overriding some values.
+    fn config() -> Config {
+        ::toml::from_str(
+            r#"
+            exe = "tool"
+            arg = ["--base"]
+            env = { A = "base", B = "base" }
+
+            [profile.p]
+            arg = ["--profile"]
+            env = { B = "profile" }
+            "#,
+        )
+        .unwrap()
+    }
+
+    /// Selecting a profile applies it, and edits are extracted back into it
+    /// such that applying it again gives the edited config.
+    #[test]
+    fn select_and_extract() {
+        let base = config();
+        let mut edited = base.with_profile(Some("p"));
+        assert_eq!(edited.active_profile.as_deref(), Some("p"));
+        assert_eq!(edited.arg, [Arg::from("--profile".to_owned())]);
+        assert_eq!(
+            edited.env,
+            [
+                ("A".to_owned(), "base".to_owned()),
+                ("B".to_owned(), "profile".to_owned())
+            ]
+        );
+
+        edited.exe = "other".to_owned();
+        let mut saved = edited.clone();
+        let mut profile = base.profile["p"].clone();
+        profile.extract(&base, &mut saved);
+        assert_eq!(saved.exe, base.exe);
+        assert_eq!(saved.arg, base.arg);
+        assert_eq!(saved.env, base.env);
+        assert_eq!(profile.exe.as_deref(), Some("other"));
+        assert_eq!(profile.arg, Some(vec![Arg::from("--profile".to_owned())]));
+        assert_eq!(profile.env, [("B".to_owned(), "profile".to_owned())]);
+
+        profile.apply(&mut saved);
+        assert_eq!(saved.exe, edited.exe);
+        assert_eq!(saved.arg, edited.arg);
+        assert_eq!(saved.env, edited.env);
+    }
+
+    /// An unknown profile selects nothing, and edits are saved to the config
+    /// itself.
+    #[test]
+    fn unknown_profile() {
+        let base = config();
+        let selected = base.with_profile(Some("missing"));
+        assert_eq!(selected.active_profile, None);
+        assert_eq!(selected.arg, base.arg);
+
+        let state = State {
+            profile: Some("missing".to_owned()),
+            loaded: base.clone(),
+            ..State::default()
+        };
+        let mut edited = base;
+        edited.exe = "other".to_owned();
+        let saved = state.for_save(edited);
+        assert_eq!(saved.exe, "other");
+        assert!(!saved.profile.contains_key("missing"));
+        assert_eq!(saved.profile["p"].exe, None);
+    }
+}
@@ -0,0 +1,69 @@
+//! Tracking of where values of config fields came from.
+
+use ::std::{collections::BTreeMap, fmt::Display};
+
+use crate::config::Config;
+
+/// Where the value of a config field came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Given on the command line.
+    Cli,
+    /// Loaded from a config file or the library.
+    File,
+    /// Changed in the ui.
+    Edited,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        f.write_str(match self {
+            Source::Cli => "command line",
+            Source::File => "config file",
+            Source::Edited => "edited",
+        })
+    }
+}
+
+/// Sources of config fields, keyed by the name fields are serialized as.
+/// Only fields set to something other than their default are tracked.
+#[derive(Debug, Clone, Default)]
+pub struct Provenance {
+    /// Source of each set field.
+    sources: BTreeMap<String, Source>,
+}
+
+impl Provenance {
+    /// Record fields set by config as coming from source, such as fields
+    /// of a loaded config replacing those given on the command line.
+    pub fn record(&mut self, config: &Config, source: Source) {
+        for (key, _) in table(config) {
+            self.sources.insert(key, source);
+        }
+    }
+
+    /// Sources of set fields in order of name, fields where edited differs
+    /// from loaded are [Source::Edited].
+    pub fn sources(&self, loaded: &Config, edited: Option<&Config>) -> Vec<(String, Source)> {
+        let mut sources = self.sources.clone();
+        if let Some(edited) = edited {
+            let loaded = table(loaded);
+            let edited = table(edited);
+            for key in loaded.keys().chain(edited.keys()) {
+                if loaded.get(key) != edited.get(key) {
+                    sources.insert(key.clone(), Source::Edited);
+                }
+            }
+        }
+        sources.into_iter().collect()
+    }
+}
+
+/// Config serialized as a table, such that fields left at their default are
+/// not present.
+fn table(config: &Config) -> ::toml::Table {
+    ::toml::Table::try_from(config).unwrap_or_else(|err| {
+        ::log::warn!("could not serialize config to track sources\n{err}");
+        ::toml::Table::new()
+    })
+}
@@ -0,0 +1,105 @@
+//! [RunQueue] impl.
+
+use ::std::path::PathBuf;
+
+use ::serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{Config, LoadError, SaveError},
+    paths,
+};
+
+/// Configs queued to run one after another, persisted such that the queue
+/// survives restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunQueue {
+    /// Queued configs, in the order they are ran.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub configs: Vec<Config>,
+}
+
+impl RunQueue {
+    /// Path queue is stored at, if any.
+    pub fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join("queue.toml"))
+    }
+
+    /// Load queue, a missing file results in an empty queue.
+    ///
+    /// # Errors
+    /// If queue exists but cannot be read [LoadError::Read] is returned.
+    /// If queue cannot be deserialized [LoadError::Deserialize] is returned.
+    pub async fn load() -> Result<Self, LoadError> {
+        paths::load(Self::path()).await
+    }
+
+    /// Save queue.
+    ///
+    /// # Errors
+    /// If queue cannot be serialized [SaveError::Serialize] is returned.
+    /// If queue cannot be written [SaveError::Write] is returned.
+    pub async fn save(self) -> Result<(), SaveError> {
+        paths::save(&self, Self::path()).await
+    }
+
+    /// Move config at index by offset, clamped to the bounds of the queue.
+    pub fn move_config(&mut self, index: usize, offset: isize) {
+        if index >= self.configs.len() {
+            return;
+        }
+        let target = index
+            .saturating_add_signed(offset)
+            .min(self.configs.len() - 1);
+        let config = self.configs.remove(index);
+        self.configs.insert(target, config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of persisting the run queue.
+
+    use super::*;
+
+    /// Queued configs are saved and loaded with the environment of their
+    /// active profile.
+    #[tokio::test]
+    async fn round_trip() {
+        let config: Config = ::toml::from_str(
+            r#"
+            exe = "tool"
+            arg = ["--a"]
+            env = { A = "base" }
+            env_when = [
+                { name = "A", value = "profile", profile = "p" },
+                { name = "B", value = "other", profile = "q" },
+            ]
+            profile.p = {}
+            "#,
+        )
+        .unwrap();
+        let config = config.with_profile(Some("p")).resolve_conditions();
+        let queue = RunQueue {
+            configs: vec![config.clone(), Config::default()],
+        };
+
+        let path = ::std::env::temp_dir().join(format!(
+            "command-runner-queue-round-trip-{}.toml",
+            ::std::process::id()
+        ));
+        paths::save(&queue, Some(path.clone())).await.unwrap();
+        let loaded = paths::load::<RunQueue>(Some(path.clone())).await;
+        _ = ::std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.configs.len(), 2);
+        let restored = &loaded.configs[0];
+        assert_eq!(restored.exe, "tool");
+        assert_eq!(restored.arg, config.arg);
+        assert_eq!(
+            restored.expand_paths().unwrap().env,
+            [("A".to_owned(), "profile".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Quoting of arguments for display and for shells.
+
+use ::std::borrow::Cow;
+
+use ::clap::ValueEnum;
+use ::serde::{Deserialize, Serialize};
+
+/// Rules used when quoting arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Quoting {
+    /// POSIX sh, arguments are single quoted.
+    #[default]
+    Posix,
+    /// `cmd.exe`, arguments are quoted as parsed by the C runtime and
+    /// special characters are escaped using `^`.
+    Cmd,
+    /// PowerShell, arguments are single quoted.
+    #[value(name = "powershell")]
+    PowerShell,
+}
+
+impl Quoting {
+    /// Quote arg such that it is read back as a single argument.
+    pub fn quote(self, arg: &str) -> Cow<'_, str> {
+        match self {
+            Quoting::Posix => ::shell_words::quote(arg),
+            Quoting::Cmd => quote_cmd(arg),
+            Quoting::PowerShell => quote_powershell(arg),
+        }
+    }
+
+    /// Quote and join args separated by spaces.
+    pub fn join<'a>(self, args: impl IntoIterator<Item = &'a str>) -> String {
+        args.into_iter()
+            .map(|arg| self.quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Characters interpreted by `cmd.exe` even when quoted by the C runtime.
+const CMD_SPECIAL: [char; 10] = ['(', ')', '%', '!', '^', '"', '<', '>', '&', '|'];
+
+/// Quote arg as parsed by the C runtime, escaping special characters of
+/// `cmd.exe` using `^`.
+fn quote_cmd(arg: &str) -> Cow<'_, str> {
+    let quoted = quote_argv(arg);
+    if !quoted.contains(CMD_SPECIAL) {
+        return quoted;
+    }
+    let mut escaped = String::with_capacity(quoted.len() * 2);
+    for c in quoted.chars() {
+        if CMD_SPECIAL.contains(&c) {
+            escaped.push('^');
+        }
+        escaped.push(c);
+    }
+    Cow::Owned(escaped)
+}
+
+/// Quote arg as parsed by the C runtime, backslashes are only special
+/// when followed by a quote.
+fn quote_argv(arg: &str) -> Cow<'_, str> {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '\u{b}', '"']) {
+        return Cow::Borrowed(arg);
+    }
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.extend(::std::iter::repeat_n('\\', backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            c => {
+                quoted.extend(::std::iter::repeat_n('\\', backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    quoted.extend(::std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
+
+/// Quote arg for PowerShell using single quotes, in which only single
+/// quotes, including typographic ones, need escaping by doubling.
+fn quote_powershell(arg: &str) -> Cow<'_, str> {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./\\:=+".contains(c))
+    {
+        return Cow::Borrowed(arg);
+    }
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('\'');
+    for c in arg.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    Cow::Owned(quoted)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of quoting.
+
+    use super::*;
+
+    /// Plain arguments are kept as is.
+    #[test]
+    fn plain() {
+        for quoting in [Quoting::Posix, Quoting::Cmd, Quoting::PowerShell] {
+            assert_eq!(quoting.quote("plain-arg"), "plain-arg", "{quoting:?}");
+        }
+    }
+
+    /// Empty arguments are quoted such that they are not dropped.
+    #[test]
+    fn empty() {
+        assert_eq!(Quoting::Posix.quote(""), "''");
+        assert_eq!(Quoting::Cmd.quote(""), "^\"^\"");
+        assert_eq!(Quoting::PowerShell.quote(""), "''");
+    }
+
+    /// Arguments with spaces are quoted.
+    #[test]
+    fn spaces() {
+        assert_eq!(Quoting::Posix.quote("a b"), "'a b'");
+        assert_eq!(Quoting::Cmd.quote("a b"), "^\"a b^\"");
+        assert_eq!(Quoting::PowerShell.quote("a b"), "'a b'");
+    }
+
+    /// Quotes within arguments are escaped.
+    #[test]
+    fn quotes() {
+        assert_eq!(Quoting::Posix.quote("it's"), r"'it'\''s'");
+        assert_eq!(Quoting::Posix.quote(r#"say "hi""#), r#"'say "hi"'"#);
+        assert_eq!(Quoting::Cmd.quote(r#"say "hi""#), r#"^"say \^"hi\^"^""#);
+        assert_eq!(Quoting::PowerShell.quote("it's"), "'it''s'");
+    }
+
+    /// Backslashes are only escaped by cmd when followed by a quote, here
+    /// the closing one.
+    #[test]
+    fn cmd_backslashes() {
+        assert_eq!(Quoting::Cmd.quote(r"C:\dir\file"), r"C:\dir\file");
+        assert_eq!(Quoting::Cmd.quote(r"C:\my dir\"), r#"^"C:\my dir\\^""#);
+    }
+
+    /// Special characters of cmd, including the quotes added by quoting, are
+    /// escaped using `^`.
+    #[test]
+    fn cmd_special() {
+        assert_eq!(Quoting::Cmd.quote("a&b"), "a^&b");
+        assert_eq!(Quoting::Cmd.quote("50% off"), "^\"50^% off^\"");
+    }
+
+    /// Joined arguments are separated by spaces.
+    #[test]
+    fn join() {
+        assert_eq!(Quoting::Posix.join(["echo", "a b", ""]), "echo 'a b' ''");
+        assert_eq!(
+            Quoting::Cmd.join(["echo", "a b", ""]),
+            "echo ^\"a b^\" ^\"^\""
+        );
+    }
+}
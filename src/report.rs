@@ -0,0 +1,240 @@
+//! Reports of run results, exported as Markdown or JSON.
+
+use ::std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    time::Duration,
+};
+
+use ::rfd::AsyncFileDialog;
+use ::serde::Serialize;
+
+use crate::{
+    Message,
+    config::{self, RunResult},
+    status::Level,
+};
+
+/// Max amount of trailing output lines kept per run.
+pub const OUTPUT_LINES: usize = 20;
+
+/// Error raised when a report cannot be exported.
+#[derive(Debug, ::thiserror::Error)]
+pub enum ReportError {
+    /// Report could not be serialized.
+    #[error("could not serialize report\n{0}")]
+    Serialize(#[from] ::serde_json::Error),
+
+    /// Report could not be written.
+    #[error("could not write report to {path:?}\n{source}")]
+    Write {
+        /// Path that could not be written.
+        path: PathBuf,
+        /// Error source.
+        #[source]
+        source: ::std::io::Error,
+    },
+
+    /// No file was selected.
+    #[error("no file selected using dialog")]
+    NoneSelected,
+}
+
+impl From<ReportError> for Message {
+    fn from(value: ReportError) -> Self {
+        match value {
+            ReportError::Serialize(_) => {
+                Message::SetStatusLevel("could not serialize report".into(), Level::Error)
+            }
+            ReportError::Write { path, source: _ } => {
+                Message::SetStatusLevel(format!("could not write {path:?}"), Level::Error)
+            }
+            ReportError::NoneSelected => {
+                Message::SetStatusLevel("no path entered".into(), Level::Warning)
+            }
+        }
+    }
+}
+
+/// Format a report is exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Human readable Markdown.
+    Markdown,
+    /// Machine readable JSON.
+    Json,
+}
+
+impl ReportFormat {
+    /// Format of path by its extension, JSON for `.json` and Markdown
+    /// otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// Result of a single run in a report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportEntry {
+    /// Command line that was ran.
+    pub command: String,
+    /// Exit code, if the process could be ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Error, if the process could not be ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Seconds from start until run finished.
+    pub duration_secs: f64,
+    /// Last [OUTPUT_LINES] lines of output.
+    pub output: String,
+    /// Set if output lines were dropped.
+    pub output_truncated: bool,
+}
+
+impl ReportEntry {
+    /// Create entry of a run of command, keeping the tail of output.
+    pub fn new(
+        command: String,
+        result: Result<ExitStatus, String>,
+        duration: Duration,
+        output: &str,
+    ) -> Self {
+        let (exit_code, error) = match result {
+            Ok(status) => (Some(config::exit_code(status)), None),
+            Err(err) => (None, Some(err)),
+        };
+        let (output, output_truncated) = tail(output, OUTPUT_LINES);
+        Self {
+            command,
+            exit_code,
+            error,
+            duration_secs: duration.as_secs_f64(),
+            output,
+            output_truncated,
+        }
+    }
+
+    /// Create entry from a [RunResult] of command, stdout is followed by
+    /// stderr in the output.
+    pub fn from_result(command: String, result: &RunResult) -> Self {
+        let mut output = String::new();
+        for stream in [&result.stdout, &result.stderr].into_iter().flatten() {
+            output.push_str(&String::from_utf8_lossy(stream));
+        }
+        Self::new(command, Ok(result.status), result.duration, &output)
+    }
+}
+
+/// Last lines of text, and whether any lines were dropped.
+fn tail(text: &str, lines: usize) -> (String, bool) {
+    let text = text.trim_end_matches('\n');
+    let count = text.lines().count();
+    if count <= lines {
+        return (text.to_owned(), false);
+    }
+    let kept = text.lines().skip(count - lines).collect::<Vec<_>>();
+    (kept.join("\n"), true)
+}
+
+/// Results of runs, in the order they finished.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    /// Entries of runs.
+    pub runs: Vec<ReportEntry>,
+}
+
+impl Report {
+    /// Add entry of a run.
+    pub fn push(&mut self, entry: ReportEntry) {
+        self.runs.push(entry);
+    }
+
+    /// Check if no runs have been added.
+    pub const fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Report as Markdown, with a summary table followed by output of each
+    /// run.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Run Report\n\n| # | Command | Exit | Duration |\n");
+        markdown.push_str("|---|---|---|---|\n");
+        for (index, run) in self.runs.iter().enumerate() {
+            let exit = run
+                .exit_code
+                .map_or_else(|| "error".to_owned(), |code| code.to_string());
+            _ = writeln!(
+                markdown,
+                "| {} | `{}` | {exit} | {:.2}s |",
+                index + 1,
+                run.command.replace('|', "\\|").replace('`', "'"),
+                run.duration_secs,
+            );
+        }
+        for (index, run) in self.runs.iter().enumerate() {
+            _ = write!(markdown, "\n## {}. `{}`\n\n", index + 1, run.command);
+            if let Some(error) = &run.error {
+                _ = writeln!(markdown, "Error: {error}\n");
+            }
+            if run.output_truncated {
+                _ = writeln!(markdown, "Last {OUTPUT_LINES} lines of output:\n");
+            }
+            _ = writeln!(markdown, "```\n{}\n```", run.output);
+        }
+        markdown
+    }
+
+    /// Report as pretty printed JSON.
+    ///
+    /// # Errors
+    /// If the report cannot be serialized.
+    pub fn to_json(&self) -> Result<String, ReportError> {
+        Ok(::serde_json::to_string_pretty(self)?)
+    }
+
+    /// Report in format.
+    ///
+    /// # Errors
+    /// If the report cannot be serialized.
+    pub fn render(&self, format: ReportFormat) -> Result<String, ReportError> {
+        match format {
+            ReportFormat::Markdown => Ok(self.to_markdown()),
+            ReportFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Save report to path, in the format given by its extension.
+    ///
+    /// # Errors
+    /// If report cannot be serialized [ReportError::Serialize] is returned.
+    /// If report cannot be written [ReportError::Write] is returned.
+    pub async fn save(self, path: PathBuf) -> Result<PathBuf, ReportError> {
+        let content = self.render(ReportFormat::from_path(&path))?;
+        match ::tokio::fs::write(&path, content).await {
+            Ok(()) => Ok(path),
+            Err(source) => Err(ReportError::Write { path, source }),
+        }
+    }
+}
+
+/// Export report dialog.
+///
+/// # Errors
+/// If nothing was selected [ReportError::NoneSelected] is returned.
+pub async fn save_dialog() -> Result<PathBuf, ReportError> {
+    match AsyncFileDialog::new()
+        .set_title("Export Report")
+        .add_filter("Markdown", &["md"])
+        .add_filter("JSON", &["json"])
+        .save_file()
+        .await
+    {
+        Some(handle) => Ok(handle.path().to_path_buf()),
+        None => Err(ReportError::NoneSelected),
+    }
+}
@@ -1,7 +1,105 @@
 //! [State] impl.
-use ::iced::widget::text_editor;
+use ::std::{
+    collections::VecDeque,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
 
-use crate::{Message, config::Config};
+use ::iced::widget::text_editor::{self, Action, Edit, Motion};
+use ::tokio::sync::watch;
+
+use crate::{
+    Message, arg::Arg, browser::BrowserEntry, compare::Comparison, config::Config,
+    diagnostics::Diagnostics, env, highlight::Highlighter, launch::Launch, library::Library,
+    output::OutputBuffer, pick::Pick, process::InputSender, profile::ProfileChoice,
+    provenance::Provenance, queue::RunQueue, report::Report, status::Level, switcher::Switcher,
+    token,
+};
+
+/// Time toasts are shown for.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// Max amount of toasts shown at once, the oldest are dropped first.
+const MAX_TOASTS: usize = 4;
+
+/// Debugger used when running under a debugger without one given.
+pub const DEFAULT_DEBUGGER: &str = if cfg!(target_os = "macos") {
+    "lldb"
+} else {
+    "gdb"
+};
+
+/// Transient notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    /// Text of toast.
+    pub text: String,
+    /// Severity of toast.
+    pub level: Level,
+    /// When toast is removed.
+    pub expires_at: Instant,
+}
+
+/// Process running in the background.
+#[derive(Debug)]
+pub struct Background {
+    /// Id of process, unique for the session.
+    pub id: u64,
+    /// Command line of process.
+    pub command: String,
+    /// Pid of process, known once it has been spawned.
+    pub pid: Option<u32>,
+    /// When process was started.
+    pub started: Instant,
+    /// Exit codes considered successful, see [Config::success_codes].
+    pub success_codes: Vec<i32>,
+    /// Used to kill process.
+    pub kill: watch::Sender<u64>,
+}
+
+/// Background run waiting for a running one to finish.
+#[derive(Debug)]
+pub struct Queued {
+    /// Id process will have, unique for the session.
+    pub id: u64,
+    /// Config to run.
+    pub config: Config,
+}
+
+/// Dialog or load that may be cancelled.
+#[derive(Debug)]
+pub struct Pending {
+    /// Id of pending task, unique for the session.
+    pub id: u64,
+    /// What is pending, shown in the ui.
+    pub description: String,
+    /// Used to abort task.
+    pub handle: ::iced::task::Handle,
+}
+
+/// Argument of loaded config that is not plain, see [Arg::is_plain].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedArg {
+    /// Amount of plain arguments before argument in the loaded config, such
+    /// that it keeps its position among them.
+    pub position: usize,
+    /// Argument.
+    pub arg: Arg,
+}
+
+/// Executable, plain arguments and environment variables of state, see
+/// [State::command_parts].
+#[derive(Debug)]
+struct CommandParts {
+    /// Executable.
+    exe: String,
+    /// Arguments without platform, description or enabled state.
+    plain_args: Vec<String>,
+    /// Positions among plain arguments of those dropped, in order.
+    dropped: Vec<usize>,
+    /// Environment variables.
+    env: Vec<(String, String)>,
+}
 
 /// Reloadable application state.
 #[derive(Debug, Default)]
@@ -12,6 +110,150 @@ pub struct State {
     pub args: text_editor::Content,
     /// Status line.
     pub status: String,
+    /// Severity of status line.
+    pub status_level: Level,
+    /// Shown toasts, oldest first.
+    pub toasts: VecDeque<Toast>,
+    /// Captured process output.
+    pub output: OutputBuffer,
+    /// Highlight rules of config applied to output.
+    pub highlighter: Highlighter,
+    /// Output has been scrolled up from the bottom, and no longer follows
+    /// new lines.
+    pub output_scrolled_up: bool,
+    /// Config most recently ran, with output paths not yet rendered.
+    pub last_run: Option<Config>,
+    /// Arguments of loaded config with a platform, description or enabled
+    /// state, these are not editable as text but may be toggled, and are
+    /// kept at their position among plain arguments when converting to a
+    /// config.
+    pub tagged_args: Vec<TaggedArg>,
+    /// Environment variables as `NAME=VALUE` lines.
+    pub env: text_editor::Content,
+    /// Names of variables removed from the inherited environment, separated
+    /// by whitespace.
+    pub env_unset: String,
+    /// Environment variables are shown.
+    pub show_env: bool,
+    /// Scratch text for composing arguments, never saved or ran.
+    pub scratch: text_editor::Content,
+    /// Scratch text area is shown.
+    pub show_scratch: bool,
+    /// Edit arguments as a table instead of as text.
+    pub args_table: bool,
+    /// Arguments when editing as a table.
+    pub args_vec: Vec<String>,
+    /// Error of last run, shown until dismissed or a new run starts.
+    pub error_banner: Option<String>,
+    /// Run through system shell.
+    pub shell: bool,
+    /// Working directory.
+    pub cwd: String,
+    /// Wrapper command as shell quoted text.
+    pub wrapper: String,
+    /// Name executable is invoked as, empty for the executable path.
+    pub arg0: String,
+    /// How executable is launched.
+    pub launch: Launch,
+    /// Installed apps of launch mode, for selection as executable.
+    pub launch_apps: Vec<String>,
+    /// Locale set for executable, empty for none.
+    pub locale: String,
+    /// Trim arguments and drop empty ones when converting to a config.
+    pub trim_args: bool,
+    /// Move leading `NAME=VALUE` arguments to the environment when
+    /// converting to a config.
+    pub inline_env: bool,
+    /// Run executable under a debugger.
+    pub use_debugger: bool,
+    /// Debugger executable is ran under, empty for [DEFAULT_DEBUGGER].
+    pub debugger: String,
+    /// Config as loaded, fields not editable in the ui are kept from it when
+    /// converting to a config unless one has been merged.
+    pub loaded: Config,
+    /// Config merged into the edits, fields not editable in the ui are kept
+    /// from it instead of from the loaded config until reloaded.
+    pub merged: Option<Config>,
+    /// Path config was loaded from or last saved to.
+    pub config_path: Option<PathBuf>,
+    /// Used to kill running process, present while a process is running.
+    pub kill: Option<watch::Sender<u64>>,
+    /// Sends input to stdin of running process, present while it is open.
+    pub input: Option<InputSender>,
+    /// Line of input being typed for running process.
+    pub input_line: String,
+    /// When running process was started.
+    pub run_started: Option<SystemTime>,
+    /// Position in output where that of running process starts, see
+    /// [OutputBuffer::end].
+    pub run_output_start: usize,
+    /// Amount of runs started this session.
+    pub run_count: u64,
+    /// Processes running in the background.
+    pub background: Vec<Background>,
+    /// Results of runs finished this session, for exporting.
+    pub report: Report,
+    /// Show statistics of run durations.
+    pub show_stats: bool,
+    /// Show where values of config fields came from.
+    pub show_sources: bool,
+    /// Where values of config fields came from.
+    pub provenance: Provenance,
+    /// Show config browser.
+    pub show_browser: bool,
+    /// Config files listed by the config browser, [None] until scanned.
+    pub browser: Option<Vec<BrowserEntry>>,
+    /// Background runs waiting for running ones to finish, in order.
+    pub background_queue: VecDeque<Queued>,
+    /// Id of next background process.
+    pub next_background_id: u64,
+    /// Dialog or load that may be cancelled, if any.
+    pub pending: Option<Pending>,
+    /// Id of next pending task.
+    pub next_pending_id: u64,
+    /// Last seen modification time of config file when watching.
+    pub config_modified: Option<SystemTime>,
+    /// When a change of the config file was last seen, cleared when acted
+    /// upon.
+    pub change_seen: Option<Instant>,
+    /// Load and run config again when running process finishes.
+    pub restart: bool,
+    /// Run most recently ran config again when running process finishes.
+    pub relaunch: bool,
+    /// Name of selected profile of config, [None] for the config itself.
+    pub profile: Option<String>,
+    /// Exit once running processes, including background ones, have been
+    /// killed.
+    pub exit_pending: bool,
+    /// Overflow row of actions is shown.
+    pub show_overflow: bool,
+    /// Reload was requested with unsaved changes, and awaits confirmation.
+    pub confirm_reload: bool,
+    /// Profile selected while there were unsaved changes, applied once
+    /// reloaded.
+    pub pending_profile: Option<ProfileChoice>,
+    /// Run of a config requiring confirmation awaits it.
+    pub confirm_run: bool,
+    /// Quick switcher, present while open.
+    pub switcher: Option<Switcher>,
+    /// Config library, present once loaded.
+    pub library: Option<Library>,
+    /// Config library is shown.
+    pub show_library: bool,
+    /// Name current config is saved to the library as.
+    pub library_name: String,
+    /// Configs queued to run one after another.
+    pub run_queue: RunQueue,
+    /// Show run queue.
+    pub show_queue: bool,
+    /// Queue is being processed, its first config is running.
+    pub queue_running: bool,
+    /// Offer to resume queue left over from a previous session.
+    pub resume_queue: bool,
+    /// Comparison with another config, present while shown.
+    pub comparison: Option<Comparison>,
+    /// Diagnostics, present while shown.
+    pub diagnostics: Option<Diagnostics>,
 }
 
 impl State {
@@ -20,24 +262,528 @@ impl State {
     /// # Errors
     /// If current state cannot be converted to a config.
     pub fn to_config(&self) -> Result<Config, ToConfigError> {
-        let arg = ::shell_words::split(&self.args.text())?;
-        let exe = self.exe.clone();
+        let CommandParts {
+            exe,
+            plain_args,
+            dropped,
+            env,
+        } = self.command_parts()?;
+        let mut plain_args = plain_args.into_iter().map(Arg::from);
+        let mut arg = Vec::new();
+        let mut position = 0;
+        for tagged in &self.tagged_args {
+            // Dropped plain arguments before a tagged one no longer count
+            // towards its position.
+            let tagged_position = tagged.position
+                - dropped
+                    .iter()
+                    .take_while(|dropped| **dropped < tagged.position)
+                    .count();
+            arg.extend(plain_args.by_ref().take(tagged_position - position));
+            position = tagged_position;
+            arg.push(tagged.arg.clone());
+        }
+        arg.extend(plain_args);
+        let shell = self.shell;
+        let cwd = (!self.cwd.is_empty()).then(|| PathBuf::from(&self.cwd));
+        let arg0 = (!self.arg0.is_empty()).then(|| self.arg0.clone());
+        let wrapper = ::shell_words::split(&self.wrapper)?;
+        let debugger = self.debugger();
+        let locale = Some(self.locale.trim().to_owned()).filter(|locale| !locale.is_empty());
 
-        Ok(Config { exe, arg })
+        Ok(Config {
+            exe,
+            arg,
+            arg0,
+            shell,
+            cwd,
+            env,
+            env_unset: self
+                .env_unset
+                .split_whitespace()
+                .map(String::from)
+                .collect(),
+            locale,
+            trim_args: self.trim_args,
+            inline_env: self.inline_env,
+            launch: self.launch,
+            active_profile: self.profile.clone(),
+            wrapper,
+            debugger,
+            ..self.merged.as_ref().unwrap_or(&self.loaded).clone()
+        })
+    }
+
+    /// Executable, plain arguments and environment variables as converted to
+    /// a config, with arguments trimmed if enabled. If leading assignments
+    /// are enabled and no executable is set they are moved to the
+    /// environment, a following `--` is dropped and the first remaining
+    /// argument is used as the executable. The positions of dropped
+    /// arguments are kept, such that tagged arguments keep their place among
+    /// the rest.
+    ///
+    /// # Errors
+    /// If arguments cannot be split or environment variables parsed.
+    fn command_parts(&self) -> Result<CommandParts, ToConfigError> {
+        let mut plain_args = self.plain_args()?;
+        // Positions of kept plain arguments.
+        let mut positions = (0..plain_args.len()).collect::<Vec<_>>();
+        let mut dropped = Vec::new();
+        if self.trim_args {
+            let (kept, empty) = plain_args
+                .iter()
+                .map(|arg| arg.trim())
+                .enumerate()
+                .partition::<Vec<_>, _>(|(_, arg)| !arg.is_empty());
+            dropped.extend(empty.into_iter().map(|(position, _)| position));
+            (positions, plain_args) = kept
+                .into_iter()
+                .map(|(position, arg)| (position, arg.to_owned()))
+                .unzip();
+        }
+        let mut exe = self.exe.clone();
+        let mut env = env::parse_lines(&self.env.text()).map_err(ToConfigError::Env)?;
+        if self.inline_env && exe.is_empty() {
+            let len = plain_args.len();
+            let assignments = env::take_assignments(&mut plain_args);
+            env::apply_overrides(&mut env, &assignments);
+            if plain_args.first().is_some_and(|arg| arg == "--") {
+                plain_args.remove(0);
+            }
+            if !plain_args.is_empty() {
+                exe = plain_args.remove(0);
+            }
+            dropped.extend(positions.drain(..len - plain_args.len()));
+            dropped.sort_unstable();
+        }
+        Ok(CommandParts {
+            exe,
+            plain_args,
+            dropped,
+            env,
+        })
+    }
+
+    /// Prepare config converted from state for saving. When a profile is
+    /// selected, values differing from those of the loaded config are saved
+    /// to the profile instead of to the config, see
+    /// [Profile::extract][crate::profile::Profile::extract].
+    pub fn for_save(&self, mut config: Config) -> Config {
+        if let Some(name) = &self.profile
+            && let Some(mut profile) = config.profile.get(name).cloned()
+        {
+            profile.extract(&self.loaded, &mut config);
+            config.profile.insert(name.clone(), profile);
+        }
+        config
+    }
+
+    /// Set editable state from config.
+    pub fn set_config(&mut self, config: &Config) {
+        let Config {
+            exe,
+            arg,
+            arg0,
+            launch,
+            wrapper,
+            debugger,
+            shell,
+            shell_program: _,
+            shell_args: _,
+            quoting: _,
+            cwd,
+            create_cwd: _,
+            env,
+            env_unset,
+            path: _,
+            locale,
+            stdin_null: _,
+            trim_args,
+            inline_env,
+            strict_expand: _,
+            glob: _,
+            strict_glob: _,
+            success_codes: _,
+            kill_signal: _,
+            kill_grace_secs: _,
+            umask: _,
+            audit_log: _,
+            audit_redact_env: _,
+            output_log: _,
+            stdout_file: _,
+            stderr_file: _,
+            resource_usage: _,
+            confirm_before_run: _,
+            accent: _,
+            palette: _,
+            highlight: _,
+            profile: _,
+            env_when: _,
+            env_from_file: _,
+            active_profile: _,
+            pre_run: _,
+            post_run: _,
+            on_success: _,
+            on_failure: _,
+        } = config;
+        self.set_args(&Arg::join_plain(arg));
+        let mut position = 0;
+        self.tagged_args = arg
+            .iter()
+            .filter_map(|arg| {
+                if arg.is_plain() {
+                    position += 1;
+                    return None;
+                }
+                Some(TaggedArg {
+                    position,
+                    arg: arg.clone(),
+                })
+            })
+            .collect();
+        self.exe = exe.clone();
+        self.wrapper = ::shell_words::join(wrapper);
+        self.arg0 = arg0.clone().unwrap_or_default();
+        self.use_debugger = debugger.is_some();
+        self.debugger = debugger.clone().unwrap_or_default();
+        self.shell = *shell;
+        self.cwd = cwd
+            .as_ref()
+            .map(|cwd| cwd.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.env = text_editor::Content::with_text(&env::to_lines(env));
+        self.env_unset = env_unset.join(" ");
+        self.locale = locale.clone().unwrap_or_default();
+        self.trim_args = *trim_args;
+        self.inline_env = *inline_env;
+        self.launch = *launch;
+    }
+
+    /// Debugger executable is ran under, if enabled.
+    pub fn debugger(&self) -> Option<String> {
+        self.use_debugger.then(|| {
+            let debugger = self.debugger.trim();
+            if debugger.is_empty() {
+                DEFAULT_DEBUGGER.to_owned()
+            } else {
+                debugger.to_owned()
+            }
+        })
+    }
+
+    /// Arguments of the active editing mode.
+    ///
+    /// # Errors
+    /// If arguments are edited as text and cannot be split.
+    pub fn plain_args(&self) -> Result<Vec<String>, ToConfigError> {
+        if self.args_table {
+            Ok(self.args_vec.clone())
+        } else {
+            Ok(::shell_words::split(&self.args.text())?)
+        }
+    }
+
+    /// Check if editable state differs from config, in which case reloading
+    /// would discard changes. Both are compared with argument options
+    /// applied, see [Config::apply_arg_options]. State that cannot be
+    /// converted to a config and merged configs count as changed.
+    pub fn is_dirty(&self, config: &Config) -> bool {
+        let Ok(mut edited) = self.to_config() else {
+            return true;
+        };
+        let mut config = config.clone();
+        edited.apply_arg_options();
+        config.apply_arg_options();
+
+        edited.exe != config.exe
+            || edited.arg != config.arg
+            || edited.env != config.env
+            || edited.shell != config.shell
+            || edited.cwd != config.cwd
+            || edited.arg0 != config.arg0
+            || edited.debugger != config.debugger
+            || edited.trim_args != config.trim_args
+            || edited.inline_env != config.inline_env
+            || edited.launch != config.launch
+            || edited.locale != config.locale
+            || edited.env_unset != config.env_unset
+            || edited.wrapper != config.wrapper
+            || self.merged.is_some()
+    }
+
+    /// Set arguments from shell quoted text, for both editing modes.
+    pub fn set_args(&mut self, args: &str) {
+        self.args = text_editor::Content::with_text(args);
+        match ::shell_words::split(args) {
+            Ok(args_vec) => self.args_vec = args_vec,
+            Err(err) => ::log::warn!("could not split arguments for table\n{err}"),
+        }
+    }
+
+    /// Switch between editing arguments as text and as a table, the
+    /// arguments are carried over to the new mode.
+    ///
+    /// # Errors
+    /// If switching from text and the arguments cannot be split.
+    pub fn set_args_table(&mut self, args_table: bool) -> Result<(), ToConfigError> {
+        if args_table == self.args_table {
+            return Ok(());
+        }
+        if args_table {
+            self.args_vec = ::shell_words::split(&self.args.text())?;
+        } else {
+            self.args = text_editor::Content::with_text(&::shell_words::join(&self.args_vec));
+        }
+        self.args_table = args_table;
+        Ok(())
+    }
+
+    /// Append arguments, quoting them when editing as text.
+    pub fn append_args(&mut self, args: &[String]) {
+        if self.args_table {
+            self.args_vec.extend_from_slice(args);
+        } else {
+            let text = self.args.text();
+            let text = text.trim_end();
+            let joined = ::shell_words::join(args);
+            let text = if text.is_empty() {
+                joined
+            } else {
+                format!("{text} {joined}")
+            };
+            self.args = text_editor::Content::with_text(&text);
+        }
+    }
+
+    /// Replace first placeholder of pick in argument at index by path,
+    /// keeping the rest of the argument text as is.
+    ///
+    /// # Errors
+    /// If arguments are edited as text and cannot be split.
+    pub fn fill_placeholder(
+        &mut self,
+        index: usize,
+        pick: Pick,
+        path: &str,
+    ) -> Result<(), ToConfigError> {
+        if self.args_table {
+            if let Some(arg) = self.args_vec.get_mut(index) {
+                *arg = pick.fill(arg, path);
+            }
+            return Ok(());
+        }
+        let text = self.args.text();
+        let args = ::shell_words::split(&text)?;
+        if let (Some(arg), Some(range)) = (args.get(index), token::tokens(&text).get(index)) {
+            let filled = ::shell_words::quote(&pick.fill(arg, path)).into_owned();
+            let text = format!("{}{filled}{}", &text[..range.start], &text[range.end..]);
+            self.args = text_editor::Content::with_text(&text);
+        }
+        Ok(())
+    }
+
+    /// Show text as a toast.
+    pub fn push_toast(&mut self, text: String, level: Level) {
+        if self.toasts.len() >= MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            text,
+            level,
+            expires_at: Instant::now() + TOAST_DURATION,
+        });
+    }
+
+    /// Append a line of captured output, line should include its line
+    /// terminator if it has one.
+    pub fn push_output(&mut self, line: &[u8]) {
+        self.output.push_line(line, &self.highlighter);
+    }
+
+    /// Byte offset of argument cursor in text of arguments.
+    fn args_cursor(&self, text: &str) -> usize {
+        let (line, column) = self.args.cursor_position();
+        text.split('\n')
+            .take(line)
+            .map(|line| line.len() + 1)
+            .sum::<usize>()
+            + column
+    }
+
+    /// Move argument cursor to byte offset of text, selecting the following
+    /// len bytes.
+    fn move_args_cursor(&mut self, text: &str, offset: usize, len: usize) {
+        self.args.perform(Action::Move(Motion::DocumentStart));
+        for _ in text[..offset].chars() {
+            self.args.perform(Action::Move(Motion::Right));
+        }
+        for _ in text[offset..offset + len].chars() {
+            self.args.perform(Action::Select(Motion::Right));
+        }
+    }
+
+    /// Select the argument token following the cursor, or preceding it if
+    /// not forward, wrapping around at the ends.
+    pub fn select_token(&mut self, forward: bool) {
+        let text = self.args.text();
+        let tokens = token::tokens(&text);
+        let cursor = self.args_cursor(&text);
+        let token = if forward {
+            tokens
+                .iter()
+                .find(|token| token.start >= cursor)
+                .or(tokens.first())
+        } else {
+            tokens
+                .iter()
+                .rev()
+                .find(|token| token.end < cursor)
+                .or(tokens.last())
+        };
+        if let Some(token) = token {
+            self.move_args_cursor(&text, token.start, token.len());
+        }
+    }
+
+    /// Split the argument token at the cursor in two, or if the cursor is
+    /// between tokens join them.
+    pub fn split_join_token(&mut self) {
+        let text = self.args.text();
+        let cursor = self.args_cursor(&text);
+        if let Some(split) = token::split_at(&text, cursor) {
+            self.move_args_cursor(&text, cursor, 0);
+            for c in split.chars() {
+                self.args.perform(Action::Edit(Edit::Insert(c)));
+            }
+        } else if let Some(space) = token::join_at(&text, cursor) {
+            self.move_args_cursor(&text, space.start, space.len());
+            self.args.perform(Action::Edit(Edit::Delete));
+        }
+    }
+
+    /// Swap table argument at index with the one following it.
+    pub fn swap_arg_down(&mut self, index: usize) {
+        if index + 1 < self.args_vec.len() {
+            self.args_vec.swap(index, index + 1);
+        }
     }
 }
 
 /// Error raised when current state cannot be parsed to a config.
 #[derive(Debug, ::thiserror::Error)]
-#[error("could not parse arguments\n{source}")]
-pub struct ToConfigError {
-    /// Argument parse error.
-    #[from]
-    source: ::shell_words::ParseError,
+pub enum ToConfigError {
+    /// Arguments could not be split.
+    #[error("could not parse arguments\n{0}")]
+    Split(#[from] ::shell_words::ParseError),
+    /// Line of environment variables is not an assignment.
+    #[error("environment variable '{0}' is not of the form NAME=VALUE")]
+    Env(String),
 }
 
 impl From<ToConfigError> for Message {
-    fn from(_value: ToConfigError) -> Self {
-        Message::SetStatus("could not parse arguments".into())
+    fn from(value: ToConfigError) -> Self {
+        let status = match value {
+            ToConfigError::Split(_) => "could not parse arguments".into(),
+            ToConfigError::Env(line) => {
+                format!("environment variable '{line}' is not of the form NAME=VALUE")
+            }
+        };
+        Message::SetStatusLevel(status, Level::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of conversion between state and configs.
+
+    use super::*;
+
+    /// Executable, argument values and environment of config.
+    fn parts(config: &Config) -> (&str, Vec<&str>, &[(String, String)]) {
+        (
+            &config.exe,
+            config.arg.iter().map(|arg| arg.value.as_str()).collect(),
+            &config.env,
+        )
+    }
+
+    /// Described and disabled arguments keep their position among plain
+    /// ones, counted in plain arguments before them when those are edited.
+    #[test]
+    fn tagged_args_keep_position() {
+        let config: Config = ::toml::from_str(
+            r#"arg = ["--a", { value = "--b", desc = "Described" }, "--c", { value = "--d", enabled = false }]"#,
+        )
+        .unwrap();
+        let mut state = State::default();
+        state.set_config(&config);
+        assert_eq!(state.to_config().unwrap().arg, config.arg);
+        assert!(!state.is_dirty(&config));
+
+        state.set_args("--a --x --c");
+        let values = state
+            .to_config()
+            .unwrap()
+            .arg
+            .into_iter()
+            .map(|arg| arg.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["--a", "--b", "--x", "--d", "--c"]);
+
+        state.set_args("");
+        let values = state
+            .to_config()
+            .unwrap()
+            .arg
+            .into_iter()
+            .map(|arg| arg.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["--b", "--d"]);
+    }
+
+    /// Leading assignments are only moved to the environment while no
+    /// executable is set, such that expanding a converted config leaves its
+    /// executable, arguments and environment as is.
+    #[test]
+    fn inline_env_round_trip() {
+        let mut state = State {
+            inline_env: true,
+            ..State::default()
+        };
+        state.set_args("A=1 -- prog B=2 x");
+        let config = state.to_config().unwrap();
+        let env = [("A".to_owned(), "1".to_owned())];
+        assert_eq!(parts(&config), ("prog", vec!["B=2", "x"], &env[..]));
+        assert_eq!(parts(&config.expand_paths().unwrap()), parts(&config));
+
+        state.exe = "prog".to_owned();
+        state.set_args("A=1 x");
+        let config = state.to_config().unwrap();
+        assert_eq!(parts(&config), ("prog", vec!["A=1", "x"], &[][..]));
+        assert_eq!(parts(&config.expand_paths().unwrap()), parts(&config));
+    }
+    /// Tagged arguments keep their place among plain arguments when leading
+    /// assignments and the executable are taken from them, or empty ones are
+    /// trimmed.
+    #[test]
+    fn inline_env_tagged_args() {
+        let config: Config = ::toml::from_str(
+            r#"
+            inline_env = true
+            trim_args = true
+            arg = ["A=1", "prog", "--a", { value = "--b", desc = "Described" }, "", "--c", { value = "--d", enabled = false }]
+            "#,
+        )
+        .unwrap();
+        let mut state = State::default();
+        state.set_config(&config);
+        let config = state.to_config().unwrap();
+        let env = [("A".to_owned(), "1".to_owned())];
+        assert_eq!(
+            parts(&config),
+            ("prog", vec!["--a", "--b", "--c", "--d"], &env[..])
+        );
+        assert!(config.arg[1].desc.is_some());
+        assert!(!config.arg[3].enabled);
     }
 }
@@ -0,0 +1,97 @@
+//! Statistics of run durations, and a bar chart of them.
+
+use ::std::fmt::Display;
+
+use ::iced::{
+    Point, Rectangle, Renderer, Size, Theme, mouse,
+    widget::canvas::{Frame, Geometry, Program},
+};
+
+/// Max amount of recent runs shown.
+pub const MAX_RUNS: usize = 50;
+
+/// Summary of run durations, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Amount of runs.
+    pub count: usize,
+    /// Shortest duration.
+    pub min: f64,
+    /// Longest duration.
+    pub max: f64,
+    /// Mean duration.
+    pub avg: f64,
+}
+
+impl Stats {
+    /// Summarize durations, [None] if there are none.
+    pub fn new(durations: &[f64]) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+        let min = durations.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = durations.iter().copied().fold(0.0, f64::max);
+        let avg = durations.iter().sum::<f64>() / durations.len() as f64;
+        Some(Self {
+            count: durations.len(),
+            min,
+            max,
+            avg,
+        })
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        let Self {
+            count,
+            min,
+            max,
+            avg,
+        } = self;
+        write!(
+            f,
+            "{count} runs, min {min:.2}s, max {max:.2}s, avg {avg:.2}s"
+        )
+    }
+}
+
+/// Bar chart of durations, oldest to the left, scaled to the longest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DurationChart {
+    /// Durations in seconds.
+    pub durations: Vec<f64>,
+}
+
+impl<Message> Program<Message> for DurationChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let max = self.durations.iter().copied().fold(0.0, f64::max);
+        if self.durations.is_empty() || max <= 0.0 {
+            return vec![frame.into_geometry()];
+        }
+
+        let color = theme.extended_palette().primary.base.color;
+        let slot = bounds.width / self.durations.len() as f32;
+        let gap = (slot * 0.2).min(4.0);
+        for (index, duration) in self.durations.iter().enumerate() {
+            // Bars are given a minimum height such that every run is visible.
+            let height = ((duration / max) as f32 * bounds.height).max(1.0);
+            frame.fill_rectangle(
+                Point::new(index as f32 * slot, bounds.height - height),
+                Size::new(slot - gap, height),
+                color,
+            );
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,24 @@
+//! Status line severity.
+
+use ::iced::Color;
+use ::serde::{Deserialize, Serialize};
+
+/// Severity of a status line message, used to color it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// Neutral information.
+    #[default]
+    Info,
+    /// Something finished successfully.
+    Success,
+    /// Something was skipped or cancelled.
+    Warning,
+    /// Something failed.
+    Error,
+}
+
+/// Color of warnings, as themes have no such color.
+pub fn warning_color() -> Color {
+    Color::from_rgb8(0xd4, 0xa0, 0x17)
+}
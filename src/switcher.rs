@@ -0,0 +1,58 @@
+//! [Switcher] impl.
+
+use ::std::path::{Path, PathBuf};
+
+use ::fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+
+/// Max amount of matches shown by switcher.
+pub const MAX_MATCHES: usize = 8;
+
+/// Quick switcher fuzzy matching recent configs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Switcher {
+    /// Text matched against paths.
+    pub query: String,
+    /// Index of selected match.
+    pub selected: usize,
+}
+
+impl Switcher {
+    /// Paths matching query, best match first. If query is empty all paths
+    /// match in given order.
+    pub fn matches<'a>(&self, paths: &'a [PathBuf]) -> Vec<&'a Path> {
+        let query = self.query.trim();
+        if query.is_empty() {
+            return paths
+                .iter()
+                .take(MAX_MATCHES)
+                .map(PathBuf::as_path)
+                .collect();
+        }
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let mut matches = paths
+            .iter()
+            .filter_map(|path| {
+                matcher
+                    .fuzzy_match(&path.to_string_lossy(), query)
+                    .map(|score| (score, path.as_path()))
+            })
+            .collect::<Vec<_>>();
+        // Sort is stable, equal scores keep recency order.
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches
+            .into_iter()
+            .take(MAX_MATCHES)
+            .map(|(_, path)| path)
+            .collect()
+    }
+
+    /// Move selection by offset, wrapping around count matches.
+    pub fn move_selection(&mut self, offset: isize, count: usize) {
+        if count == 0 {
+            self.selected = 0;
+            return;
+        }
+        let selected = self.selected.min(count - 1) as isize + offset;
+        self.selected = selected.rem_euclid(count as isize) as usize;
+    }
+}
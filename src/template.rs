@@ -0,0 +1,98 @@
+//! Placeholder substitution in paths and other templated values.
+
+/// Replace `{name}` placeholders in template with values resolved by lookup,
+/// `{{` and `}}` are replaced by literal braces. Placeholders lookup cannot
+/// resolve are kept as is and their names returned alongside the result.
+pub fn render(
+    template: &str,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> (String, Vec<String>) {
+    let mut rendered = String::with_capacity(template.len());
+    let mut unknown = Vec::new();
+    let mut rest = template;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if let Some(after) = rest.strip_prefix("{{") {
+            rendered.push('{');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("}}") {
+            rendered.push('}');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('{')
+            && let Some(end) = after.find('}')
+        {
+            let name = &after[..end];
+            match lookup(name) {
+                Some(value) => rendered.push_str(&value),
+                None => {
+                    rendered.push_str(&rest[..end + 2]);
+                    unknown.push(name.to_owned());
+                }
+            }
+            rest = &after[end + 1..];
+        } else {
+            rendered.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    rendered.push_str(rest);
+
+    (rendered, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of placeholder substitution.
+
+    use super::*;
+
+    /// Lookup of placeholders used by tests.
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "n" => Some("3".to_owned()),
+            "name" => Some("run".to_owned()),
+            _ => None,
+        }
+    }
+
+    /// Known placeholders are replaced by their values.
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            render("out/{name}-{n}.log", lookup),
+            ("out/run-3.log".to_owned(), Vec::new())
+        );
+        assert_eq!(render("", lookup), (String::new(), Vec::new()));
+    }
+
+    /// Doubled braces are literal braces, also around placeholders.
+    #[test]
+    fn escaped_braces() {
+        assert_eq!(
+            render("{{n}} {{{n}}} }}{{", lookup),
+            ("{n} {3} }{".to_owned(), Vec::new())
+        );
+    }
+
+    /// Unknown placeholders are kept as is and their names returned.
+    #[test]
+    fn unknown_placeholders() {
+        assert_eq!(
+            render("{date}/{n}-{}.log", lookup),
+            (
+                "{date}/3-{}.log".to_owned(),
+                vec!["date".to_owned(), String::new()]
+            )
+        );
+    }
+
+    /// Single braces without a match are kept as is.
+    #[test]
+    fn unclosed_braces() {
+        assert_eq!(render("out/{n", lookup), ("out/{n".to_owned(), Vec::new()));
+        assert_eq!(render("a}b{", lookup), ("a}b{".to_owned(), Vec::new()));
+    }
+}
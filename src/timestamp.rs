@@ -0,0 +1,139 @@
+//! [Timestamp] impl.
+
+use ::std::{
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Point in time broken down into UTC calendar date and time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp {
+    /// Year.
+    pub year: i64,
+    /// Month of year, starting at 1.
+    pub month: u32,
+    /// Day of month, starting at 1.
+    pub day: u32,
+    /// Hour of day.
+    pub hour: u32,
+    /// Minute of hour.
+    pub minute: u32,
+    /// Second of minute.
+    pub second: u32,
+}
+
+impl Timestamp {
+    /// Current time.
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+
+    /// Timestamp from seconds since unix epoch.
+    pub const fn from_unix(secs: i64) -> Self {
+        let days = secs.div_euclid(86_400);
+        let time = secs.rem_euclid(86_400);
+
+        // Days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Self {
+            year,
+            month: month as u32,
+            day: day as u32,
+            hour: (time / 3_600) as u32,
+            minute: (time % 3_600 / 60) as u32,
+            second: (time % 60) as u32,
+        }
+    }
+
+    /// Format timestamp using a strftime style format, the supported
+    /// specifiers are `%Y`, `%y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%F`, `%T`
+    /// and `%%`.
+    ///
+    /// # Errors
+    /// If format contains an unsupported or incomplete specifier, the
+    /// specifier is returned.
+    pub fn format(&self, format: &str) -> Result<String, String> {
+        let Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } = self;
+        let mut formatted = String::with_capacity(format.len() * 2);
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                formatted.push(c);
+                continue;
+            }
+            let part = match chars.next() {
+                Some('Y') => format!("{year:04}"),
+                Some('y') => format!("{:02}", year.rem_euclid(100)),
+                Some('m') => format!("{month:02}"),
+                Some('d') => format!("{day:02}"),
+                Some('H') => format!("{hour:02}"),
+                Some('M') => format!("{minute:02}"),
+                Some('S') => format!("{second:02}"),
+                Some('F') => format!("{year:04}-{month:02}-{day:02}"),
+                Some('T') => format!("{hour:02}:{minute:02}:{second:02}"),
+                Some('%') => "%".to_owned(),
+                Some(c) => return Err(format!("%{c}")),
+                None => return Err("%".to_owned()),
+            };
+            formatted.push_str(&part);
+        }
+        Ok(formatted)
+    }
+
+    /// Timestamp formatted for use in file names, e.g. `20250601T123000Z`.
+    pub fn compact(&self) -> String {
+        let Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } = self;
+        format!("{year:04}{month:02}{day:02}T{hour:02}{minute:02}{second:02}Z")
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(value: SystemTime) -> Self {
+        let secs = match value.duration_since(UNIX_EPOCH) {
+            Ok(duration) => i64::try_from(duration.as_secs()).unwrap_or(i64::MAX),
+            Err(err) => i64::try_from(err.duration().as_secs()).map_or(i64::MIN, |secs| -secs),
+        };
+        Self::from_unix(secs)
+    }
+}
+
+impl Display for Timestamp {
+    /// Formats timestamp as RFC 3339, e.g. `2025-06-01T12:30:00Z`.
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        let Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } = self;
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z"
+        )
+    }
+}
@@ -0,0 +1,381 @@
+//! Positions of shell quoted tokens in text, split according to the same
+//! rules as [shell_words::split].
+
+use ::std::ops::Range;
+
+use ::iced::{
+    Font, Theme,
+    advanced::text::highlighter::{Format, Highlighter},
+};
+
+/// Class of a character of shell quoted text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    /// Whitespace separating tokens.
+    Space,
+    /// Part of a comment.
+    Comment,
+    /// Part of token with index.
+    Token(usize),
+}
+
+/// State of splitting text into tokens, advanced one character at a time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Scanner {
+    /// Quote character of the quoted section currently in, if any.
+    quote: Option<char>,
+    /// Previous character was an escaping backslash.
+    escaped: bool,
+    /// Currently in a comment.
+    comment: bool,
+    /// Currently in a token.
+    in_token: bool,
+    /// Amount of tokens started.
+    count: usize,
+}
+
+impl Scanner {
+    /// Advance scanner by c, returning the class of c.
+    pub const fn step(&mut self, c: char) -> Class {
+        if self.comment {
+            if c == '\n' {
+                self.comment = false;
+                return Class::Space;
+            }
+            return Class::Comment;
+        }
+
+        if self.escaped {
+            self.escaped = false;
+            return self.token();
+        }
+
+        match (self.quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => self.quote = None,
+            (Some('"'), '\\') => self.escaped = true,
+            (Some(_), _) => {}
+            (None, '\\') => self.escaped = true,
+            (None, '\'' | '"') => self.quote = Some(c),
+            (None, '#') if !self.in_token => {
+                self.comment = true;
+                return Class::Comment;
+            }
+            (None, c) if c.is_whitespace() => {
+                self.in_token = false;
+                return Class::Space;
+            }
+            (None, _) => {}
+        }
+        self.token()
+    }
+
+    /// Class of a token character, starting a new token if needed.
+    const fn token(&mut self) -> Class {
+        if !self.in_token {
+            self.in_token = true;
+            self.count += 1;
+        }
+        Class::Token(self.count - 1)
+    }
+
+    /// Scanner state after advancing through all of text.
+    fn after(text: &str) -> Self {
+        let mut scanner = Self::default();
+        for c in text.chars() {
+            scanner.step(c);
+        }
+        scanner
+    }
+}
+
+/// Byte ranges of tokens in text.
+pub fn tokens(text: &str) -> Vec<Range<usize>> {
+    let mut scanner = Scanner::default();
+    let mut tokens = Vec::<Range<usize>>::new();
+    for (index, c) in text.char_indices() {
+        if let Class::Token(token) = scanner.step(c) {
+            let end = index + c.len_utf8();
+            match tokens.get_mut(token) {
+                Some(range) => range.end = end,
+                None => tokens.push(index..end),
+            }
+        }
+    }
+    tokens
+}
+
+/// Text to insert at offset to split the token containing it in two, [None]
+/// if offset is not inside a token or would split an escape sequence.
+pub fn split_at(text: &str, offset: usize) -> Option<String> {
+    if !tokens(text)
+        .iter()
+        .any(|token| token.start < offset && offset < token.end)
+    {
+        return None;
+    }
+    let scanner = Scanner::after(text.get(..offset)?);
+    if scanner.escaped {
+        return None;
+    }
+    Some(match scanner.quote {
+        Some(quote) => format!("{quote} {quote}"),
+        None => " ".to_owned(),
+    })
+}
+
+/// Whitespace between the tokens surrounding offset, removing which joins
+/// them, [None] if offset is not between two tokens separated only by
+/// whitespace.
+pub fn join_at(text: &str, offset: usize) -> Option<Range<usize>> {
+    let tokens = tokens(text);
+    let before = tokens.iter().rev().find(|token| token.end <= offset)?;
+    let after = tokens.iter().find(|token| token.start >= offset)?;
+    text[before.end..after.start]
+        .chars()
+        .all(char::is_whitespace)
+        .then_some(before.end..after.start)
+}
+
+/// Highlight of a part of shell quoted text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// Token with an even index.
+    Even,
+    /// Token with an odd index.
+    Odd,
+    /// Comment.
+    Comment,
+}
+
+impl Highlight {
+    /// Format of highlight using theme.
+    pub fn to_format(&self, theme: &Theme) -> Format<Font> {
+        let palette = theme.extended_palette();
+        let color = match self {
+            Highlight::Even => None,
+            Highlight::Odd => Some(palette.primary.strong.color),
+            Highlight::Comment => Some(palette.background.strong.color),
+        };
+        Format { color, font: None }
+    }
+}
+
+/// Highlighter alternating highlight of tokens, such that token boundaries
+/// are visible.
+#[derive(Debug, Clone)]
+pub struct TokenHighlighter {
+    /// Scanner state at start of each highlighted line, and of the line
+    /// following them.
+    starts: Vec<Scanner>,
+}
+
+impl Highlighter for TokenHighlighter {
+    type Settings = ();
+    type Highlight = Highlight;
+    type Iterator<'a> = ::std::vec::IntoIter<(Range<usize>, Highlight)>;
+
+    fn new(_settings: &Self::Settings) -> Self {
+        Self {
+            starts: vec![Scanner::default()],
+        }
+    }
+
+    fn update(&mut self, _new_settings: &Self::Settings) {
+        self.change_line(0);
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.starts.truncate(line + 1);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        let mut scanner = self.starts.last().copied().unwrap_or_default();
+        let mut highlights = Vec::<(Range<usize>, Highlight)>::new();
+        for (index, c) in line.char_indices() {
+            let highlight = match scanner.step(c) {
+                Class::Space => continue,
+                Class::Comment => Highlight::Comment,
+                Class::Token(token) if token % 2 == 0 => Highlight::Even,
+                Class::Token(_) => Highlight::Odd,
+            };
+            let end = index + c.len_utf8();
+            match highlights.last_mut() {
+                Some((range, last)) if range.end == index && *last == highlight => {
+                    range.end = end;
+                }
+                _ => highlights.push((index..end, highlight)),
+            }
+        }
+        scanner.step('\n');
+        self.starts.push(scanner);
+        highlights.into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.starts.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests of token positions, checked against [shell_words::split].
+
+    use super::*;
+
+    /// Shell quoted texts covering quotes, escapes, comments and multi-byte
+    /// characters.
+    const TEXTS: &[&str] = &[
+        "a b  c",
+        "'a b' \"c d\"",
+        r#""a \" b" 'c \ d' e\ f"#,
+        "a # comment\nb",
+        "a#b 'c # d'",
+        "ä 'ö ü' €x\t\"ß\"",
+    ];
+
+    /// Class of each character of text.
+    fn classes(text: &str) -> Vec<Class> {
+        let mut scanner = Scanner::default();
+        text.chars().map(|c| scanner.step(c)).collect()
+    }
+
+    /// Text with insert inserted at offset.
+    fn inserted(text: &str, offset: usize, insert: &str) -> String {
+        format!("{}{insert}{}", &text[..offset], &text[offset..])
+    }
+
+    /// Characters are classed by quotes, escapes and comments.
+    #[test]
+    fn step() {
+        use Class::{Comment, Space, Token};
+
+        assert_eq!(
+            classes("a 'b c'"),
+            [
+                Token(0),
+                Space,
+                Token(1),
+                Token(1),
+                Token(1),
+                Token(1),
+                Token(1)
+            ]
+        );
+        assert_eq!(
+            classes(r#""\" "\ b"#),
+            [
+                Token(0),
+                Token(0),
+                Token(0),
+                Token(0),
+                Token(0),
+                Token(0),
+                Token(0),
+                Token(0)
+            ]
+        );
+        assert_eq!(
+            classes("#x\ny #"),
+            [Comment, Comment, Space, Token(0), Space, Comment]
+        );
+        assert_eq!(classes("a#"), [Token(0), Token(0)]);
+        assert_eq!(classes("ä ö"), [Token(0), Space, Token(1)]);
+    }
+
+    /// Tokens are the same words as split by [shell_words::split].
+    #[test]
+    fn tokens_match_split() {
+        for text in TEXTS {
+            let words = ::shell_words::split(text).unwrap();
+            let tokens = tokens(text);
+            assert_eq!(tokens.len(), words.len(), "{text:?}");
+            for (token, word) in tokens.into_iter().zip(words) {
+                assert_eq!(
+                    ::shell_words::split(&text[token]).unwrap(),
+                    [word],
+                    "{text:?}"
+                );
+            }
+        }
+    }
+
+    /// Splitting inside quotes closes and reopens them around the space.
+    #[test]
+    fn split() {
+        for (text, offset, words) in [
+            ("abc", 1, &["a", "bc"][..]),
+            ("'a b'", 2, &["a", " b"]),
+            ("\"a b\"", 3, &["a ", "b"]),
+            ("äö", 2, &["ä", "ö"]),
+        ] {
+            let insert = split_at(text, offset).unwrap();
+            assert_eq!(
+                ::shell_words::split(&inserted(text, offset, &insert)).unwrap(),
+                words,
+                "{text:?} at {offset}"
+            );
+        }
+        assert_eq!(split_at("'a b'", 2), Some("' '".to_owned()));
+    }
+
+    /// Offsets outside tokens or inside escapes cannot be split at.
+    #[test]
+    fn split_outside() {
+        assert_eq!(split_at("ab cd", 0), None);
+        assert_eq!(split_at("ab cd", 2), None);
+        assert_eq!(split_at("ab cd", 5), None);
+        assert_eq!(split_at(r#""a\"b""#, 3), None);
+        assert_eq!(split_at(r"a\ b", 2), None);
+        assert_eq!(split_at("a # bc", 5), None);
+    }
+
+    /// Joining removes whitespace between tokens, making them one word.
+    #[test]
+    fn join() {
+        for (text, offset, range, word) in [
+            ("a  b", 2, 1..3, "ab"),
+            ("'a' \"b\"", 3, 3..4, "ab"),
+            ("ä\tö", 2, 2..3, "äö"),
+        ] {
+            assert_eq!(join_at(text, offset), Some(range.clone()), "{text:?}");
+            let joined = format!("{}{}", &text[..range.start], &text[range.end..]);
+            assert_eq!(::shell_words::split(&joined).unwrap(), [word], "{text:?}");
+        }
+    }
+
+    /// Tokens separated by more than whitespace, or offsets not between
+    /// tokens, cannot be joined.
+    #[test]
+    fn join_outside() {
+        assert_eq!(join_at("ab", 1), None);
+        assert_eq!(join_at("a b", 0), None);
+        assert_eq!(join_at("a b", 3), None);
+        assert_eq!(join_at("a # c\nb", 1), None);
+    }
+
+    /// Tokens alternate highlight, also across lines within quotes.
+    #[test]
+    fn highlighter() {
+        let mut highlighter = TokenHighlighter::new(&());
+        assert_eq!(
+            highlighter.highlight_line("ä 'b").collect::<Vec<_>>(),
+            [(0..2, Highlight::Even), (3..5, Highlight::Odd)]
+        );
+        assert_eq!(
+            highlighter.highlight_line("c' d # x").collect::<Vec<_>>(),
+            [
+                (0..2, Highlight::Odd),
+                (3..4, Highlight::Even),
+                (5..8, Highlight::Comment)
+            ]
+        );
+        assert_eq!(highlighter.current_line(), 2);
+
+        highlighter.change_line(1);
+        assert_eq!(
+            highlighter.highlight_line("c d").collect::<Vec<_>>(),
+            [(0..3, Highlight::Odd)]
+        );
+        assert_eq!(highlighter.current_line(), 2);
+    }
+}
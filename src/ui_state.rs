@@ -0,0 +1,177 @@
+//! [UiState] impl.
+
+use ::std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use ::serde::{Deserialize, Serialize};
+
+use crate::{
+    Theme,
+    action::Action,
+    config::{LoadError, SaveError},
+    paste::PasteMode,
+    paths,
+    timestamp::Timestamp,
+};
+
+/// Max amount of argument strings remembered per executable.
+const ARG_HISTORY_LEN: usize = 16;
+
+/// Max amount of recent configs remembered.
+const RECENT_CONFIGS_LEN: usize = 32;
+
+/// Ui state persisted between sessions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// Recently used argument strings keyed by executable, most recent first.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub arg_history: BTreeMap<String, Vec<String>>,
+    /// Theme selected in the ui.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+    /// Write command line and timestamp to output before each run.
+    pub echo_command: bool,
+    /// Edit argument text token by token, Tab and Shift+Tab select the
+    /// next and previous token and Enter splits or joins tokens.
+    pub token_editor: bool,
+    /// Save output as the raw bytes written by processes instead of as
+    /// lossily decoded text.
+    pub save_raw_output: bool,
+    /// Strip ANSI escape sequences from output when copying or saving it as
+    /// text.
+    pub strip_ansi: bool,
+    /// Color lines of output according to highlight rules of config.
+    pub highlight_output: bool,
+    /// Max amount of bytes of captured output kept in memory, older lines
+    /// are dropped when exceeded. If not set
+    /// [DEFAULT_MAX_OUTPUT_BYTES][crate::output::DEFAULT_MAX_OUTPUT_BYTES]
+    /// is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+    /// Path of last loaded config.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_config: Option<PathBuf>,
+    /// Recently loaded configs, most recent first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recent_configs: Vec<PathBuf>,
+    /// Load last loaded config on startup when no config is given.
+    pub reopen_last: bool,
+    /// Last selected profile keyed by config path.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub active_profile: BTreeMap<PathBuf, String>,
+    /// Kill running processes, including background ones, when exiting.
+    pub kill_on_exit: bool,
+    /// How arguments pasted from the clipboard are split.
+    pub paste_mode: PasteMode,
+    /// Pipe stdin of runs such that input can be typed to them, otherwise
+    /// they read no input.
+    pub interactive_input: bool,
+    /// Max amount of background processes running at once, further runs
+    /// are queued. Unlimited if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+    /// Run when Enter is pressed in the executable input, instead of
+    /// checking that the executable can be found.
+    pub run_on_submit: bool,
+    /// Actions shown in the bottom button row in order, the rest are placed
+    /// in an overflow row. If not set the default actions are shown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<Action>>,
+    /// Run statistics keyed by config path.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub run_stats: BTreeMap<PathBuf, RunStats>,
+    /// Directory listed by the config browser.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser_dir: Option<PathBuf>,
+}
+
+/// Usage statistics of a config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    /// Amount of successful runs.
+    pub run_count: u64,
+    /// Time of last successful run as an RFC 3339 timestamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<String>,
+}
+
+impl UiState {
+    /// Path ui state is stored at, if any.
+    pub fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join("ui_state.toml"))
+    }
+
+    /// Load ui state, a missing file results in default state.
+    ///
+    /// # Errors
+    /// If state exists but cannot be read [LoadError::Read] is returned.
+    /// If state cannot be deserialized [LoadError::Deserialize] is returned.
+    pub async fn load() -> Result<Self, LoadError> {
+        paths::load(Self::path()).await
+    }
+
+    /// Save ui state.
+    ///
+    /// # Errors
+    /// If state cannot be serialized [SaveError::Serialize] is returned.
+    /// If state cannot be written [SaveError::Write] is returned.
+    pub async fn save(self) -> Result<(), SaveError> {
+        paths::save(&self, Self::path()).await
+    }
+
+    /// Argument history of exe, most recent first.
+    pub fn arg_history(&self, exe: &str) -> &[String] {
+        self.arg_history.get(exe).map_or(&[], Vec::as_slice)
+    }
+
+    /// Actions shown in the bottom button row, in order.
+    pub fn actions(&self) -> &[Action] {
+        self.actions.as_deref().unwrap_or(&Action::DEFAULT)
+    }
+
+    /// Show or hide action in the bottom button row, shown actions are
+    /// placed last.
+    pub fn set_action_shown(&mut self, action: Action, shown: bool) {
+        let actions = self.actions.get_or_insert_with(|| Action::DEFAULT.to_vec());
+        actions.retain(|shown| *shown != action);
+        if shown {
+            actions.push(action);
+        }
+    }
+
+    /// Run statistics of config at path, if it has been ran.
+    pub fn run_stats(&self, path: &Path) -> Option<&RunStats> {
+        self.run_stats.get(path)
+    }
+
+    /// Record a successful run of config at path.
+    pub fn record_run(&mut self, path: &Path) {
+        let stats = self.run_stats.entry(path.to_path_buf()).or_default();
+        stats.run_count += 1;
+        stats.last_run = Some(Timestamp::now().to_string());
+    }
+
+    /// Remember config at path as most recently loaded, returns false if it
+    /// already was.
+    pub fn push_recent_config(&mut self, path: &Path) -> bool {
+        if self.recent_configs.first().map(PathBuf::as_path) == Some(path) {
+            return false;
+        }
+        self.recent_configs.retain(|recent| recent != path);
+        self.recent_configs.insert(0, path.to_path_buf());
+        self.recent_configs.truncate(RECENT_CONFIGS_LEN);
+        true
+    }
+
+    /// Remember args as most recently used with exe.
+    pub fn push_arg_history(&mut self, exe: &str, args: String) {
+        let history = self.arg_history.entry(exe.to_owned()).or_default();
+        history.retain(|entry| *entry != args);
+        history.insert(0, args);
+        history.truncate(ARG_HISTORY_LEN);
+    }
+}
@@ -0,0 +1,146 @@
+//! Resource usage of finished processes.
+
+use ::std::{fmt::Display, time::Duration};
+
+/// Resource usage of a child process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    /// Time spent in user mode.
+    pub user: Duration,
+    /// Time spent in kernel mode.
+    pub system: Duration,
+    /// Largest resident set size, in bytes.
+    pub max_rss: u64,
+}
+
+impl Usage {
+    /// Usage as reported by `wait4` for a reaped child.
+    #[cfg(unix)]
+    pub fn from_rusage(usage: &::libc::rusage) -> Self {
+        /// Convert time value to duration, negative values are clamped.
+        fn duration(time: ::libc::timeval) -> Duration {
+            Duration::from_secs(u64::try_from(time.tv_sec).unwrap_or_default())
+                + Duration::from_micros(u64::try_from(time.tv_usec).unwrap_or_default())
+        }
+
+        let max_rss = u64::try_from(usage.ru_maxrss).unwrap_or_default();
+        Self {
+            user: duration(usage.ru_utime),
+            system: duration(usage.ru_stime),
+            // Linux and the BSDs report kilobytes, macOS reports bytes.
+            max_rss: if cfg!(target_os = "macos") {
+                max_rss
+            } else {
+                max_rss * 1024
+            },
+        }
+    }
+
+    /// Usage of an exited process with handle, with processor times read
+    /// from job object with handle if given, such that they include
+    /// processes started by it. The largest resident set size is the peak
+    /// working set of the process.
+    ///
+    /// # Errors
+    /// If the times or memory counters cannot be read.
+    #[cfg(windows)]
+    pub fn from_process(
+        process: ::windows_sys::Win32::Foundation::HANDLE,
+        job: Option<::windows_sys::Win32::Foundation::HANDLE>,
+    ) -> ::std::io::Result<Self> {
+        use ::windows_sys::Win32::{
+            Foundation::FILETIME,
+            System::{
+                JobObjects::{
+                    JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, JobObjectBasicAccountingInformation,
+                    QueryInformationJobObject,
+                },
+                ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+                Threading::GetProcessTimes,
+            },
+        };
+
+        /// Convert time in 100 nanosecond intervals to duration, negative
+        /// values are clamped.
+        fn duration(intervals: i64) -> Duration {
+            Duration::from_nanos(
+                u64::try_from(intervals)
+                    .unwrap_or_default()
+                    .saturating_mul(100),
+            )
+        }
+
+        /// Time in 100 nanosecond intervals of file time.
+        fn intervals(time: FILETIME) -> i64 {
+            (i64::from(time.dwHighDateTime) << 32) | i64::from(time.dwLowDateTime)
+        }
+
+        let (user, system) = if let Some(job) = job {
+            // SAFETY: Accounting information is plain data, for which all
+            // zeroes is a valid value.
+            let mut info =
+                unsafe { ::std::mem::zeroed::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() };
+            let size = u32::try_from(size_of_val(&info)).map_err(::std::io::Error::other)?;
+            // SAFETY: info is valid for writes of size for the whole call.
+            if unsafe {
+                QueryInformationJobObject(
+                    job,
+                    JobObjectBasicAccountingInformation,
+                    (&raw mut info).cast(),
+                    size,
+                    ::std::ptr::null_mut(),
+                )
+            } == 0
+            {
+                return Err(::std::io::Error::last_os_error());
+            }
+            (info.TotalUserTime, info.TotalKernelTime)
+        } else {
+            let zero = FILETIME {
+                dwLowDateTime: 0,
+                dwHighDateTime: 0,
+            };
+            let [mut creation, mut exit, mut kernel, mut user] = [zero; 4];
+            // SAFETY: All times are valid for writes for the whole call.
+            if unsafe { GetProcessTimes(process, &mut creation, &mut exit, &mut kernel, &mut user) }
+                == 0
+            {
+                return Err(::std::io::Error::last_os_error());
+            }
+            (intervals(user), intervals(kernel))
+        };
+
+        // SAFETY: Memory counters are plain data, for which all zeroes is a
+        // valid value.
+        let mut counters = unsafe { ::std::mem::zeroed::<PROCESS_MEMORY_COUNTERS>() };
+        let size = u32::try_from(size_of_val(&counters)).map_err(::std::io::Error::other)?;
+        counters.cb = size;
+        // SAFETY: counters is valid for writes of size for the whole call.
+        if unsafe { GetProcessMemoryInfo(process, &raw mut counters, size) } == 0 {
+            return Err(::std::io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            user: duration(user),
+            system: duration(system),
+            max_rss: u64::try_from(counters.PeakWorkingSetSize).unwrap_or_default(),
+        })
+    }
+}
+
+impl Display for Usage {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        let Self {
+            user,
+            system,
+            max_rss,
+        } = self;
+        write!(
+            f,
+            "user {user:.2}s, system {system:.2}s, max rss {rss:.1} MiB",
+            user = user.as_secs_f64(),
+            system = system.as_secs_f64(),
+            rss = *max_rss as f64 / (1024.0 * 1024.0),
+        )
+    }
+}